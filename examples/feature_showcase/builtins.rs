@@ -7,6 +7,13 @@ pub async fn servers(ctx: Context<'_>) -> Result<(), Error> {
     Ok(())
 }
 
+#[cfg(feature = "cache")]
+#[poise::command(slash_command, prefix_command)]
+pub async fn stats(ctx: Context<'_>) -> Result<(), Error> {
+    poise::builtins::stats(ctx).await?;
+    Ok(())
+}
+
 #[poise::command(slash_command, prefix_command)]
 pub async fn help(ctx: Context<'_>, command: Option<String>) -> Result<(), Error> {
     let configuration = poise::builtins::HelpConfiguration {
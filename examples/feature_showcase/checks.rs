@@ -144,3 +144,23 @@ pub async fn permissions_v2(ctx: Context<'_>) -> Result<(), Error> {
 
     Ok(())
 }
+
+fn validate_range(&(min, max): &(i32, i32)) -> Result<(), String> {
+    if max < min {
+        return Err(format!("max ({max}) must not be smaller than min ({min})"));
+    }
+    Ok(())
+}
+
+/// Counts the numbers in the given range, which is validated before the command runs
+#[poise::command(prefix_command, slash_command, validate = "validate_range")]
+pub async fn range(
+    ctx: Context<'_>,
+    #[description = "Lower bound"] min: i32,
+    #[description = "Upper bound"] max: i32,
+) -> Result<(), Error> {
+    let count = max as i64 - min as i64 + 1;
+    ctx.say(format!("{min}..={max} contains {count} numbers"))
+        .await?;
+    Ok(())
+}
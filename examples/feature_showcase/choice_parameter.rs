@@ -15,7 +15,10 @@ pub enum MyStringChoice {
 #[poise::command(prefix_command, slash_command)]
 pub async fn choice(
     ctx: Context<'_>,
-    #[description = "The choice you want to choose"] choice: MyStringChoice,
+    // In prefix commands, #[fuzzy] also accepts abbreviations like `"the f"`
+    #[description = "The choice you want to choose"]
+    #[fuzzy]
+    choice: MyStringChoice,
 ) -> Result<(), Error> {
     ctx.say(format!("You entered {:?}", choice)).await?;
     Ok(())
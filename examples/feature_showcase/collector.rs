@@ -30,15 +30,9 @@ pub async fn boop(ctx: Context<'_>) -> Result<(), Error> {
     {
         boop_count += 1;
 
-        let mut msg = mci.message.clone();
-        msg.edit(
-            ctx,
-            serenity::EditMessage::new().content(format!("Boop count: {boop_count}")),
-        )
-        .await?;
-
-        mci.create_response(ctx, serenity::CreateInteractionResponse::Acknowledge)
-            .await?;
+        // Edits the message with the button in place
+        let reply = CreateReply::default().content(format!("Boop count: {boop_count}"));
+        ctx.update_response(&mci, reply).await?;
     }
 
     Ok(())
@@ -37,6 +37,8 @@ async fn main() {
                 bool_parameter::oracle(),
                 #[cfg(feature = "cache")]
                 builtins::servers(),
+                #[cfg(feature = "cache")]
+                builtins::stats(),
                 builtins::help(),
                 checks::shutdown(),
                 checks::modonly(),
@@ -48,6 +50,7 @@ async fn main() {
                 checks::only_in_dms(),
                 checks::lennyface(),
                 checks::permissions_v2(),
+                checks::range(),
                 choice_parameter::choice(),
                 code_block_parameter::code(),
                 collector::boop(),
@@ -62,6 +65,10 @@ async fn main() {
                 parameter_attributes::addmultiple(),
                 parameter_attributes::voiceinfo(),
                 parameter_attributes::say(),
+                parameter_attributes::poll(),
+                parameter_attributes::remind(),
+                parameter_attributes::schedule(),
+                parameter_attributes::warn(),
                 parameter_attributes::punish(),
                 parameter_attributes::stringlen(),
                 raw_identifiers::r#move(),
@@ -60,6 +60,114 @@ pub async fn say(
     Ok(())
 }
 
+/// Starts a poll with comma-separated options
+///
+/// Demonstrates `delimiter` and `examples`
+#[poise::command(
+    prefix_command,
+    delimiter = ',',
+    examples("poll Best color?, dark red, blue")
+)]
+pub async fn poll(ctx: Context<'_>, question: String, options: Vec<String>) -> Result<(), Error> {
+    let options = options
+        .iter()
+        .enumerate()
+        .map(|(i, option)| format!("{}. {}", i + 1, option))
+        .collect::<Vec<_>>()
+        .join("\n");
+    ctx.say(format!("**{}**\n{}", question, options)).await?;
+    Ok(())
+}
+
+/// A reminder in the form of `in <hours>h to <task>`
+#[derive(Debug)]
+pub struct Reminder {
+    hours: u32,
+    task: String,
+}
+
+#[derive(Debug)]
+pub struct ReminderParseError;
+
+impl std::fmt::Display for ReminderParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expected `in <hours>h to <task>`")
+    }
+}
+
+impl std::error::Error for ReminderParseError {}
+
+impl std::str::FromStr for Reminder {
+    type Err = ReminderParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (hours, task) = s
+            .strip_prefix("in ")
+            .and_then(|s| s.split_once("h to "))
+            .ok_or(ReminderParseError)?;
+        Ok(Self {
+            hours: hours.trim().parse().map_err(|_| ReminderParseError)?,
+            task: task.to_owned(),
+        })
+    }
+}
+
+/// Pretends to set a reminder, e.g. `~remind in 3h to do the thing`
+///
+/// Demonstrates `#[rest]` with a custom type which parses the whole argument string by itself
+#[poise::command(prefix_command, slash_command)]
+pub async fn remind(
+    ctx: Context<'_>,
+    #[rest]
+    #[description = "When and what, e.g. `in 3h to do the thing`"]
+    reminder: Reminder,
+) -> Result<(), Error> {
+    let response = format!(
+        "I would remind you in {} hours to {}",
+        reminder.hours, reminder.task
+    );
+    ctx.say(response).await?;
+    Ok(())
+}
+
+/// Pretends to schedule a message, e.g. `~schedule 2h30m` or `~schedule "in 1 day"`
+///
+/// Demonstrates the built-in duration and timestamp parameter types
+#[poise::command(prefix_command, slash_command)]
+pub async fn schedule(
+    ctx: Context<'_>,
+    #[description = "When to send the message, e.g. `2h30m` or `<t:1706702400>`"]
+    time: poise::HumanTimestamp,
+    #[description = "How long to keep the message around, e.g. `1d`"] lifetime: Option<
+        poise::HumanDuration,
+    >,
+) -> Result<(), Error> {
+    let mut response = format!("I would send a message <t:{}:R>", time.0.unix_timestamp());
+    if let Some(lifetime) = lifetime {
+        response += &format!(" and delete it after {}", lifetime);
+    }
+    ctx.say(response).await?;
+    Ok(())
+}
+
+/// Pretends to warn a user, e.g. `~warn @user spamming in general silent`
+///
+/// Demonstrates free text followed by flags
+#[poise::command(prefix_command)]
+pub async fn warn(
+    ctx: Context<'_>,
+    user: serenity::User,
+    #[until_flag] reason: String,
+    #[flag] silent: bool,
+) -> Result<(), Error> {
+    let mut response = format!("I would warn {} for: {}", user.name, reason);
+    if silent {
+        response += " (without notifying them)";
+    }
+    ctx.say(response).await?;
+    Ok(())
+}
+
 #[derive(Debug, poise::ChoiceParameter)]
 pub enum PunishType {
     Ban,
@@ -17,6 +17,7 @@ async fn ping(ctx: poise::Context<'_, (), Error>) -> Result<(), Error> {
 struct Handler {
     options: poise::FrameworkOptions<(), Error>,
     shard_manager: std::sync::Mutex<Option<std::sync::Arc<serenity::ShardManager>>>,
+    started_at: std::time::Instant,
 }
 #[serenity::async_trait]
 impl serenity::EventHandler for Handler {
@@ -28,6 +29,7 @@ impl serenity::EventHandler for Handler {
             options: &self.options,
             user_data: &(),
             shard_manager: &shard_manager,
+            started_at: self.started_at,
         };
 
         let event = serenity::FullEvent::Message { new_message };
@@ -47,6 +49,7 @@ async fn main() -> Result<(), Error> {
             ..Default::default()
         },
         shard_manager: std::sync::Mutex::new(None),
+        started_at: std::time::Instant::now(),
     };
     poise::set_qualified_names(&mut handler.options.commands); // some setup
 
@@ -18,6 +18,7 @@ pub struct CommandArgs {
     //  if it's actually irrational, the inconsistency should be fixed)
     subcommands: crate::util::List<syn::Path>,
     aliases: crate::util::List<String>,
+    examples: crate::util::List<String>,
     subcommand_required: bool,
     invoke_on_edit: bool,
     reuse_response: bool,
@@ -27,18 +28,25 @@ pub struct CommandArgs {
     help_text_fn: Option<syn::Path>,
     #[darling(multiple)]
     check: Vec<syn::Path>,
+    validate: Option<syn::Path>,
     on_error: Option<syn::Path>,
+    available: Option<syn::Path>,
+    registration_guilds: crate::util::List<u64>,
     rename: Option<String>,
     #[darling(multiple)]
     name_localized: Vec<crate::util::Tuple2<String>>,
     #[darling(multiple)]
     description_localized: Vec<crate::util::Tuple2<String>>,
     discard_spare_arguments: bool,
+    delimiter: Option<char>,
     hide_in_help: bool,
     ephemeral: bool,
+    manual_response: bool,
+    no_defer: bool,
     default_member_permissions: Option<syn::punctuated::Punctuated<syn::Ident, syn::Token![|]>>,
     required_permissions: Option<syn::punctuated::Punctuated<syn::Ident, syn::Token![|]>>,
     required_bot_permissions: Option<syn::punctuated::Punctuated<syn::Ident, syn::Token![|]>>,
+    required_intents: Option<syn::punctuated::Punctuated<syn::Ident, syn::Token![|]>>,
     owners_only: bool,
     guild_only: bool,
     dm_only: bool,
@@ -53,6 +61,7 @@ pub struct CommandArgs {
     guild_cooldown: Option<u64>,
     channel_cooldown: Option<u64>,
     member_cooldown: Option<u64>,
+    timeout: Option<u64>,
 }
 
 /// Representation of the function parameter attribute arguments
@@ -75,6 +84,9 @@ struct ParamArgs {
     lazy: bool,
     flag: bool,
     rest: bool,
+    until_flag: bool,
+    fuzzy: bool,
+    redact: bool,
 }
 
 /// Part of the Invocation struct. Represents a single parameter of a Discord command.
@@ -94,9 +106,30 @@ pub struct Invocation {
     default_member_permissions: syn::Expr,
     required_permissions: syn::Expr,
     required_bot_permissions: syn::Expr,
+    required_intents: syn::Expr,
     args: CommandArgs,
 }
 
+/// Generates the code which passes the parsed arguments to the `validate` function, if any, and
+/// returns an argument parse error if validation fails
+fn generate_argument_validation(
+    inv: &Invocation,
+    param_idents: &[syn::Ident],
+) -> Option<proc_macro2::TokenStream> {
+    let validate = inv.args.validate.as_ref()?;
+    Some(quote::quote! {
+        let args = ( #( #param_idents, )* );
+        if let Err(error) = #validate(&args) {
+            return Err(poise::FrameworkError::new_argument_parse(
+                ctx.into(),
+                None,
+                String::from(error).into(),
+            ));
+        }
+        let ( #( #param_idents, )* ) = args;
+    })
+}
+
 fn extract_help_from_doc_comments(attrs: &[syn::Attribute]) -> (Option<String>, Option<String>) {
     let mut doc_lines = String::new();
     for attr in attrs {
@@ -212,6 +245,13 @@ pub fn command(
     let default_member_permissions = permissions_to_tokens(&args.default_member_permissions);
     let required_permissions = permissions_to_tokens(&args.required_permissions);
     let required_bot_permissions = permissions_to_tokens(&args.required_bot_permissions);
+    let required_intents = match &args.required_intents {
+        Some(intents) => {
+            let intents = intents.iter();
+            syn::parse_quote! { #(poise::serenity_prelude::GatewayIntents::#intents)|* }
+        }
+        None => syn::parse_quote! { poise::serenity_prelude::GatewayIntents::empty() },
+    };
 
     let inv = Invocation {
         parameters,
@@ -222,6 +262,7 @@ pub fn command(
         default_member_permissions,
         required_permissions,
         required_bot_permissions,
+        required_intents,
     };
 
     Ok(TokenStream::from(generate_command(inv)?))
@@ -277,17 +318,22 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
         None => quote::quote! { None },
     };
     let hide_in_help = &inv.args.hide_in_help;
-    let category = wrap_option_to_string(inv.args.category.as_ref());
+    let category = match &inv.args.category {
+        Some(x) => quote::quote! { Some(::std::borrow::Cow::Borrowed(#x)) },
+        None => quote::quote! { None },
+    };
 
     let global_cooldown = wrap_option(inv.args.global_cooldown);
     let user_cooldown = wrap_option(inv.args.user_cooldown);
     let guild_cooldown = wrap_option(inv.args.guild_cooldown);
     let channel_cooldown = wrap_option(inv.args.channel_cooldown);
     let member_cooldown = wrap_option(inv.args.member_cooldown);
+    let timeout = wrap_option(inv.args.timeout);
 
     let default_member_permissions = &inv.default_member_permissions;
     let required_permissions = &inv.required_permissions;
     let required_bot_permissions = &inv.required_bot_permissions;
+    let required_intents = &inv.required_intents;
     let subcommand_required = inv.args.subcommand_required;
     let owners_only = inv.args.owners_only;
     let guild_only = inv.args.guild_only;
@@ -295,9 +341,11 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
     let nsfw_only = inv.args.nsfw_only;
 
     let help_text = match &inv.args.help_text_fn {
-        Some(help_text_fn) => quote::quote! { Some(#help_text_fn()) },
+        Some(help_text_fn) => quote::quote! { Some(#help_text_fn().into()) },
         None => match &inv.help_text {
-            Some(extracted_explanation) => quote::quote! { Some(#extracted_explanation.into()) },
+            Some(extracted_explanation) => {
+                quote::quote! { Some(::std::borrow::Cow::Borrowed(#extracted_explanation)) }
+            }
             None => quote::quote! { None },
         },
     };
@@ -309,15 +357,25 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
         None => quote::quote! { None },
     };
 
+    let available = match &inv.args.available {
+        Some(available) => quote::quote! { #available },
+        None => quote::quote! { || true },
+    };
+
     let invoke_on_edit = inv.args.invoke_on_edit || inv.args.track_edits;
     let reuse_response = inv.args.reuse_response || inv.args.track_edits;
     let track_deletion = inv.args.track_deletion || inv.args.track_edits;
     let broadcast_typing = inv.args.broadcast_typing;
+    let delimiter = wrap_option(inv.args.delimiter);
     let aliases = &inv.args.aliases.0;
+    let examples = &inv.args.examples.0;
     let subcommands = &inv.args.subcommands.0;
+    let registration_guilds = &inv.args.registration_guilds.0;
 
     let parameters = slash::generate_parameters(&inv)?;
     let ephemeral = inv.args.ephemeral;
+    let manual_response = inv.args.manual_response;
+    let no_defer = inv.args.no_defer;
     let custom_data = match &inv.args.custom_data {
         Some(custom_data) => quote::quote! { Box::new(#custom_data) },
         None => quote::quote! { Box::new(()) },
@@ -350,12 +408,13 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
                 name: #command_name.to_string(),
                 name_localizations: #name_localizations,
                 qualified_name: String::from(#command_name), // properly filled in later by Framework
-                identifying_name: String::from(#identifying_name),
-                source_code_name: String::from(#function_name),
+                identifying_name: ::std::borrow::Cow::Borrowed(#identifying_name),
+                source_code_name: ::std::borrow::Cow::Borrowed(#function_name),
                 category: #category,
                 description: #description,
                 description_localizations: #description_localizations,
                 help_text: #help_text,
+                examples: vec![ #( ::std::borrow::Cow::Borrowed(#examples), )* ],
                 hide_in_help: #hide_in_help,
                 cooldowns: std::sync::Mutex::new(::poise::Cooldowns::new()),
                 cooldown_config: std::sync::RwLock::new(::poise::CooldownConfig {
@@ -364,12 +423,22 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
                     guild: #guild_cooldown.map(std::time::Duration::from_secs),
                     channel: #channel_cooldown.map(std::time::Duration::from_secs),
                     member: #member_cooldown.map(std::time::Duration::from_secs),
+                    exempt_owners: false,
+                    exempt_roles: Vec::new(),
                     __non_exhaustive: ()
                 }),
+                rate_limits: Default::default(),
+                rate_limit_config: Default::default(),
+                timeout: #timeout.map(std::time::Duration::from_secs),
+                available: #available,
+                registration_guilds: vec![ #( poise::serenity_prelude::GuildId::new(#registration_guilds), )* ],
+                disabled_guilds: Default::default(),
+                guild_overrides: Default::default(),
                 reuse_response: #reuse_response,
                 default_member_permissions: #default_member_permissions,
                 required_permissions: #required_permissions,
                 required_bot_permissions: #required_bot_permissions,
+                required_intents: #required_intents,
                 owners_only: #owners_only,
                 guild_only: #guild_only,
                 dm_only: #dm_only,
@@ -378,14 +447,18 @@ fn generate_command(mut inv: Invocation) -> Result<proc_macro2::TokenStream, dar
                 on_error: #on_error,
                 parameters: vec![ #( #parameters ),* ],
                 custom_data: #custom_data,
+                extensions: Default::default(),
 
                 aliases: vec![ #( #aliases.to_string(), )* ],
+                delimiter: #delimiter,
                 invoke_on_edit: #invoke_on_edit,
                 track_deletion: #track_deletion,
                 broadcast_typing: #broadcast_typing,
 
                 context_menu_name: #context_menu_name,
                 ephemeral: #ephemeral,
+                manual_response: #manual_response,
+                no_defer: #no_defer,
 
                 __non_exhaustive: (),
             }
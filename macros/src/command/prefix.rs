@@ -2,18 +2,32 @@ use super::Invocation;
 use quote::format_ident;
 use syn::spanned::Spanned as _;
 
-fn quote_parameter(p: &super::CommandParameter) -> Result<proc_macro2::TokenStream, syn::Error> {
+/// `following` are the parameters after `p`, needed for #[until_flag]
+fn quote_parameter(
+    p: &super::CommandParameter,
+    following: &[super::CommandParameter],
+) -> Result<proc_macro2::TokenStream, syn::Error> {
     enum Modifier {
         None,
         Lazy,
         Flag,
         Rest,
+        UntilFlag,
+        Fuzzy,
     }
-    let modifier = match (p.args.lazy, p.args.rest, p.args.flag) {
-        (false, false, false) => Modifier::None,
-        (true, false, false) => Modifier::Lazy,
-        (false, true, false) => Modifier::Rest,
-        (false, false, true) => Modifier::Flag,
+    let modifier = match (
+        p.args.lazy,
+        p.args.rest,
+        p.args.flag,
+        p.args.fuzzy,
+        p.args.until_flag,
+    ) {
+        (false, false, false, false, false) => Modifier::None,
+        (true, false, false, false, false) => Modifier::Lazy,
+        (false, true, false, false, false) => Modifier::Rest,
+        (false, false, true, false, false) => Modifier::Flag,
+        (false, false, false, true, false) => Modifier::Fuzzy,
+        (false, false, false, false, true) => Modifier::UntilFlag,
         _ => {
             let message = "modifiers like #[lazy] or #[rest] currently cannot be used together";
             return Err(syn::Error::new(p.span, message));
@@ -33,6 +47,19 @@ fn quote_parameter(p: &super::CommandParameter) -> Result<proc_macro2::TokenStre
         }
         Modifier::Lazy => quote::quote! { #[lazy] (#type_) },
         Modifier::Rest => quote::quote! { #[rest] (#type_) },
+        Modifier::UntilFlag => {
+            let flags = following
+                .iter()
+                .filter(|p| p.args.flag)
+                .map(|p| proc_macro2::Literal::string(&p.name))
+                .collect::<Vec<_>>();
+            if flags.is_empty() {
+                let message = "#[until_flag] must be followed by #[flag] parameters";
+                return Err(syn::Error::new(p.span, message));
+            }
+            quote::quote! { #[until_flag(#( #flags ),*)] (#type_) }
+        }
+        Modifier::Fuzzy => quote::quote! { #[fuzzy] (#type_) },
         Modifier::None => quote::quote! { (#type_) },
     })
 }
@@ -44,28 +71,60 @@ pub fn generate_prefix_action(inv: &Invocation) -> Result<proc_macro2::TokenStre
     let param_specs = inv
         .parameters
         .iter()
-        .map(quote_parameter)
+        .enumerate()
+        .map(|(i, p)| quote_parameter(p, &inv.parameters[i + 1..]))
         .collect::<Result<Vec<_>, syn::Error>>()?;
+    let validation = super::generate_argument_validation(inv, &param_idents);
+    // With a custom delimiter, a trailing #[rest] parameter receives everything after the
+    // preceding arguments verbatim. Not possible if a Vec parameter makes that count unknown
+    let max_delimited_args = match inv.parameters.split_last() {
+        Some((last, preceding))
+            if last.args.rest
+                && !preceding
+                    .iter()
+                    .any(|p| crate::util::extract_type_parameter("Vec", &p.type_).is_some()) =>
+        {
+            let max = preceding.len();
+            quote::quote! { Some(#max) }
+        }
+        _ => quote::quote! { None },
+    };
     let wildcard_arg = match inv.args.discard_spare_arguments {
         true => Some(quote::quote! { #[rest] (Option<String>), }),
         false => None,
     };
+    let extra_arguments_handling = match inv.parameters.last() {
+        _ if inv.args.discard_spare_arguments => None,
+        Some(last) if last.args.rest => None,
+        last => Some(generate_extra_arguments_handling(
+            last,
+            &param_idents,
+            &param_specs,
+        )),
+    };
 
     Ok(quote::quote! {
         |ctx| Box::pin(async move {
-            let ( #( #param_idents, )* .. ) = ::poise::parse_prefix_args!(
-                ctx.serenity_context, ctx.msg, ctx.args, 0 =>
+            let args = match ctx.command.delimiter {
+                Some(delimiter) => ::std::borrow::Cow::Owned(
+                    ::poise::requote_delimited_args(ctx.args, delimiter, #max_delimited_args),
+                ),
+                None => ::std::borrow::Cow::Borrowed(ctx.args),
+            };
+            let parsed = ::poise::parse_prefix_args!(
+                ctx.serenity_context, ctx.msg, &*args, 0 =>
                 #( #param_specs, )*
                 #wildcard_arg
-            ).await.map_err(|(error, input)| poise::FrameworkError::new_argument_parse(
+            ).await;
+            #extra_arguments_handling
+            let ( #( #param_idents, )* .. ) = parsed.map_err(|(error, input)| poise::FrameworkError::new_argument_parse(
                 ctx.into(),
                 input,
                 error,
             ))?;
+            #validation
 
-            if !ctx.framework.options.manual_cooldowns {
-                ctx.command.cooldowns.lock().unwrap().start_cooldown(ctx.cooldown_context());
-            }
+            poise::start_cooldowns(ctx.into()).await;
 
             inner(ctx.into(), #( #param_idents, )* )
                 .await
@@ -76,3 +135,45 @@ pub fn generate_prefix_action(inv: &Invocation) -> Result<proc_macro2::TokenStre
         })
     })
 }
+
+/// Generates code which reparses the arguments with a trailing catch-all parameter if there were
+/// too many, to implement [`poise::PrefixFrameworkOptions::on_extra_arguments`]
+fn generate_extra_arguments_handling(
+    last: Option<&super::CommandParameter>,
+    param_idents: &[syn::Ident],
+    param_specs: &[proc_macro2::TokenStream],
+) -> proc_macro2::TokenStream {
+    let appendable = last.map_or(false, |last| {
+        last.type_ == syn::parse_quote! { String }
+            && !(last.args.lazy || last.args.flag || last.args.fuzzy || last.args.until_flag)
+    });
+    let append = match param_idents.len().checked_sub(1) {
+        Some(last_index) if appendable => {
+            let last_index = syn::Index::from(last_index);
+            quote::quote! { Some(|args, extra| {
+                args.#last_index.push(' ');
+                args.#last_index.push_str(&extra);
+            }) }
+        }
+        _ => quote::quote! { None },
+    };
+
+    quote::quote! {
+        let parsed = {
+            let args = &*args;
+            ::poise::handle_extra_arguments(
+                ctx.framework.options.prefix_options.on_extra_arguments,
+                parsed,
+                move || async move {
+                    let ( #( #param_idents, )* extra, ) = ::poise::parse_prefix_args!(
+                        ctx.serenity_context, ctx.msg, args, 0 =>
+                        #( #param_specs, )*
+                        #[rest] (Option<String>),
+                    ).await?;
+                    Ok((( #( #param_idents, )* ), extra))
+                },
+                #append,
+            ).await
+        };
+    }
+}
@@ -97,6 +97,8 @@ pub fn generate_parameters(inv: &Invocation) -> Result<Vec<proc_macro2::TokenStr
             None => quote::quote! { None },
         };
 
+        let redact = param.args.redact;
+
         parameter_structs.push((
             quote::quote! {
                 ::poise::CommandParameter {
@@ -112,6 +114,7 @@ pub fn generate_parameters(inv: &Invocation) -> Result<Vec<proc_macro2::TokenStr
                     channel_types: #channel_types,
                     type_setter: #type_setter,
                     choices: #choices,
+                    redact: #redact,
                     autocomplete_callback: #autocomplete_callback,
                     __non_exhaustive: (),
                 }
@@ -140,10 +143,25 @@ pub fn generate_slash_action(inv: &Invocation) -> Result<proc_macro2::TokenStrea
         }
     }
 
+    for param in &inv.parameters {
+        if let Some(desc) = &param.args.description {
+            let len = desc.chars().count();
+            if !(1..=100).contains(&len) {
+                return Err(syn::Error::new(
+                    param.span,
+                    format!(
+                        "slash command parameter description must be 1-100 chars ({len} chars)"
+                    ),
+                ));
+            }
+        }
+    }
+
     let param_identifiers = (0..inv.parameters.len())
         .map(|i| format_ident!("poise_param_{i}"))
         .collect::<Vec<_>>();
     let param_names = inv.parameters.iter().map(|p| &p.name).collect::<Vec<_>>();
+    let validation = super::generate_argument_validation(inv, &param_identifiers);
 
     let param_types = inv
         .parameters
@@ -176,10 +194,9 @@ pub fn generate_slash_action(inv: &Invocation) -> Result<proc_macro2::TokenStrea
                 },
                 poise::SlashArgError::__NonExhaustive => unreachable!(),
             })?;
+            #validation
 
-            if !ctx.framework.options.manual_cooldowns {
-                ctx.command.cooldowns.lock().unwrap().start_cooldown(ctx.cooldown_context());
-            }
+            poise::start_cooldowns(ctx.into()).await;
 
             inner(ctx.into(), #( #param_identifiers, )*)
                 .await
@@ -207,9 +224,7 @@ pub fn generate_context_menu_action(
     Ok(quote::quote! {
         <#param_type as ::poise::ContextMenuParameter<_, _>>::to_action(|ctx, value| {
             Box::pin(async move {
-                if !ctx.framework.options.manual_cooldowns {
-                    ctx.command.cooldowns.lock().unwrap().start_cooldown(ctx.cooldown_context());
-                }
+                poise::start_cooldowns(ctx.into()).await;
 
                 inner(ctx.into(), value)
                     .await
@@ -34,6 +34,7 @@ for example for command-specific help (i.e. `~help command_name`). Escape newlin
 - `rename`: Choose an alternative command name instead of the function name
     - Useful if your command name is a Rust keyword, like `move`
 - `aliases`: Command name aliases (only applies to prefix commands)
+- `examples`: Example invocations without the prefix, shown in the command specific help, e.g. `examples("remind 10m take a break")`
 - `category`: Category of this command which affects placement in the help command
 - `custom_data`: Arbitrary expression that will be boxed and stored in `Command::custom_data`
 - `identifying_name`: Optionally, a unique identifier for this command for your personal usage
@@ -42,16 +43,22 @@ for example for command-specific help (i.e. `~help command_name`). Escape newlin
 
 - `required_permissions`: Permissions which the command caller needs to have
 - `required_bot_permissions`: Permissions which the bot is known to need
-- `default_member_permissions`: Like `required_permissions`, but checked server-side (slash only)
+- `required_intents`: Gateway intents which the command relies on, e.g. `required_intents = "GUILD_MEMBERS"`
+    - Not enforced, but the framework warns on startup if they're not enabled on the client
+- `default_member_permissions`: Like `required_permissions`, but checked server-side (slash and context menu only)
     - Due to being checked server-side, users without the required permissions are prevented from executing the command in the first place, which is a better experience
     - However, `default_member_permissions` has no effect on subcommands, which always inherit their permissions from the top-level command
     - Also, guild owners can freely change the required permissions for any bot command for their guild
 - `owners_only`: Restricts command callers to a configurable list of owners (see FrameworkOptions)
 - `guild_only`: Restricts command callers to only run on a guild
+    - Application commands are also registered with `dm_permission: false`, so Discord hides them in DMs
 - `dm_only`: Restricts command callers to only run on a DM
 - `nsfw_only`: Restricts command callers to only run on a NSFW channel
 - `subcommand_required`: Requires a subcommand to be specified (prefix only)
 - `check`: Path to a function which is invoked for every invocation. If the function returns false, the command is not executed (can be used multiple times)
+- `validate`: Path to a function which is invoked with a reference to the tuple of all parsed arguments, before the command body runs (prefix and slash)
+    - Signature: `fn(&(T1, T2, ...)) -> Result<(), String>`, e.g. `fn(&(i32, i32)) -> Result<(), String>` for a command with two `i32` parameters
+    - On `Err`, the command is not executed and `FrameworkError::ArgumentParse` is raised with the returned message
 
 ## Help-related arguments
 
@@ -77,10 +84,18 @@ for example for command-specific help (i.e. `~help command_name`). Escape newlin
 ## Other
 
 - `on_error`: Error handling function
+- `registration_guilds`: List of guild IDs to register this command in instead of globally, e.g. `registration_guilds(123, 456)` (see `poise::builtins::register_scoped`)
+- `available`: Path to a `fn() -> bool`; if it returns false on startup, the command is excluded from dispatch and registration
+- `timeout`: Cancel the command if it runs longer than this many seconds (overrides `FrameworkOptions::command_timeout`)
 - `broadcast_typing`: Trigger a typing indicator while command runs (prefix only)
 - `discard_spare_arguments`: Don't throw an error if the user supplies too many arguments (prefix only)
+    - See `PrefixFrameworkOptions::on_extra_arguments` to configure this for all commands
+- `delimiter`: Character separating the arguments instead of whitespace, e.g. `delimiter = ','` (prefix only)
 - `ephemeral`: Make bot responses ephemeral if possible (slash only)
     - Only poise's function, like `poise::send_reply`, respect this preference
+- `manual_response`: The command sends the initial interaction response itself; poise only sends followups (slash only)
+    - Make sure to respond within three seconds, or the interaction expires. See `Command::manual_response`
+- `no_defer`: Don't defer this command automatically if `FrameworkOptions::defer_all_commands` is set, e.g. because it responds with a modal (slash only)
 
 # Function parameters
 
@@ -95,11 +110,12 @@ are multiple attributes you can use on parameters:
 
 ## Meta properties
 
-- `#[description = ""]`: Sets description of the parameter (slash-only)
+- `#[description = ""]`: Sets description of the parameter, 1-100 characters long (slash-only). Parameters without a description are registered with a placeholder text and logged as a warning on startup
 - `#[description_localized("locale", "Description")]`: Adds localized description of the parameter (slash-only)
 - `#[name_localized("locale", "new_name")]`: Adds localized name of the parameter (slash-only)
 - `#[autocomplete = "callback()"]`: Sets the autocomplete callback (slash-only)
 - `#[rename = "new_name"]`: Changes the user-facing name of the parameter (slash-only)
+- `#[redact]`: Hides the value of this parameter in `Context::redacted_invocation_string()`, e.g. for passwords
 
 ## Input filter (slash only)
 
@@ -111,9 +127,17 @@ are multiple attributes you can use on parameters:
 
 ## Parser settings (prefix only)
 - `#[rest]`: Use the entire rest of the message for this parameter (prefix-only)
+    - The rest of the message is passed to the type's parser as-is, without tokenization. This way, a custom `FromStr` type can parse the whole argument string however it likes, for example for DSL-like commands (`~remind in 3h to do thing`)
+    - Line breaks, indentation and other whitespace within the rest are kept verbatim. Only the whitespace separating it from the preceding argument is stripped, including at most one line break, so a rest starting on a new line keeps its indentation
+    - In slash commands, such a type is a single string option which is parsed the same way
+- `#[until_flag]`: Like `#[rest]`, but stops before the first word matching one of the `#[flag]` parameters after this one, which are then parsed as usual (prefix-only)
+    - For example with `async fn warn(ctx: Context<'_>, user: serenity::Member, #[until_flag] reason: String, #[flag] silent: bool)`, `~warn @user spamming in general silent` sets reason to `spamming in general` and silent to true
+    - To use such a word in the text instead, quote it, like `~warn @user the "silent" treatment` (the quotes are kept)
 - `#[lazy]`: Can be used on Option and Vec parameters and is equivalent to regular expressions' laziness (prefix-only)
 - `#[flag]`: Can be used on a bool parameter to set the bool to true if the user typed the parameter name literally (prefix-only)
     - For example with `async fn my_command(ctx: Context<'_>, #[flag] my_flag: bool)`, `~my_command` would set my_flag to false, and `~my_command my_flag` would set my_flag to true
+- `#[fuzzy]`: Can be used on a `ChoiceParameter` parameter (or `Option` of one) to also accept unambiguous prefixes of choice names (prefix-only)
+    - For example, `~setmode ag` matches an `Aggressive` choice. Exact matches always win; if the input is a prefix of multiple choices, an argument parse error listing them is raised
 
 # Help text
 
@@ -182,6 +206,29 @@ fn my_command() -> poise::Command<Data, Error> {
 
 If you're curious, you can use [`cargo expand`](https://github.com/dtolnay/cargo-expand) to see the
 exact desugaring
+
+## Performance
+
+The generated function builds a new `poise::Command` every time it's called. It's meant to be
+called once at startup, when creating the command list for `FrameworkOptions::commands`; the
+framework then keeps and reuses that list for the lifetime of the bot. Building a command is cheap
+(a handful of small allocations), so even bots with hundreds of commands pay this cost only once.
+
+What the generated `Command` consists of, and why it can't be a `const` or `static`:
+- All callbacks (`prefix_action`, `slash_action`, `context_menu_action`, `checks`, `on_error`,
+  parameter autocomplete functions) are plain function pointers, which don't allocate
+- Metadata which is only ever read, namely `identifying_name`, `source_code_name`, `category`,
+  `help_text` and `examples`, is stored as `Cow<'static, str>` and borrowed from the binary, so
+  building a command doesn't allocate for it. A `help_text_fn` is still called on every build
+- The remaining metadata (names, descriptions, aliases, localizations, permissions) is known at
+  compile time too, but stored as owned `String`s and `HashMap`s so that it can be modified
+  programmatically, e.g. to apply translations before registering
+- Runtime state (cooldown and rate limit trackers, guild enablement, extensions) is created empty
+  and requires interior mutability
+
+If you need the command list in multiple places, e.g. to register commands in a separate
+process, call the generated functions again instead of cloning; `Command` doesn't implement
+`Clone` because of its runtime state.
 */
 #[proc_macro_attribute]
 pub fn command(args: TokenStream, function: TokenStream) -> TokenStream {
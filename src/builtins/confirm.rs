@@ -0,0 +1,83 @@
+//! Yes/No confirmation prompt, e.g. for destructive commands
+
+use crate::serenity_prelude as serenity;
+
+/// Sends a message with the given prompt and Yes/No buttons, then waits for the command author to
+/// click one of them. Button presses by other users are ignored.
+///
+/// Returns `true` if the author clicked Yes, and `false` if they clicked No or didn't click
+/// anything within `timeout`. Either way, the buttons are removed afterwards.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # async fn _test(ctx: poise::Context<'_, (), serenity::Error>) -> Result<(), serenity::Error> {
+/// let timeout = std::time::Duration::from_secs(30);
+/// if poise::builtins::confirm(ctx, "Really delete all messages?", timeout).await? {
+///     // ...
+/// }
+/// # Ok(()) }
+/// ```
+pub async fn confirm<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    prompt: impl Into<String>,
+    timeout: std::time::Duration,
+) -> Result<bool, serenity::Error> {
+    let prompt = prompt.into();
+
+    // Define some unique identifiers for the buttons
+    let ctx_id = ctx.component_id_base();
+    let _claim = ctx.framework().options.claim_component_ids(ctx_id.clone());
+    let yes_button_id = format!("{}yes", ctx_id);
+    let no_button_id = format!("{}no", ctx_id);
+
+    let reply = {
+        let components = serenity::CreateActionRow::Buttons(vec![
+            serenity::CreateButton::new(&yes_button_id)
+                .label("Yes")
+                .style(serenity::ButtonStyle::Success),
+            serenity::CreateButton::new(&no_button_id)
+                .label("No")
+                .style(serenity::ButtonStyle::Danger),
+        ]);
+
+        crate::CreateReply::default()
+            .content(prompt.clone())
+            .components(vec![components])
+    };
+
+    let reply_handle = ctx.send(reply).await?;
+
+    let (yes_id, no_id) = (yes_button_id.clone(), no_button_id);
+    let press = serenity::collector::ComponentInteractionCollector::new(ctx)
+        .author_id(ctx.author().id)
+        .filter(move |press| press.data.custom_id == yes_id || press.data.custom_id == no_id)
+        .timeout(timeout)
+        .await;
+
+    match press {
+        Some(press) => {
+            let confirmed = press.data.custom_id == yes_button_id;
+            press
+                .create_response(
+                    ctx.serenity_context(),
+                    serenity::CreateInteractionResponse::UpdateMessage(
+                        serenity::CreateInteractionResponseMessage::new().components(Vec::new()),
+                    ),
+                )
+                .await?;
+            Ok(confirmed)
+        }
+        None => {
+            reply_handle
+                .edit(
+                    ctx,
+                    crate::CreateReply::default()
+                        .content(prompt)
+                        .components(Vec::new()),
+                )
+                .await?;
+            Ok(false)
+        }
+    }
+}
@@ -101,6 +101,48 @@ async fn get_prefix_from_options<U, E>(ctx: crate::Context<'_, U, E>) -> Option<
     }
 }
 
+/// Looks up the help string `key` with [`crate::FrameworkOptions::translator`] in the locale of
+/// the user, falling back to the English `default` if there's no translator or translation
+fn tr<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    key: &str,
+    default: &str,
+    args: &[(&str, &dyn std::fmt::Display)],
+) -> String {
+    ctx.framework()
+        .options()
+        .translator
+        .as_ref()
+        .and_then(|translator| translator.translate(ctx.locale(), key, args))
+        .unwrap_or_else(|| fill_placeholders(default, args))
+}
+
+/// Replaces `{name}` placeholders with the given named arguments
+fn fill_placeholders(text: &str, args: &[(&str, &dyn std::fmt::Display)]) -> String {
+    let mut text = text.to_owned();
+    for (name, value) in args {
+        text = text.replace(&format!("{{{}}}", name), &value.to_string());
+    }
+    text
+}
+
+/// Returns the prefix to show in front of [`crate::Command::examples`]: the prefix that the help
+/// command was invoked with, if possible, so that the examples can be copied as-is
+async fn example_prefix<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    command: &crate::Command<U, E>,
+) -> String {
+    if command.prefix_action.is_none() {
+        return String::from("/");
+    }
+    match ctx {
+        crate::Context::Prefix(ctx) => ctx.prefix.to_owned(),
+        crate::Context::Application(_) => get_prefix_from_options(ctx)
+            .await
+            .unwrap_or_else(|| String::from("<prefix>")),
+    }
+}
+
 /// Format context menu command name
 fn format_context_menu_name<U, E>(command: &crate::Command<U, E>) -> Option<String> {
     let kind = match command.context_menu_action {
@@ -179,15 +221,20 @@ async fn help_single_command<U, E>(
                 if config.include_description {
                     format!("{}\n\n{}", description, help_text)
                 } else {
-                    help_text.clone()
+                    help_text.to_string()
                 }
             }
             (Some(description), None) => description.to_owned(),
-            (None, Some(help_text)) => help_text.clone(),
-            (None, None) => "No help available".to_string(),
+            (None, Some(help_text)) => help_text.to_string(),
+            (None, None) => tr(ctx, "help-no-help", "No help available", &[]),
         };
         if !command.parameters.is_empty() {
-            text += "\n\n```\nParameters:\n";
+            write!(
+                text,
+                "\n\n```\n{}:\n",
+                tr(ctx, "help-parameters", "Parameters", &[])
+            )
+            .unwrap();
             let mut parameterlist = TwoColumnList::new();
             for parameter in &command.parameters {
                 let name = parameter.name.clone();
@@ -195,9 +242,9 @@ async fn help_single_command<U, E>(
                 let description = format!(
                     "({}) {}",
                     if parameter.required {
-                        "required"
+                        tr(ctx, "help-required", "required", &[])
                     } else {
-                        "optional"
+                        tr(ctx, "help-optional", "optional", &[])
                     },
                     description,
                 );
@@ -206,8 +253,20 @@ async fn help_single_command<U, E>(
             text += &parameterlist.into_string();
             text += "```";
         }
+        if !command.examples.is_empty() {
+            let prefix = example_prefix(ctx, command).await;
+            write!(text, "\n\n{}:", tr(ctx, "help-examples", "Examples", &[])).unwrap();
+            for example in &command.examples {
+                write!(text, "\n`{}{}`", prefix, example).unwrap();
+            }
+        }
         if !command.subcommands.is_empty() {
-            text += "\n\n```\nSubcommands:\n";
+            write!(
+                text,
+                "\n\n```\n{}:\n",
+                tr(ctx, "help-subcommands", "Subcommands", &[])
+            )
+            .unwrap();
             let mut commandlist = TwoColumnList::new();
             // Subcommands can exist on context menu commands, but there's no
             // hierarchy in the menu, so just display them as a list without
@@ -222,7 +281,12 @@ async fn help_single_command<U, E>(
         }
         format!("**{}**\n\n{}", invocations, text)
     } else {
-        format!("No such command `{}`", command_name)
+        tr(
+            ctx,
+            "help-no-such-command",
+            "No such command `{command}`",
+            &[("command", &command_name)],
+        )
     };
 
     let reply = CreateReply::default()
@@ -304,6 +368,7 @@ async fn generate_all_commands<U, E>(
 
     let mut menu = String::from("```\n");
 
+    let default_category = tr(ctx, "help-commands", "Commands", &[]);
     let mut commandlist = TwoColumnList::new();
     for (category_name, commands) in categories {
         let commands = commands
@@ -315,7 +380,7 @@ async fn generate_all_commands<U, E>(
         if commands.is_empty() {
             continue;
         }
-        commandlist.push_heading(category_name.unwrap_or("Commands"));
+        commandlist.push_heading(category_name.unwrap_or(&default_category));
         for command in commands {
             preformat_command(
                 &mut commandlist,
@@ -329,7 +394,13 @@ async fn generate_all_commands<U, E>(
     menu += &commandlist.into_string();
 
     if config.show_context_menu_commands {
-        menu += "\nContext menu commands:\n";
+        let heading = tr(
+            ctx,
+            "help-context-menu-commands",
+            "Context menu commands",
+            &[],
+        );
+        writeln!(menu, "\n{}:", heading).unwrap();
 
         for command in &ctx.framework().options().commands {
             let name = format_context_menu_name(command);
@@ -414,6 +485,26 @@ async fn help_all_commands<U, E>(
 /// Type ?help command for more info on a command.
 /// You can edit your message to the bot and the bot will edit its response.
 /// ```
+///
+/// # Localization
+///
+/// The headings and labels of the help output are looked up with
+/// [`crate::FrameworkOptions::translator`] in the [locale](crate::Context::locale) of the user,
+/// which is None in prefix commands, so the translator can pick a default language. Missing
+/// translations fall back to English. The keys, with their English text, are:
+/// - `help-commands`: Commands (heading of commands without category)
+/// - `help-context-menu-commands`: Context menu commands
+/// - `help-no-help`: No help available
+/// - `help-no-such-command`: No such command `{command}`
+/// - `help-did-you-mean`: Did you mean {suggestions}? (only in [`command_help`])
+/// - `help-parameters`, `help-examples`, `help-subcommands`
+/// - `help-required`, `help-optional`
+///
+/// [`command_help`] additionally uses `help-usage`, `help-no-description`,
+/// `help-more-parameters` (heading of parameters beyond Discord's embed field limit),
+/// `help-aliases`, `help-required-permissions`, `help-cooldown` and `help-cooldown-global`,
+/// `help-cooldown-user`, `help-cooldown-guild`, `help-cooldown-channel` and
+/// `help-cooldown-member` with a `{duration}` argument, like "{duration} per user".
 pub async fn help<U, E>(
     ctx: crate::Context<'_, U, E>,
     command: Option<&str>,
@@ -424,3 +515,370 @@ pub async fn help<U, E>(
         None => help_all_commands(ctx, config).await,
     }
 }
+
+/// Computes the Levenshtein distance between two strings, case-insensitively
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.to_lowercase().chars().collect::<Vec<_>>();
+    let b = b.to_lowercase().chars().collect::<Vec<_>>();
+
+    let mut previous_row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a_char) in a.iter().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous_row[j] + usize::from(a_char != b_char);
+            row.push(substitution.min(previous_row[j + 1] + 1).min(row[j] + 1));
+        }
+        previous_row = row;
+    }
+    previous_row[b.len()]
+}
+
+/// Returns up to three names and aliases of the given commands which are close to `name`, closest
+/// first
+fn suggest_command_names<'a, U, E>(
+    commands: &'a [crate::Command<U, E>],
+    name: &str,
+) -> Vec<&'a str> {
+    // Allow at least two edits, so that swapped letters are caught
+    let max_distance = std::cmp::max(2, name.chars().count() / 3);
+    let mut candidates = commands
+        .iter()
+        .filter(|command| !command.hide_in_help)
+        .flat_map(|command| std::iter::once(&command.name).chain(&command.aliases))
+        .map(|candidate| (edit_distance(candidate, name), candidate.as_str()))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .collect::<Vec<_>>();
+    candidates.sort();
+    candidates
+        .into_iter()
+        .map(|(_, candidate)| candidate)
+        .take(3)
+        .collect()
+}
+
+/// Formats a list of names like "`a`, `b`"
+fn format_name_list(names: impl IntoIterator<Item = impl std::fmt::Display>) -> String {
+    names
+        .into_iter()
+        .map(|name| format!("`{}`", name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Shows detailed help for a single command as an embed, e.g. for `~help ban` or
+/// `~help config prefix`.
+///
+/// The command (or nested subcommand) is resolved like during dispatch. The embed contains the
+/// description and help text, the usage, a field per parameter, and aliases, cooldowns and
+/// required permissions where applicable. If no command matches, similarly named commands are
+/// suggested.
+///
+/// Use this in your help command when a command argument is given; for the overview of all
+/// commands, see [`help()`]. Both are localized the same way; see [`help()#localization`].
+/// ```rust
+/// # type Error = Box<dyn std::error::Error>;
+/// # type Context<'a> = poise::Context<'a, (), Error>;
+/// /// Show this menu
+/// #[poise::command(prefix_command, slash_command)]
+/// pub async fn help(
+///     ctx: Context<'_>,
+///     #[description = "Specific command to show help about"]
+///     #[rest]
+///     command: Option<String>,
+/// ) -> Result<(), Error> {
+///     let config = poise::builtins::HelpConfiguration::default();
+///     match command {
+///         Some(command) => poise::builtins::command_help(ctx, &command, config).await?,
+///         None => poise::builtins::help(ctx, None, config).await?,
+///     }
+///     Ok(())
+/// }
+/// ```
+pub async fn command_help<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    query: &str,
+    config: HelpConfiguration<'_>,
+) -> Result<(), serenity::Error> {
+    let commands = &ctx.framework().options().commands;
+    let query = query.trim();
+
+    let mut parent_commands = Vec::new();
+    let found = crate::find_command(commands, query, true, &mut parent_commands);
+    let command = match found {
+        Some((command, _, "")) => command,
+        not_found => {
+            // If a parent command was found, the unknown part is one of its subcommands
+            let (candidates, unknown_name) = match not_found {
+                Some((command, _, rest)) => (&command.subcommands, rest),
+                None => (commands, query),
+            };
+            let unknown_name = unknown_name.split_whitespace().next().unwrap_or("");
+            let suggestions = suggest_command_names(candidates, unknown_name);
+            let mut response = tr(
+                ctx,
+                "help-no-such-command",
+                "No such command `{command}`",
+                &[("command", &query)],
+            );
+            if !suggestions.is_empty() {
+                let suggestions = format_name_list(suggestions);
+                let suggestions = tr(
+                    ctx,
+                    "help-did-you-mean",
+                    "Did you mean {suggestions}?",
+                    &[("suggestions", &suggestions)],
+                );
+                write!(response, ". {}", suggestions).unwrap();
+            }
+            let reply = CreateReply::default()
+                .content(response)
+                .ephemeral(config.ephemeral);
+            ctx.send(reply).await?;
+            return Ok(());
+        }
+    };
+
+    let prefix = if command.prefix_action.is_some() {
+        get_prefix_from_options(ctx)
+            .await
+            .unwrap_or_else(|| String::from("<prefix>"))
+    } else {
+        String::from("/")
+    };
+    let qualified_name = parent_commands
+        .iter()
+        .map(|parent| parent.name.as_str())
+        .chain(std::iter::once(command.name.as_str()))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut usage = format!("{}{}", prefix, qualified_name);
+    for parameter in &command.parameters {
+        match parameter.required {
+            true => write!(usage, " <{}>", parameter.name).unwrap(),
+            false => write!(usage, " [{}]", parameter.name).unwrap(),
+        }
+    }
+
+    let description = match (&command.description, &command.help_text) {
+        (Some(description), Some(help_text)) if config.include_description => {
+            format!("{}\n\n{}", description, help_text)
+        }
+        (_, Some(help_text)) => help_text.to_string(),
+        (Some(description), None) => description.clone(),
+        (None, None) => tr(ctx, "help-no-help", "No help available", &[]),
+    };
+
+    let mut embed = serenity::CreateEmbed::new()
+        .title(format!("{}{}", prefix, qualified_name))
+        .description(description)
+        .field(
+            tr(ctx, "help-usage", "Usage", &[]),
+            format!("`{}`", usage),
+            false,
+        );
+    let parameters = command
+        .parameters
+        .iter()
+        .map(|parameter| {
+            let name = match parameter.required {
+                true => format!("<{}>", parameter.name),
+                false => format!(
+                    "[{}] ({})",
+                    parameter.name,
+                    tr(ctx, "help-optional", "optional", &[])
+                ),
+            };
+            let description = match &parameter.description {
+                Some(description) => description.clone(),
+                None => tr(ctx, "help-no-description", "No description", &[]),
+            };
+            (name, description)
+        })
+        .collect();
+    let more_parameters = tr(ctx, "help-more-parameters", "More parameters", &[]);
+    for (name, description) in parameter_fields(parameters, more_parameters) {
+        embed = embed.field(name, description, true);
+    }
+    if !command.examples.is_empty() {
+        let prefix = example_prefix(ctx, command).await;
+        let examples = command
+            .examples
+            .iter()
+            .map(|example| format!("`{}{}`", prefix, example))
+            .collect::<Vec<_>>();
+        embed = embed.field(
+            tr(ctx, "help-examples", "Examples", &[]),
+            examples.join("\n"),
+            false,
+        );
+    }
+    let subcommands = command
+        .subcommands
+        .iter()
+        .filter(|subcommand| !subcommand.hide_in_help)
+        .map(|subcommand| &subcommand.name)
+        .collect::<Vec<_>>();
+    // Discord rejects fields with an empty value
+    if !subcommands.is_empty() {
+        embed = embed.field(
+            tr(ctx, "help-subcommands", "Subcommands", &[]),
+            format_name_list(subcommands),
+            false,
+        );
+    }
+    if !command.aliases.is_empty() {
+        embed = embed.field(
+            tr(ctx, "help-aliases", "Aliases", &[]),
+            format_name_list(&command.aliases),
+            false,
+        );
+    }
+
+    let cooldowns = {
+        let config = command.cooldown_config.read().unwrap();
+        [
+            ("help-cooldown-global", "{duration} global", config.global),
+            ("help-cooldown-user", "{duration} per user", config.user),
+            ("help-cooldown-guild", "{duration} per guild", config.guild),
+            (
+                "help-cooldown-channel",
+                "{duration} per channel",
+                config.channel,
+            ),
+            (
+                "help-cooldown-member",
+                "{duration} per member",
+                config.member,
+            ),
+        ]
+        .iter()
+        .filter_map(|&(key, default, cooldown)| {
+            let duration = super::format_duration(cooldown?);
+            Some(tr(ctx, key, default, &[("duration", &duration)]))
+        })
+        .collect::<Vec<_>>()
+    };
+    if !cooldowns.is_empty() {
+        embed = embed.field(
+            tr(ctx, "help-cooldown", "Cooldown", &[]),
+            cooldowns.join("\n"),
+            false,
+        );
+    }
+    if !command.required_permissions.is_empty() {
+        embed = embed.field(
+            tr(
+                ctx,
+                "help-required-permissions",
+                "Required permissions",
+                &[],
+            ),
+            command.required_permissions.to_string(),
+            false,
+        );
+    }
+
+    let reply = CreateReply::default()
+        .embed(embed)
+        .ephemeral(config.ephemeral);
+    ctx.send(reply).await?;
+    Ok(())
+}
+
+/// Discord rejects embeds with more fields than this
+const MAX_EMBED_FIELDS: usize = 25;
+/// How many fields [`command_help`] adds besides the parameters at most: usage, examples,
+/// subcommands, aliases, cooldown and required permissions
+const MAX_NON_PARAMETER_FIELDS: usize = 6;
+/// Discord rejects embed field values longer than this
+const MAX_FIELD_VALUE_LENGTH: usize = 1024;
+
+/// Turns the names and descriptions of the parameters into embed fields. If there are too many
+/// parameters to fit into the embed besides the other fields, the remaining ones are folded into
+/// a single field called `more_name`
+fn parameter_fields(parameters: Vec<(String, String)>, more_name: String) -> Vec<(String, String)> {
+    let max_fields = MAX_EMBED_FIELDS - MAX_NON_PARAMETER_FIELDS;
+    if parameters.len() <= max_fields {
+        return parameters;
+    }
+
+    let mut parameters = parameters.into_iter();
+    let mut fields = parameters.by_ref().take(max_fields - 1).collect::<Vec<_>>();
+    let mut folded = parameters
+        .map(|(name, description)| format!("`{}`: {}", name, description))
+        .collect::<Vec<_>>()
+        .join("\n");
+    if folded.len() > MAX_FIELD_VALUE_LENGTH {
+        let mut truncate_at = MAX_FIELD_VALUE_LENGTH;
+        while !folded.is_char_boundary(truncate_at) {
+            truncate_at -= 1;
+        }
+        folded.truncate(truncate_at);
+    }
+    fields.push((more_name, folded));
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suggest_command_names() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("Ping", "ping"), 0);
+        assert_eq!(edit_distance("", "abc"), 3);
+
+        let command = |name: &str, aliases: &[&str]| crate::Command::<(), ()> {
+            name: name.to_string(),
+            aliases: aliases.iter().map(|alias| alias.to_string()).collect(),
+            ..Default::default()
+        };
+        let commands = vec![
+            command("ping", &[]),
+            command("purge", &["clear"]),
+            command("ban", &[]),
+        ];
+        assert_eq!(suggest_command_names(&commands, "pign"), vec!["ping"]);
+        assert_eq!(suggest_command_names(&commands, "cler"), vec!["clear"]);
+        assert!(suggest_command_names(&commands, "weather").is_empty());
+    }
+
+    #[test]
+    fn test_fill_placeholders() {
+        assert_eq!(
+            fill_placeholders("No such command `{command}`", &[("command", &"pign")]),
+            "No such command `pign`",
+        );
+        assert_eq!(
+            fill_placeholders(
+                "{duration} per user",
+                &[("duration", &"5s"), ("unused", &1)]
+            ),
+            "5s per user",
+        );
+        assert_eq!(fill_placeholders("Usage", &[]), "Usage");
+    }
+
+    #[test]
+    fn test_parameter_fields() {
+        let parameters = |n: usize| {
+            (0..n)
+                .map(|i| (format!("<p{}>", i), "x".repeat(100)))
+                .collect::<Vec<_>>()
+        };
+        let more = || String::from("More parameters");
+
+        let fields = parameter_fields(parameters(19), more());
+        assert_eq!(fields, parameters(19));
+
+        let fields = parameter_fields(parameters(30), more());
+        assert_eq!(fields.len(), MAX_EMBED_FIELDS - MAX_NON_PARAMETER_FIELDS);
+        assert_eq!(fields[..18], parameters(18)[..]);
+        let (name, value) = &fields[18];
+        assert_eq!(name, "More parameters");
+        assert!(value.starts_with(&format!("`<p18>`: {}\n`<p19>`", "x".repeat(100))));
+        assert!(value.len() <= MAX_FIELD_VALUE_LENGTH);
+    }
+}
@@ -3,17 +3,32 @@
 //! This file provides sample commands and utility functions like help menus or error handlers to
 //! use as a starting point for the framework.
 
+#[cfg(any(feature = "chrono", feature = "time"))]
+mod confirm;
+#[cfg(any(feature = "chrono", feature = "time"))]
+pub use confirm::*;
+
 mod help;
 pub use help::*;
 
 mod register;
 pub use register::*;
 
+#[cfg(any(feature = "chrono", feature = "time"))]
+mod prompt;
+#[cfg(any(feature = "chrono", feature = "time"))]
+pub use prompt::*;
+
 #[cfg(any(feature = "chrono", feature = "time"))]
 mod paginate;
 #[cfg(any(feature = "chrono", feature = "time"))]
 pub use paginate::*;
 
+#[cfg(any(feature = "chrono", feature = "time"))]
+mod subcommand_menu;
+#[cfg(any(feature = "chrono", feature = "time"))]
+pub use subcommand_menu::*;
+
 use crate::{serenity_prelude as serenity, CreateReply};
 
 /// An error handler that logs errors either via the [`tracing`] crate or via a Discord message. Set
@@ -110,8 +125,8 @@ pub async fn on_error<U, E: std::fmt::Display + std::fmt::Debug>(
             ctx,
         } => {
             let msg = format!(
-                "You're too fast. Please wait {} seconds before retrying",
-                remaining_cooldown.as_secs()
+                "You're too fast. Please try again in {}",
+                format_duration(remaining_cooldown)
             );
             ctx.send(CreateReply::default().content(msg).ephemeral(true))
                 .await?;
@@ -180,14 +195,70 @@ pub async fn on_error<U, E: std::fmt::Display + std::fmt::Debug>(
             prefix,
             ..
         } => {
-            tracing::warn!(
-                "Recognized prefix `{}`, but didn't recognize command name in `{}`",
-                prefix,
-                msg_content,
-            );
+            if prefix.is_empty() {
+                // Prefix-less channel (see `PrefixFrameworkOptions::dynamic_prefix`), where most
+                // messages are just regular chat
+                tracing::debug!("Didn't recognize command name in `{}`", msg_content);
+            } else {
+                tracing::warn!(
+                    "Recognized prefix `{}`, but didn't recognize command name in `{}`",
+                    prefix,
+                    msg_content,
+                );
+            }
         }
-        crate::FrameworkError::UnknownInteraction { interaction, .. } => {
+        crate::FrameworkError::UnknownInteraction {
+            ctx, interaction, ..
+        } => {
             tracing::warn!("received unknown interaction \"{}\"", interaction.data.name);
+            // Acknowledge the interaction so the user doesn't see "The application did not
+            // respond". Autocomplete interactions can't be answered with a message
+            if interaction.data.autocomplete().is_none() {
+                let response = serenity::CreateInteractionResponse::Message(
+                    serenity::CreateInteractionResponseMessage::new()
+                        .content("This command no longer exists.")
+                        .ephemeral(true),
+                );
+                interaction.create_response(ctx, response).await?;
+            }
+        }
+        crate::FrameworkError::CommandTimeout { ctx } => {
+            tracing::warn!("Command {} timed out", ctx.command().qualified_name);
+            let response = "The command took too long and was cancelled.";
+            ctx.send(CreateReply::default().content(response).ephemeral(true))
+                .await?;
+        }
+        crate::FrameworkError::CommandDisabled { ctx } => {
+            let response = "This command is disabled in this server.";
+            ctx.send(CreateReply::default().content(response).ephemeral(true))
+                .await?;
+        }
+        crate::FrameworkError::GuildSetup {
+            error,
+            guild_id,
+            ctx,
+        } => {
+            tracing::error!("Error in setup for guild {}: {}", guild_id, error);
+            let response = "Failed to set up the bot for this server. Please try again later.";
+            ctx.send(CreateReply::default().content(response).ephemeral(true))
+                .await?;
+        }
+        crate::FrameworkError::RateLimited { retry_after, ctx } => {
+            let msg = format!(
+                "You're using this command too often. Please try again in {}",
+                format_duration(retry_after)
+            );
+            ctx.send(CreateReply::default().content(msg).ephemeral(true))
+                .await?;
+        }
+        crate::FrameworkError::ConcurrencyLimitHit { ctx } => {
+            // Interactions must be answered or they show as failed. Replying to prefix commands
+            // would only add to the load
+            if let crate::Context::Application(_) = ctx {
+                let response = "The bot is busy right now, please try again in a moment";
+                ctx.send(CreateReply::default().content(response).ephemeral(true))
+                    .await?;
+            }
         }
         crate::FrameworkError::__NonExhaustive(unreachable) => match unreachable {},
     }
@@ -195,6 +266,44 @@ pub async fn on_error<U, E: std::fmt::Display + std::fmt::Debug>(
     Ok(())
 }
 
+/// Renders a duration in a human-friendly way, like `1 minute 23 seconds` or `2 days 5 hours`.
+///
+/// Sub-second remainders are rounded up, so that a cooldown which is about to expire never shows
+/// up as `0 seconds`.
+///
+/// ```rust
+/// # use std::time::Duration;
+/// assert_eq!(poise::builtins::format_duration(Duration::from_secs(3600)), "1 hour");
+/// assert_eq!(poise::builtins::format_duration(Duration::from_millis(1500)), "2 seconds");
+/// ```
+pub fn format_duration(duration: std::time::Duration) -> String {
+    let mut secs = duration.as_secs();
+    if duration.subsec_nanos() != 0 {
+        secs += 1;
+    }
+
+    let units = [
+        ("day", secs / 86400),
+        ("hour", secs / 3600 % 24),
+        ("minute", secs / 60 % 60),
+        ("second", secs % 60),
+    ];
+    let parts = units
+        .iter()
+        .filter(|(_, amount)| *amount != 0)
+        .map(|(unit, amount)| match amount {
+            1 => format!("1 {}", unit),
+            _ => format!("{} {}s", amount, unit),
+        })
+        .collect::<Vec<_>>();
+
+    if parts.is_empty() {
+        "0 seconds".into()
+    } else {
+        parts.join(" ")
+    }
+}
+
 /// An autocomplete function that can be used for the command parameter in your help function.
 ///
 /// See `examples/feature_showcase` for an example
@@ -298,3 +407,67 @@ pub async fn servers<U, E>(ctx: crate::Context<'_, U, E>) -> Result<(), serenity
     ctx.send(reply).await?;
     Ok(())
 }
+
+/// Reports the bot's uptime, the number of servers it's in, and the latency of each shard.
+///
+/// Uptime is measured from [`crate::Framework::started_at`], so it's not reset by reconnects.
+///
+/// Example:
+/// > **Uptime:** 3 days 4 hours 12 minutes 5 seconds
+/// > **Servers:** 42
+/// > **Shard latencies:**
+/// > - Shard 0: 56ms
+/// > - Shard 1: unknown
+#[cfg(feature = "cache")]
+pub async fn stats<U, E>(ctx: crate::Context<'_, U, E>) -> Result<(), serenity::Error> {
+    use std::fmt::Write as _;
+
+    let uptime = std::time::Duration::from_secs(ctx.framework().started_at.elapsed().as_secs());
+    let mut response = format!(
+        "**Uptime:** {}\n**Servers:** {}\n**Shard latencies:**\n",
+        format_duration(uptime),
+        ctx.cache().guild_count(),
+    );
+
+    let mut latencies = ctx
+        .framework()
+        .shard_manager
+        .runners
+        .lock()
+        .await
+        .iter()
+        .map(|(shard_id, runner)| (*shard_id, runner.latency))
+        .collect::<Vec<_>>();
+    latencies.sort_by_key(|(shard_id, _)| *shard_id);
+    for (shard_id, latency) in latencies {
+        let _ = match latency {
+            Some(latency) => writeln!(response, "- Shard {}: {}ms", shard_id, latency.as_millis()),
+            None => writeln!(response, "- Shard {}: unknown", shard_id),
+        };
+    }
+
+    ctx.say(response).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_duration() {
+        use std::time::Duration;
+
+        assert_eq!(
+            format_duration(Duration::from_secs(83)),
+            "1 minute 23 seconds"
+        );
+        assert_eq!(format_duration(Duration::from_secs(1)), "1 second");
+        assert_eq!(format_duration(Duration::from_secs(0)), "0 seconds");
+        assert_eq!(format_duration(Duration::from_millis(200)), "1 second");
+        assert_eq!(
+            format_duration(Duration::from_secs(2 * 86400 + 3600 + 5)),
+            "2 days 1 hour 5 seconds"
+        );
+    }
+}
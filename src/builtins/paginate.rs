@@ -9,7 +9,7 @@ use crate::serenity_prelude as serenity;
 /// - add more navigation buttons
 /// - change timeout duration
 /// - add a page selector dropdown
-/// - use reactions instead of buttons
+/// - use reactions instead of buttons (see [`paginate_reactions`])
 /// - remove message after navigation timeout
 /// - ...
 ///
@@ -37,7 +37,8 @@ pub async fn paginate<U, E>(
     pages: &[&str],
 ) -> Result<(), serenity::Error> {
     // Define some unique identifiers for the navigation buttons
-    let ctx_id = ctx.id();
+    let ctx_id = ctx.component_id_base();
+    let _claim = ctx.framework().options.claim_component_ids(ctx_id.clone());
     let prev_button_id = format!("{}prev", ctx_id);
     let next_button_id = format!("{}next", ctx_id);
 
@@ -60,7 +61,10 @@ pub async fn paginate<U, E>(
     while let Some(press) = serenity::collector::ComponentInteractionCollector::new(ctx)
         // We defined our button IDs to start with `ctx_id`. If they don't, some other command's
         // button was pressed
-        .filter(move |press| press.data.custom_id.starts_with(&ctx_id.to_string()))
+        .filter({
+            let ctx_id = ctx_id.clone();
+            move |press| press.data.custom_id.starts_with(&ctx_id)
+        })
         // Timeout when no navigation button has been pressed for 24 hours
         .timeout(std::time::Duration::from_secs(3600 * 24))
         .await
@@ -92,3 +96,486 @@ pub async fn paginate<U, E>(
 
     Ok(())
 }
+
+/// Like [`paginate`], but each page is a full [`crate::CreateReply`], so pages can consist of
+/// multiple embeds with images and fields, message content and additional components.
+///
+/// The navigation buttons are added as an extra action row below the components of each page, so
+/// pages can have at most four action rows of their own.
+///
+/// Note: this is a long-running function. It will only return once the timeout for navigation
+/// button interactions has been reached.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # async fn _test(ctx: poise::Context<'_, (), serenity::Error>) -> Result<(), serenity::Error> {
+/// # use poise::serenity_prelude as serenity;
+/// let pages = vec![
+///     poise::CreateReply::default()
+///         .embed(
+///             serenity::CreateEmbed::new()
+///                 .title("Ferris")
+///                 .image("https://rustacean.net/assets/rustacean-flat-happy.png"),
+///         )
+///         .embed(serenity::CreateEmbed::new().field("Species", "Crab", true)),
+///     poise::CreateReply::default().content("Second page"),
+/// ];
+///
+/// poise::builtins::paginate_embeds(ctx, pages).await?;
+/// # Ok(()) }
+/// ```
+pub async fn paginate_embeds<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    pages: Vec<crate::CreateReply>,
+) -> Result<(), serenity::Error> {
+    paginate_lazy(ctx, Some(pages.len()), |i| pages.get(i).cloned()).await
+}
+
+/// Like [`paginate_embeds`], but the pages are generated on demand by `page_provider`, which is
+/// called with the zero-based index of the page to show. This way, large datasets can be paginated
+/// without building all pages upfront.
+///
+/// `page_provider` returns `None` for out-of-range pages. If the total number of pages is known,
+/// pass it as `page_count`: then, going back from the first page wraps around to the last page.
+/// Otherwise, the first page stays where it is, and going forward from the last page (i.e. when
+/// `page_provider` returns `None` for the next page) wraps around to the first page either way.
+///
+/// Nothing is sent if `page_provider` returns `None` for the first page.
+///
+/// Note: this is a long-running function. It will only return once the timeout for navigation
+/// button interactions has been reached.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # async fn _test(ctx: poise::Context<'_, (), serenity::Error>) -> Result<(), serenity::Error> {
+/// # use poise::serenity_prelude as serenity;
+/// // A million square numbers, ten per page
+/// let page_size = 10;
+/// let total = 1_000_000;
+///
+/// poise::builtins::paginate_lazy(ctx, Some(total / page_size), |page| {
+///     let start = page * page_size;
+///     if start >= total {
+///         return None;
+///     }
+///     let text = (start..(start + page_size).min(total))
+///         .map(|n| format!("{}² = {}", n, n * n))
+///         .collect::<Vec<_>>()
+///         .join("\n");
+///     Some(poise::CreateReply::default().embed(serenity::CreateEmbed::new().description(text)))
+/// })
+/// .await?;
+/// # Ok(()) }
+/// ```
+pub async fn paginate_lazy<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    page_count: Option<usize>,
+    page_provider: impl FnMut(usize) -> Option<crate::CreateReply> + Send,
+) -> Result<(), serenity::Error> {
+    paginate_with_configuration(
+        ctx,
+        page_count,
+        page_provider,
+        PaginationConfiguration::default(),
+    )
+    .await
+}
+
+/// Optional configuration for the navigation of [`paginate_with_configuration`]
+pub struct PaginationConfiguration {
+    /// Whether to show ⏮ and ⏭ buttons to go to the first and last page. The ⏭ button is only
+    /// shown if the total number of pages is known
+    pub first_last_buttons: bool,
+    /// Whether to show a 🔢 button which opens a modal asking for the page number to jump to
+    pub jump_button: bool,
+    /// How long to wait for navigation after the last button press
+    pub timeout: std::time::Duration,
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl Default for PaginationConfiguration {
+    fn default() -> Self {
+        Self {
+            first_last_buttons: false,
+            jump_button: false,
+            timeout: std::time::Duration::from_secs(3600 * 24),
+            __non_exhaustive: (),
+        }
+    }
+}
+
+/// Like [`paginate_lazy`], but with configurable navigation buttons, e.g. the standard
+/// ⏮ ◀ 🔢 ▶ ⏭ set:
+///
+/// ```rust,no_run
+/// # async fn _test(ctx: poise::Context<'_, (), serenity::Error>) -> Result<(), serenity::Error> {
+/// let pages = (1..=100)
+///     .map(|i| poise::CreateReply::default().content(format!("Page {}", i)))
+///     .collect::<Vec<_>>();
+///
+/// let config = poise::builtins::PaginationConfiguration {
+///     first_last_buttons: true,
+///     jump_button: true,
+///     ..Default::default()
+/// };
+/// poise::builtins::paginate_with_configuration(ctx, Some(pages.len()), |i| pages.get(i).cloned(), config)
+///     .await?;
+/// # Ok(()) }
+/// ```
+///
+/// Page numbers entered into the jump modal are clamped to the existing pages if the total number
+/// of pages is known. Invalid page numbers, or pages for which `page_provider` returns `None`, are
+/// answered with an ephemeral error message.
+pub async fn paginate_with_configuration<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    page_count: Option<usize>,
+    mut page_provider: impl FnMut(usize) -> Option<crate::CreateReply> + Send,
+    config: PaginationConfiguration,
+) -> Result<(), serenity::Error> {
+    // Define some unique identifiers for the navigation buttons
+    let ctx_id = ctx.component_id_base();
+    let _claim = ctx.framework().options.claim_component_ids(ctx_id.clone());
+    let first_button_id = format!("{}first", ctx_id);
+    let prev_button_id = format!("{}prev", ctx_id);
+    let jump_button_id = format!("{}jump", ctx_id);
+    let next_button_id = format!("{}next", ctx_id);
+    let last_button_id = format!("{}last", ctx_id);
+    let jump_modal_id = format!("{}jump_modal", ctx_id);
+    let jump_input_id = "page";
+
+    // Appends the navigation buttons to the components of the page
+    let with_navigation = |mut page: crate::CreateReply| {
+        let mut buttons = Vec::new();
+        if config.first_last_buttons {
+            buttons.push(serenity::CreateButton::new(&first_button_id).emoji('⏮'));
+        }
+        buttons.push(serenity::CreateButton::new(&prev_button_id).emoji('◀'));
+        if config.jump_button {
+            buttons.push(serenity::CreateButton::new(&jump_button_id).emoji('🔢'));
+        }
+        buttons.push(serenity::CreateButton::new(&next_button_id).emoji('▶'));
+        if config.first_last_buttons && page_count.is_some() {
+            buttons.push(serenity::CreateButton::new(&last_button_id).emoji('⏭'));
+        }
+
+        let mut components = page.components.take().unwrap_or_default();
+        components.push(serenity::CreateActionRow::Buttons(buttons));
+        page.components(components)
+    };
+
+    // Send the first page
+    let first_page = match page_provider(0) {
+        Some(page) => page,
+        None => return Ok(()),
+    };
+    ctx.send(with_navigation(first_page)).await?;
+
+    // Unlike ComponentInteractionCollector, this receives submissions of the jump modal too
+    let mut interactions = Box::pin(serenity::collector::collect(
+        &ctx.serenity_context().shard,
+        {
+            // We defined our button and modal IDs to start with `ctx_id`. If they don't, some
+            // other command's button was pressed
+            let ctx_id = ctx_id.clone();
+            move |event| match event {
+                serenity::Event::InteractionCreate(event) => {
+                    let custom_id = match &event.interaction {
+                        serenity::Interaction::Component(press) => &press.data.custom_id,
+                        serenity::Interaction::Modal(submit) => &submit.data.custom_id,
+                        _ => return None,
+                    };
+                    custom_id
+                        .starts_with(&ctx_id)
+                        .then(|| event.interaction.clone())
+                }
+                _ => None,
+            }
+        },
+    ));
+
+    // Loop through incoming interactions with the navigation buttons and the jump modal
+    let mut current_page: usize = 0;
+    while let Ok(Some(interaction)) = tokio::time::timeout(
+        config.timeout,
+        futures_util::StreamExt::next(&mut interactions),
+    )
+    .await
+    {
+        // Depending on which button was pressed, go to next or previous page, or to the page
+        // entered into the modal
+        let (target_page, custom_id) = match &interaction {
+            serenity::Interaction::Component(press) if press.data.custom_id == jump_button_id => {
+                let placeholder = match page_count {
+                    Some(page_count) => format!("1-{}", page_count),
+                    None => "1".to_owned(),
+                };
+                let input = serenity::CreateInputText::new(
+                    serenity::InputTextStyle::Short,
+                    "Page",
+                    jump_input_id,
+                )
+                .placeholder(placeholder);
+                press
+                    .create_response(
+                        ctx.serenity_context(),
+                        serenity::CreateInteractionResponse::Modal(
+                            serenity::CreateModal::new(&jump_modal_id, "Jump to page")
+                                .components(vec![serenity::CreateActionRow::InputText(input)]),
+                        ),
+                    )
+                    .await?;
+                continue;
+            }
+            serenity::Interaction::Component(press) => {
+                let custom_id = &press.data.custom_id;
+                let target_page = if *custom_id == next_button_id {
+                    Some(current_page + 1)
+                } else if *custom_id == prev_button_id {
+                    current_page
+                        .checked_sub(1)
+                        .or_else(|| page_count.and_then(|count| count.checked_sub(1)))
+                } else if *custom_id == first_button_id {
+                    Some(0)
+                } else if *custom_id == last_button_id {
+                    page_count.and_then(|count| count.checked_sub(1))
+                } else {
+                    // This is an unrelated button interaction
+                    continue;
+                };
+                (target_page, custom_id)
+            }
+            serenity::Interaction::Modal(submit) if submit.data.custom_id == jump_modal_id => {
+                let input = crate::find_modal_text(&mut submit.data.clone(), jump_input_id);
+                let target_page = input
+                    .as_deref()
+                    .and_then(|input| parse_page_number(input, page_count));
+                let target_page = match target_page {
+                    Some(target_page) => target_page,
+                    None => {
+                        let error =
+                            format!("`{}` is not a valid page number", input.unwrap_or_default());
+                        respond_ephemeral(ctx, &interaction, error).await?;
+                        continue;
+                    }
+                };
+                (Some(target_page), &submit.data.custom_id)
+            }
+            _ => continue,
+        };
+
+        let mut page = target_page.and_then(|i| Some((i, page_provider(i)?)));
+        if page.is_none() && *custom_id == next_button_id {
+            // Went past the last page, so wrap around to the first page
+            page = page_provider(0).map(|page| (0, page));
+        }
+        let mut page = match (page, target_page) {
+            (Some((i, page)), _) => {
+                current_page = i;
+                page
+            }
+            (None, Some(target_page)) if *custom_id == jump_modal_id => {
+                let error = format!("Page {} doesn't exist", target_page + 1);
+                respond_ephemeral(ctx, &interaction, error).await?;
+                continue;
+            }
+            (None, _) => {
+                // There's nowhere to go, so just acknowledge the button press
+                respond(
+                    ctx,
+                    &interaction,
+                    serenity::CreateInteractionResponse::Acknowledge,
+                )
+                .await?;
+                continue;
+            }
+        };
+
+        // Update the message with the new page contents. Without content, the content of the
+        // previous page would stay. The modal was opened from a button, so its submission can
+        // update the button's message as well
+        page.content.get_or_insert_with(String::new);
+        page.ephemeral = None;
+        let response = with_navigation(page)
+            .to_slash_initial_response(serenity::CreateInteractionResponseMessage::new());
+        respond(
+            ctx,
+            &interaction,
+            serenity::CreateInteractionResponse::UpdateMessage(response),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Converts a page number entered by the user to a zero-based page index, clamped to the existing
+/// pages if their number is known
+fn parse_page_number(input: &str, page_count: Option<usize>) -> Option<usize> {
+    let page = input.trim().parse::<usize>().ok()?.saturating_sub(1);
+    Some(match page_count {
+        Some(page_count) => page.min(page_count.saturating_sub(1)),
+        None => page,
+    })
+}
+
+/// Responds to a component interaction or modal submission
+async fn respond<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    interaction: &serenity::Interaction,
+    response: serenity::CreateInteractionResponse,
+) -> Result<(), serenity::Error> {
+    match interaction {
+        serenity::Interaction::Component(press) => {
+            press
+                .create_response(ctx.serenity_context(), response)
+                .await
+        }
+        serenity::Interaction::Modal(submit) => {
+            submit
+                .create_response(ctx.serenity_context(), response)
+                .await
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Responds to a component interaction or modal submission with an ephemeral message
+async fn respond_ephemeral<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    interaction: &serenity::Interaction,
+    content: String,
+) -> Result<(), serenity::Error> {
+    let response = serenity::CreateInteractionResponse::Message(
+        serenity::CreateInteractionResponseMessage::new()
+            .content(content)
+            .ephemeral(true),
+    );
+    respond(ctx, interaction, response).await
+}
+
+/// Like [`paginate`], but navigates with ◀ and ▶ reactions instead of buttons, for bots or guilds
+/// where components aren't desired.
+///
+/// Only reactions by the command author are considered. The bot needs the `ADD_REACTIONS`
+/// permission; otherwise, adding the navigation reactions fails and the error is returned. With
+/// the `MANAGE_MESSAGES` permission, the author's reaction is removed again after each page turn,
+/// so they can click the same reaction repeatedly. Without it (or in DMs, where bots can't remove
+/// reactions of others), removing a reaction turns the page as well, so each click still counts.
+///
+/// Requires the `GUILD_MESSAGE_REACTIONS` or `DIRECT_MESSAGE_REACTIONS` intent, respectively.
+///
+/// Note: this is a long-running function. It will only return once the timeout for navigation
+/// reactions has been reached.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # async fn _test(ctx: poise::Context<'_, (), serenity::Error>) -> Result<(), serenity::Error> {
+/// let pages = &["Content of first page", "Content of second page"];
+///
+/// poise::builtins::paginate_reactions(ctx, pages).await?;
+/// # Ok(()) }
+/// ```
+pub async fn paginate_reactions<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    pages: &[&str],
+) -> Result<(), serenity::Error> {
+    const PREV: &str = "◀";
+    const NEXT: &str = "▶";
+
+    // Send the embed with the first page as content
+    let reply =
+        crate::CreateReply::default().embed(serenity::CreateEmbed::default().description(pages[0]));
+    let reply_handle = ctx.send(reply).await?;
+    let message = reply_handle.message().await?;
+    let message_id = message.id;
+    for emoji in [PREV, NEXT] {
+        message
+            .react(
+                ctx.serenity_context(),
+                serenity::ReactionType::Unicode(emoji.into()),
+            )
+            .await?;
+    }
+
+    // Unlike ReactionCollector, this receives reaction removals too
+    let author_id = ctx.author().id;
+    let mut reactions = Box::pin(serenity::collector::collect(
+        &ctx.serenity_context().shard,
+        move |event| {
+            let (reaction, added) = match event {
+                serenity::Event::ReactionAdd(event) => (&event.reaction, true),
+                serenity::Event::ReactionRemove(event) => (&event.reaction, false),
+                _ => return None,
+            };
+            if reaction.message_id != message_id || reaction.user_id != Some(author_id) {
+                return None;
+            }
+            Some((reaction.clone(), added))
+        },
+    ));
+
+    // Removals of the author's reactions that were done by us and must not turn the page
+    let mut own_removals = 0;
+    let mut current_page = 0;
+    // Timeout when no navigation reaction has been added for 24 hours
+    let timeout = std::time::Duration::from_secs(3600 * 24);
+    while let Ok(Some((reaction, added))) =
+        tokio::time::timeout(timeout, futures_util::StreamExt::next(&mut reactions)).await
+    {
+        let emoji = match &reaction.emoji {
+            serenity::ReactionType::Unicode(emoji) => emoji.as_str(),
+            // This is an unrelated reaction
+            _ => continue,
+        };
+        let is_navigation = emoji == PREV || emoji == NEXT;
+        if !added && is_navigation && own_removals > 0 {
+            own_removals -= 1;
+            continue;
+        }
+
+        // Depending on which reaction was used, go to next or previous page
+        if emoji == NEXT {
+            current_page += 1;
+            if current_page >= pages.len() {
+                current_page = 0;
+            }
+        } else if emoji == PREV {
+            current_page = current_page.checked_sub(1).unwrap_or(pages.len() - 1);
+        } else {
+            continue;
+        }
+
+        // Update the message with the new page contents
+        let reply = crate::CreateReply::default()
+            .embed(serenity::CreateEmbed::new().description(pages[current_page]));
+        reply_handle.edit(ctx, reply).await?;
+
+        // Fails without MANAGE_MESSAGES, in which case the author removes the reaction themselves
+        if added && reaction.delete(ctx.serenity_context()).await.is_ok() {
+            own_removals += 1;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_page_number() {
+        assert_eq!(parse_page_number("1", Some(10)), Some(0));
+        assert_eq!(parse_page_number(" 5 ", Some(10)), Some(4));
+        assert_eq!(parse_page_number("0", Some(10)), Some(0));
+        assert_eq!(parse_page_number("11", Some(10)), Some(9));
+        assert_eq!(parse_page_number("1000", None), Some(999));
+        assert_eq!(parse_page_number("-1", Some(10)), None);
+        assert_eq!(parse_page_number("first", Some(10)), None);
+        assert_eq!(parse_page_number("", None), None);
+    }
+}
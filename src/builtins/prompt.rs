@@ -0,0 +1,111 @@
+//! Text input prompt via a button and a modal, e.g. for multi-step commands
+
+use crate::serenity_prelude as serenity;
+
+/// Sends a message with the given prompt and a button, then waits for the command author to click
+/// it. The click opens a modal with a single text input, whose submitted text is returned.
+///
+/// Unlike [`crate::execute_modal`], this doesn't have to be the first response to a command, so
+/// it can be used as a later step in slash and prefix commands alike.
+///
+/// Returns `None` if the author didn't click the button or didn't submit the modal within
+/// `timeout` (each), or submitted an empty text. Either way, the button is removed afterwards.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # async fn _test(ctx: poise::Context<'_, (), serenity::Error>) -> Result<(), serenity::Error> {
+/// let timeout = std::time::Duration::from_secs(60);
+/// if let Some(reason) = poise::builtins::prompt_text(ctx, "Why?", timeout).await? {
+///     ctx.say(format!("You said: {}", reason)).await?;
+/// }
+/// # Ok(()) }
+/// ```
+pub async fn prompt_text<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    label: impl Into<String>,
+    timeout: std::time::Duration,
+) -> Result<Option<String>, serenity::Error> {
+    let label = label.into();
+
+    // Define some unique identifiers for the button and the modal
+    let ctx_id = ctx.component_id_base();
+    let _claim = ctx.framework().options.claim_component_ids(ctx_id.clone());
+    let button_id = format!("{}prompt", ctx_id);
+    let modal_id = format!("{}prompt_modal", ctx_id);
+    let input_id = "text";
+
+    let reply = {
+        let components =
+            serenity::CreateActionRow::Buttons(vec![serenity::CreateButton::new(&button_id)
+                .label("Answer")
+                .style(serenity::ButtonStyle::Primary)]);
+
+        crate::CreateReply::default()
+            .content(label.clone())
+            .components(vec![components])
+    };
+
+    let reply_handle = ctx.send(reply).await?;
+    let remove_button = || {
+        reply_handle.edit(
+            ctx,
+            crate::CreateReply::default()
+                .content(label.clone())
+                .components(Vec::new()),
+        )
+    };
+
+    let press_id = button_id.clone();
+    let press = serenity::collector::ComponentInteractionCollector::new(ctx)
+        .author_id(ctx.author().id)
+        .filter(move |press| press.data.custom_id == press_id)
+        .timeout(timeout)
+        .await;
+    let press = match press {
+        Some(press) => press,
+        None => {
+            remove_button().await?;
+            return Ok(None);
+        }
+    };
+
+    // Modal titles and input labels are limited to 45 characters
+    let title = label.chars().take(45).collect::<String>();
+    let input =
+        serenity::CreateInputText::new(serenity::InputTextStyle::Paragraph, "Answer", input_id);
+    press
+        .create_response(
+            ctx.serenity_context(),
+            serenity::CreateInteractionResponse::Modal(
+                serenity::CreateModal::new(&modal_id, title)
+                    .components(vec![serenity::CreateActionRow::InputText(input)]),
+            ),
+        )
+        .await?;
+
+    let response = serenity::collector::ModalInteractionCollector::new(ctx)
+        .author_id(ctx.author().id)
+        .filter(move |response| response.data.custom_id == modal_id)
+        .timeout(timeout)
+        .await;
+    let mut response = match response {
+        Some(response) => response,
+        None => {
+            remove_button().await?;
+            return Ok(None);
+        }
+    };
+
+    // The modal was opened from the button, so the submission can update the button's message
+    response
+        .create_response(
+            ctx.serenity_context(),
+            serenity::CreateInteractionResponse::UpdateMessage(
+                serenity::CreateInteractionResponseMessage::new().components(Vec::new()),
+            ),
+        )
+        .await?;
+
+    Ok(crate::find_modal_text(&mut response.data, input_id))
+}
@@ -16,9 +16,53 @@ use crate::serenity_prelude as serenity;
 /// serenity::Command::set_global_commands(ctx, create_commands).await?;
 /// # Ok(()) }
 /// ```
+///
+/// Commands with [`crate::Command::registration_guilds`] are left out, since they're only
+/// registered in those guilds.
 pub fn create_application_commands<U, E>(
     commands: &[crate::Command<U, E>],
 ) -> Vec<serenity::CreateCommand> {
+    create_application_commands_filtered(commands, None, |command| is_in_scope(command, None))
+}
+
+/// Like [`create_application_commands`], but leaves out commands which have been
+/// [disabled](crate::Command::set_enabled_in_guild) in the given guild and applies
+/// [guild overrides](crate::Command::set_guild_override)
+///
+/// Commands with [`crate::Command::registration_guilds`] are only included if the list contains
+/// the given guild.
+pub fn create_application_commands_for_guild<U, E>(
+    commands: &[crate::Command<U, E>],
+    guild_id: serenity::GuildId,
+) -> Vec<serenity::CreateCommand> {
+    create_application_commands_filtered(commands, Some(guild_id), |command| {
+        is_in_scope(command, Some(guild_id))
+    })
+}
+
+/// Whether a top-level command belongs into the global command list (`guild_id` is None) or into
+/// the command list of the given guild, according to [`crate::Command::registration_guilds`]
+fn is_in_scope<U, E>(command: &crate::Command<U, E>, guild_id: Option<serenity::GuildId>) -> bool {
+    match guild_id {
+        None => command.registration_guilds.is_empty(),
+        Some(guild_id) => {
+            command.registration_guilds.is_empty()
+                || command.registration_guilds.contains(&guild_id)
+        }
+    }
+}
+
+/// Shared implementation of [`create_application_commands`] and
+/// [`create_application_commands_for_guild`]. `in_scope` is only applied to top-level commands
+fn create_application_commands_filtered<U, E>(
+    commands: &[crate::Command<U, E>],
+    guild_id: Option<serenity::GuildId>,
+    in_scope: impl Fn(&crate::Command<U, E>) -> bool,
+) -> Vec<serenity::CreateCommand> {
+    let filter = |command: &crate::Command<U, E>| {
+        guild_id.map_or(true, |guild_id| command.is_enabled_in_guild(guild_id))
+    };
+
     /// We decided to extract context menu commands recursively, despite the subcommand hierarchy
     /// not being preserved. Because it's more confusing to just silently discard context menu
     /// commands if they're not top-level commands.
@@ -26,29 +70,385 @@ pub fn create_application_commands<U, E>(
     fn recursively_add_context_menu_commands<U, E>(
         builder: &mut Vec<serenity::CreateCommand>,
         command: &crate::Command<U, E>,
+        filter: &impl Fn(&crate::Command<U, E>) -> bool,
     ) {
+        if !filter(command) {
+            return;
+        }
         if let Some(context_menu_command) = command.create_as_context_menu_command() {
             builder.push(context_menu_command);
         }
         for subcommand in &command.subcommands {
-            recursively_add_context_menu_commands(builder, subcommand);
+            recursively_add_context_menu_commands(builder, subcommand, filter);
         }
     }
 
     let mut commands_builder = Vec::with_capacity(commands.len());
     for command in commands {
-        if let Some(slash_command) = command.create_as_slash_command() {
+        if !filter(command) || !in_scope(command) {
+            continue;
+        }
+        if let Some(mut slash_command) = command.create_as_slash_command() {
+            if let Some(guild_override) = guild_id.and_then(|id| command.guild_override(id)) {
+                if let Some(name) = guild_override.name {
+                    slash_command = slash_command.name(name);
+                }
+                if let Some(description) = guild_override.description {
+                    slash_command = slash_command.description(description);
+                }
+            }
             commands_builder.push(slash_command);
         }
-        recursively_add_context_menu_commands(&mut commands_builder, command);
+        recursively_add_context_menu_commands(&mut commands_builder, command, &filter);
     }
     commands_builder
 }
 
+/// A command or parameter name or description that Discord would reject, as returned by
+/// [`validate_application_commands`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidCommandName {
+    /// What is invalid, e.g. `"command name"` or `"description of parameter"`
+    pub kind: &'static str,
+    /// The offending name, or the name of the command or parameter with the offending description
+    pub name: String,
+    /// Explanation of which rule is violated
+    pub reason: &'static str,
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl std::fmt::Display for InvalidCommandName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} `{}` {}", self.kind, self.name, self.reason)
+    }
+}
+
+impl std::error::Error for InvalidCommandName {}
+
+/// Checks a single name against Discord's application command naming rules
+fn validate_name(
+    kind: &'static str,
+    name: &str,
+    is_chat_input: bool,
+) -> Result<(), InvalidCommandName> {
+    let error = |reason| InvalidCommandName {
+        kind,
+        name: name.to_owned(),
+        reason,
+        __non_exhaustive: (),
+    };
+
+    if !(1..=32).contains(&name.chars().count()) {
+        return Err(error("must be between 1 and 32 characters long"));
+    }
+    if !is_chat_input {
+        // Context menu command names may contain any characters, including spaces and uppercase
+        return Ok(());
+    }
+    // Discord documents this regex: `^[-_\p{L}\p{N}\p{sc=Deva}\p{sc=Thai}]{1,32}$`. Combining
+    // marks (`\p{M}`) are accepted too, since Devanagari and Thai names, among others, need them
+    static NAME_CHARACTERS: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+    let name_characters = NAME_CHARACTERS.get_or_init(|| {
+        regex::Regex::new(r"^[-_\p{L}\p{M}\p{N}\p{sc=Deva}\p{sc=Thai}]+$").unwrap()
+    });
+    if !name_characters.is_match(name) {
+        return Err(error("contains invalid characters"));
+    }
+    if name.chars().any(char::is_uppercase) {
+        return Err(error("must be lowercase"));
+    }
+    Ok(())
+}
+
+/// Checks a single slash command or parameter description against Discord's length limit
+fn validate_description(
+    kind: &'static str,
+    name: &str,
+    description: &str,
+) -> Result<(), InvalidCommandName> {
+    if !(1..=100).contains(&description.chars().count()) {
+        return Err(InvalidCommandName {
+            kind,
+            name: name.to_owned(),
+            reason: "must be between 1 and 100 characters long",
+            __non_exhaustive: (),
+        });
+    }
+    Ok(())
+}
+
+/// Checks all command and parameter names, including localized ones, against Discord's naming
+/// rules for application commands.
+///
+/// Slash command and parameter names must match `^[-_\p{L}\p{N}\p{sc=Deva}\p{sc=Thai}]{1,32}$`
+/// and be lowercase. Context menu command names only need to be between 1 and 32 characters
+/// long. Slash command and parameter descriptions, including localized ones, must be between 1
+/// and 100 characters long. Discord rejects the entire registration with an HTTP 400 if any name
+/// or description is invalid, so it's worth catching this early. [`crate::Framework`] runs this
+/// check on startup and logs the result.
+///
+/// Missing descriptions are not an error since poise substitutes a placeholder; see
+/// [`missing_parameter_descriptions`] to find them.
+///
+/// ```rust
+/// #[poise::command(slash_command, rename = "Ban Hammer")]
+/// async fn ban(ctx: poise::Context<'_, (), ()>) -> Result<(), ()> { Ok(()) }
+///
+/// let error = poise::builtins::validate_application_commands(&[ban()]).unwrap_err();
+/// assert_eq!(
+///     error.to_string(),
+///     "command name `Ban Hammer` contains invalid characters",
+/// );
+/// ```
+pub fn validate_application_commands<U, E>(
+    commands: &[crate::Command<U, E>],
+) -> Result<(), InvalidCommandName> {
+    for command in commands {
+        if command.slash_action.is_some() {
+            validate_name("command name", &command.name, true)?;
+            for name in command.name_localizations.values() {
+                validate_name("localized command name", name, true)?;
+            }
+            let descriptions = command
+                .description
+                .iter()
+                .chain(command.description_localizations.values());
+            for description in descriptions {
+                validate_description("description of command", &command.name, description)?;
+            }
+            for guild_override in command.guild_overrides.read().unwrap().values() {
+                if let Some(name) = &guild_override.name {
+                    validate_name("overridden command name", name, true)?;
+                }
+                if let Some(description) = &guild_override.description {
+                    validate_description(
+                        "overridden description of command",
+                        &command.name,
+                        description,
+                    )?;
+                }
+            }
+            for parameter in &command.parameters {
+                validate_name("parameter name", &parameter.name, true)?;
+                for name in parameter.name_localizations.values() {
+                    validate_name("localized parameter name", name, true)?;
+                }
+                let descriptions = parameter
+                    .description
+                    .iter()
+                    .chain(parameter.description_localizations.values());
+                for description in descriptions {
+                    validate_description("description of parameter", &parameter.name, description)?;
+                }
+            }
+        }
+        if command.context_menu_action.is_some() {
+            let name = command
+                .context_menu_name
+                .as_deref()
+                .unwrap_or(&command.name);
+            validate_name("context menu command name", name, false)?;
+        }
+        validate_application_commands(&command.subcommands)?;
+    }
+    Ok(())
+}
+
+/// Returns the qualified command name and parameter name of every slash command parameter
+/// which has no description.
+///
+/// Discord requires a description for every parameter, so poise registers those with a generic
+/// placeholder text. That's rarely intended; [`crate::Framework`] logs a warning for each of them
+/// on startup. Use `#[description = "..."]` on the parameter to add one.
+///
+/// ```rust
+/// #[poise::command(slash_command)]
+/// async fn ban(
+///     ctx: poise::Context<'_, (), ()>,
+///     #[description = "User to ban"] user: String,
+///     reason: Option<String>,
+/// ) -> Result<(), ()> { Ok(()) }
+///
+/// assert_eq!(
+///     poise::builtins::missing_parameter_descriptions(&[ban()]),
+///     vec![("ban".to_string(), "reason".to_string())],
+/// );
+/// ```
+pub fn missing_parameter_descriptions<U, E>(
+    commands: &[crate::Command<U, E>],
+) -> Vec<(String, String)> {
+    let mut missing = Vec::new();
+    for command in commands {
+        if command.slash_action.is_some() {
+            for parameter in &command.parameters {
+                if parameter.description.is_none() {
+                    missing.push((command.qualified_name.clone(), parameter.name.clone()));
+                }
+            }
+        }
+        missing.extend(missing_parameter_descriptions(&command.subcommands));
+    }
+    missing
+}
+
+/// Returns the names and types of all application commands that
+/// [`create_application_commands_filtered`] would create
+fn application_command_names<U, E>(
+    commands: &[crate::Command<U, E>],
+    guild_id: Option<serenity::GuildId>,
+) -> Vec<(String, serenity::CommandType)> {
+    /// Mirrors `recursively_add_context_menu_commands`
+    fn recursively_add_context_menu_names<U, E>(
+        names: &mut Vec<(String, serenity::CommandType)>,
+        command: &crate::Command<U, E>,
+        filter: &impl Fn(&crate::Command<U, E>) -> bool,
+    ) {
+        if !filter(command) {
+            return;
+        }
+        let kind = match command.context_menu_action {
+            Some(crate::ContextMenuCommandAction::User(_)) => Some(serenity::CommandType::User),
+            Some(crate::ContextMenuCommandAction::Message(_)) => {
+                Some(serenity::CommandType::Message)
+            }
+            _ => None,
+        };
+        if let Some(kind) = kind {
+            let name = command
+                .context_menu_name
+                .as_deref()
+                .unwrap_or(&command.name);
+            names.push((name.to_owned(), kind));
+        }
+        for subcommand in &command.subcommands {
+            recursively_add_context_menu_names(names, subcommand, filter);
+        }
+    }
+
+    let filter = |command: &crate::Command<U, E>| {
+        guild_id.map_or(true, |guild_id| command.is_enabled_in_guild(guild_id))
+    };
+    let mut names = Vec::new();
+    for command in commands {
+        if !filter(command) || !is_in_scope(command, guild_id) {
+            continue;
+        }
+        if command.slash_action.is_some() {
+            let name = command.name_in_guild(guild_id).into_owned();
+            names.push((name, serenity::CommandType::ChatInput));
+        }
+        recursively_add_context_menu_names(&mut names, command, &filter);
+    }
+    names
+}
+
+/// Fetches the application commands registered on Discord which are missing from the given list
+/// of commands, i.e. leftovers from commands that you removed from your code.
+///
+/// Looks at global commands if `guild_id` is None, otherwise at the commands of that guild. In the
+/// latter case, commands [disabled](crate::Command::set_enabled_in_guild) in the guild count as
+/// missing too.
+///
+/// You don't need to delete these manually: [`register_globally`], [`register_in_guild`] and
+/// [`register_scoped`] overwrite the entire list of registered commands, which removes stale ones,
+/// and [`register_in_scope`] does so if asked to. Use this function to log them beforehand, for
+/// example:
+/// ```rust,no_run
+/// # async fn _f(http: &poise::serenity_prelude::Http, commands: &[poise::Command<(), ()>]) -> Result<(), poise::serenity_prelude::Error> {
+/// for command in poise::builtins::find_stale_application_commands(http, commands, None).await? {
+///     tracing::info!("Deleting stale command {}", command.name);
+/// }
+/// poise::builtins::register_globally(http, commands).await?;
+/// # Ok(()) }
+/// ```
+pub async fn find_stale_application_commands<U, E>(
+    http: impl AsRef<serenity::Http>,
+    commands: &[crate::Command<U, E>],
+    guild_id: Option<serenity::GuildId>,
+) -> Result<Vec<serenity::Command>, serenity::Error> {
+    let registered = match guild_id {
+        Some(guild_id) => guild_id.get_commands(http).await?,
+        None => serenity::Command::get_global_commands(http).await?,
+    };
+    let names = application_command_names(commands, guild_id);
+    Ok(registered
+        .into_iter()
+        .filter(|registered| {
+            !names
+                .iter()
+                .any(|(name, kind)| *name == registered.name && *kind == registered.kind)
+        })
+        .collect())
+}
+
+/// Registers the given list of application commands globally if `guild_id` is None, or else in
+/// that guild, like [`register_globally`] and [`register_in_guild`].
+///
+/// Commands which are registered in the scope but missing from the list (see
+/// [`find_stale_application_commands`]) are kept unless `delete_stale_commands` is true. In that
+/// case, each of them is logged and deleted. Commands outside of the scope are never touched.
+///
+/// [`register_application_commands`] and [`register_application_commands_buttons`] use this with
+/// [`crate::FrameworkOptions::delete_stale_commands`].
+pub async fn register_in_scope<U, E>(
+    http: impl AsRef<serenity::Http>,
+    commands: &[crate::Command<U, E>],
+    guild_id: Option<serenity::GuildId>,
+    delete_stale_commands: bool,
+) -> Result<(), serenity::Error> {
+    let builder = match guild_id {
+        Some(guild_id) => create_application_commands_for_guild(commands, guild_id),
+        None => create_application_commands(commands),
+    };
+    let stale = find_stale_application_commands(&http, commands, guild_id).await?;
+
+    // The registered commands are overwritten as a whole, so stale commands are deleted by
+    // leaving them out and kept by registering them again unchanged
+    let mut payload = Vec::new();
+    for command in builder {
+        payload.push(serenity::json::to_value(command)?);
+    }
+    for command in kept_stale_commands(stale, delete_stale_commands) {
+        payload.push(serenity::json::to_value(command)?);
+    }
+    match guild_id {
+        Some(guild_id) => {
+            http.as_ref()
+                .create_guild_commands(guild_id, &payload)
+                .await?
+        }
+        None => http.as_ref().create_global_commands(&payload).await?,
+    };
+    Ok(())
+}
+
+/// Returns the stale commands which [`register_in_scope`] registers again, and logs the others
+/// as deleted
+fn kept_stale_commands(
+    stale: Vec<serenity::Command>,
+    delete_stale_commands: bool,
+) -> Vec<serenity::Command> {
+    if !delete_stale_commands {
+        return stale;
+    }
+    for command in stale {
+        tracing::info!(
+            "Deleting stale application command `{}` ({:?})",
+            command.name,
+            command.kind
+        );
+    }
+    Vec::new()
+}
+
 /// Registers the given list of application commands to Discord as global commands.
 ///
 /// Thin wrapper around [`create_application_commands`] that funnels the returned builder into
 /// [`serenity::Command::set_global_commands`].
+///
+/// This replaces all global commands, so commands that are registered on Discord but missing from
+/// the list are deleted. See [`find_stale_application_commands`].
 pub async fn register_globally<U, E>(
     http: impl AsRef<serenity::Http>,
     commands: &[crate::Command<U, E>],
@@ -60,18 +460,67 @@ pub async fn register_globally<U, E>(
 
 /// Registers the given list of application commands to Discord as guild-specific commands.
 ///
-/// Thin wrapper around [`create_application_commands`] that funnels the returned builder into
-/// [`serenity::GuildId::set_commands`].
+/// Thin wrapper around [`create_application_commands_for_guild`] that funnels the returned builder
+/// into [`serenity::GuildId::set_commands`].
+///
+/// This replaces all commands of the guild, so commands that are registered in the guild but
+/// missing from the list are deleted. Global commands are unaffected. See
+/// [`find_stale_application_commands`].
 pub async fn register_in_guild<U, E>(
     http: impl AsRef<serenity::Http>,
     commands: &[crate::Command<U, E>],
     guild_id: serenity::GuildId,
 ) -> Result<(), serenity::Error> {
-    let builder = create_application_commands(commands);
+    let builder = create_application_commands_for_guild(commands, guild_id);
     guild_id.set_commands(http, builder).await?;
     Ok(())
 }
 
+/// Registers commands with [`crate::Command::registration_guilds`] in each of their listed
+/// guilds, and all other commands globally.
+///
+/// Each guild only receives the commands listed for it, so commands that were registered in the
+/// guild otherwise, e.g. with [`register_in_guild`], are deleted. If registering in a guild fails,
+/// for example because the bot isn't in that guild anymore, the error is logged and the remaining
+/// guilds are processed anyway. Errors in global registration are returned.
+///
+/// ```rust,no_run
+/// # use poise::serenity_prelude as serenity;
+/// #[poise::command(slash_command, registration_guilds(123456789012345678))]
+/// async fn premium(ctx: poise::Context<'_, (), ()>) -> Result<(), ()> { Ok(()) }
+///
+/// # async fn _test(ctx: &serenity::Context) -> Result<(), serenity::Error> {
+/// let commands = vec![premium()];
+/// poise::builtins::register_scoped(ctx, &commands).await?;
+/// # Ok(()) }
+/// ```
+pub async fn register_scoped<U, E>(
+    http: impl AsRef<serenity::Http>,
+    commands: &[crate::Command<U, E>],
+) -> Result<(), serenity::Error> {
+    register_globally(&http, commands).await?;
+
+    let mut guild_ids = commands
+        .iter()
+        .flat_map(|command| command.registration_guilds.iter().copied())
+        .collect::<Vec<_>>();
+    guild_ids.sort();
+    guild_ids.dedup();
+    for guild_id in guild_ids {
+        let builder = create_application_commands_filtered(commands, Some(guild_id), |command| {
+            command.registration_guilds.contains(&guild_id)
+        });
+        if let Err(error) = guild_id.set_commands(&http, builder).await {
+            tracing::warn!(
+                "Failed to register commands in guild {}: {}",
+                guild_id,
+                error
+            );
+        }
+    }
+    Ok(())
+}
+
 /// _Note: you probably want [`register_application_commands_buttons`] instead; it's easier and more
 /// powerful_
 ///
@@ -95,13 +544,15 @@ pub async fn register_application_commands<U, E>(
         return Ok(());
     }
 
-    let commands_builder = create_application_commands(&ctx.framework().options().commands);
-    let num_commands = commands_builder.len();
+    let commands = &ctx.framework().options().commands;
+    let delete_stale_commands = ctx.framework().options().delete_stale_commands;
 
     if global {
+        let num_commands = create_application_commands(commands).len();
+
         ctx.say(format!("Registering {num_commands} commands...",))
             .await?;
-        serenity::Command::set_global_commands(ctx, commands_builder).await?;
+        register_in_scope(ctx, commands, None, delete_stale_commands).await?;
     } else {
         let guild_id = match ctx.guild_id() {
             Some(x) => x,
@@ -110,10 +561,11 @@ pub async fn register_application_commands<U, E>(
                 return Ok(());
             }
         };
+        let num_commands = create_application_commands_for_guild(commands, guild_id).len();
 
         ctx.say(format!("Registering {num_commands} commands..."))
             .await?;
-        guild_id.set_commands(ctx, commands_builder).await?;
+        register_in_scope(ctx, commands, Some(guild_id), delete_stale_commands).await?;
     }
 
     ctx.say("Done!").await?;
@@ -151,8 +603,9 @@ pub async fn register_application_commands<U, E>(
 pub async fn register_application_commands_buttons<U, E>(
     ctx: crate::Context<'_, U, E>,
 ) -> Result<(), serenity::Error> {
-    let create_commands = create_application_commands(&ctx.framework().options().commands);
-    let num_commands = create_commands.len();
+    let commands = &ctx.framework().options().commands;
+    let delete_stale_commands = ctx.framework().options().delete_stale_commands;
+    let num_commands = create_application_commands(commands).len();
 
     let is_bot_owner = ctx.framework().options().owners.contains(&ctx.author().id);
     if !is_bot_owner {
@@ -228,7 +681,7 @@ pub async fn register_application_commands_buttons<U, E>(
                 ":gear: Registering {num_commands} global commands...",
             ))
             .await?;
-            serenity::Command::set_global_commands(ctx, create_commands).await?;
+            register_in_scope(ctx, commands, None, delete_stale_commands).await?;
         } else {
             ctx.say(":gear: Unregistering global commands...").await?;
             serenity::Command::set_global_commands(ctx, vec![]).await?;
@@ -242,11 +695,13 @@ pub async fn register_application_commands_buttons<U, E>(
             }
         };
         if register {
+            let num_commands = create_application_commands_for_guild(commands, guild_id).len();
+
             ctx.say(format!(
                 ":gear: Registering {num_commands} guild commands...",
             ))
             .await?;
-            guild_id.set_commands(ctx, create_commands).await?;
+            register_in_scope(ctx, commands, Some(guild_id), delete_stale_commands).await?;
         } else {
             ctx.say(":gear: Unregistering guild commands...").await?;
             guild_id.set_commands(ctx, vec![]).await?;
@@ -263,3 +718,125 @@ pub async fn register_application_commands_buttons<U, E>(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_application_command_names() {
+        let slash_command = |name: &str| crate::Command::<(), ()> {
+            name: name.into(),
+            slash_action: Some(|_| Box::pin(async { Ok(()) })),
+            ..Default::default()
+        };
+        let mut parent = slash_command("parent");
+        parent.subcommands.push(crate::Command {
+            context_menu_name: Some("Inspect".into()),
+            context_menu_action: Some(crate::ContextMenuCommandAction::User(|_, _| {
+                Box::pin(async { Ok(()) })
+            })),
+            ..slash_command("child")
+        });
+        let commands = [parent, slash_command("other")];
+
+        assert_eq!(
+            application_command_names(&commands, None),
+            [
+                ("parent".into(), serenity::CommandType::ChatInput),
+                ("Inspect".into(), serenity::CommandType::User),
+                ("other".into(), serenity::CommandType::ChatInput),
+            ]
+        );
+
+        // Guild-specific state is taken into account
+        let guild_id = serenity::GuildId::new(1);
+        commands[0].set_enabled_in_guild(guild_id, false);
+        commands[1].set_guild_override(
+            guild_id,
+            Some(crate::CommandOverride {
+                name: Some("renamed".into()),
+                ..Default::default()
+            }),
+        );
+        assert_eq!(
+            application_command_names(&commands, Some(guild_id)),
+            [("renamed".into(), serenity::CommandType::ChatInput)]
+        );
+        assert_eq!(application_command_names(&commands, None).len(), 3);
+
+        // Commands with registration guilds are only registered there
+        let mut commands = commands;
+        commands[1].registration_guilds = vec![serenity::GuildId::new(2)];
+        assert_eq!(application_command_names(&commands, None).len(), 2);
+        assert_eq!(
+            application_command_names(&commands, Some(serenity::GuildId::new(2))).len(),
+            3
+        );
+        assert!(application_command_names(&commands, Some(guild_id)).is_empty());
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("command name", "ban-hammer_2", true).is_ok());
+        assert!(validate_name("command name", "страница", true).is_ok());
+        // Devanagari and Thai names contain combining marks
+        assert!(validate_name("command name", "नमस्ते", true).is_ok());
+        assert!(validate_name("command name", "สวัสดี", true).is_ok());
+        assert!(validate_name("command name", "ban hammer", true).is_err());
+        assert_eq!(
+            validate_name("command name", "Ban", true)
+                .unwrap_err()
+                .reason,
+            "must be lowercase"
+        );
+        assert_eq!(
+            validate_name("parameter name", &"a".repeat(33), true)
+                .unwrap_err()
+                .to_string(),
+            format!(
+                "parameter name `{}` must be between 1 and 32 characters long",
+                "a".repeat(33)
+            )
+        );
+        assert!(validate_name("command name", "", true).is_err());
+        // Context menu commands may use spaces and uppercase
+        assert!(validate_name("context menu command name", "Ban Hammer", false).is_ok());
+
+        assert!(validate_description("description of command", "ping", "Pong!").is_ok());
+        assert!(validate_description("description of command", "ping", "").is_err());
+        assert_eq!(
+            validate_description("description of parameter", "user", &"a".repeat(101))
+                .unwrap_err()
+                .to_string(),
+            "description of parameter `user` must be between 1 and 100 characters long"
+        );
+        // Discord counts characters, not bytes
+        assert!(validate_description("description of parameter", "user", &"ä".repeat(100)).is_ok());
+    }
+
+    #[test]
+    fn test_kept_stale_commands() {
+        let stale = || {
+            vec![
+                ::serenity::json::from_value::<serenity::Command>(::serenity::json::json!({
+                    "id": "2",
+                    "application_id": "1",
+                    "type": 1,
+                    "name": "removed",
+                    "description": "Removed from the code",
+                    "version": "3",
+                }))
+                .unwrap(),
+            ]
+        };
+
+        assert!(kept_stale_commands(stale(), true).is_empty());
+        // Stale commands are kept by default, with their ID
+        let kept = kept_stale_commands(stale(), false);
+        assert_eq!(kept.len(), 1);
+        let kept = ::serenity::json::to_value(&kept[0]).unwrap();
+        assert_eq!(kept["name"], "removed");
+        assert_eq!(kept["id"], "2");
+    }
+}
@@ -0,0 +1,183 @@
+//! Select menu to pick and run a subcommand, for command groups with many subcommands
+
+use crate::serenity_prelude as serenity;
+
+/// Presents the subcommands of the invoked command in a select menu and lets the author pick one.
+///
+/// If the picked subcommand takes no arguments, it's run right away, including its checks and
+/// cooldowns; errors are passed to [`crate::FrameworkOptions::on_error`]. Otherwise, the
+/// [`super::command_help`] of the subcommand is shown, so the author knows how to invoke it.
+///
+/// Subcommands which are [hidden in help](crate::Command::hide_in_help) aren't listed, and Discord
+/// limits the menu to 25 entries. If the author doesn't pick a subcommand within two minutes, the
+/// menu is removed.
+///
+/// Use this as the body of a command group:
+/// ```rust
+/// # type Error = Box<dyn std::error::Error + Send + Sync>;
+/// # type Context<'a> = poise::Context<'a, (), Error>;
+/// #[poise::command(prefix_command, subcommands("add", "remove", "list"))]
+/// pub async fn tags(ctx: Context<'_>) -> Result<(), Error> {
+///     poise::builtins::subcommand_menu(ctx).await?;
+///     Ok(())
+/// }
+/// # #[poise::command(prefix_command)] async fn add(ctx: Context<'_>, name: String) -> Result<(), Error> { Ok(()) }
+/// # #[poise::command(prefix_command)] async fn remove(ctx: Context<'_>, name: String) -> Result<(), Error> { Ok(()) }
+/// # #[poise::command(prefix_command)] async fn list(ctx: Context<'_>) -> Result<(), Error> { Ok(()) }
+/// ```
+pub async fn subcommand_menu<U, E>(ctx: crate::Context<'_, U, E>) -> Result<(), serenity::Error> {
+    let subcommands = ctx
+        .command()
+        .subcommands
+        .iter()
+        .filter(|subcommand| !subcommand.hide_in_help)
+        .take(25)
+        .collect::<Vec<_>>();
+    if subcommands.is_empty() {
+        ctx.say("This command has no subcommands").await?;
+        return Ok(());
+    }
+
+    // Define some unique identifiers for the select menu
+    let ctx_id = ctx.component_id_base();
+    let _claim = ctx.framework().options.claim_component_ids(ctx_id.clone());
+    let menu_id = format!("{}subcommand_menu", ctx_id);
+
+    let reply = {
+        let options = subcommands
+            .iter()
+            .enumerate()
+            .map(|(i, subcommand)| {
+                let mut option =
+                    serenity::CreateSelectMenuOption::new(&subcommand.name, i.to_string());
+                if let Some(description) = &subcommand.description {
+                    option = option.description(description.chars().take(100).collect::<String>());
+                }
+                option
+            })
+            .collect();
+        let menu = serenity::CreateSelectMenu::new(
+            &menu_id,
+            serenity::CreateSelectMenuKind::String { options },
+        )
+        .placeholder("Choose a subcommand");
+
+        crate::CreateReply::default()
+            .content(format!(
+                "Subcommands of `{}`:",
+                ctx.command().qualified_name
+            ))
+            .components(vec![serenity::CreateActionRow::SelectMenu(menu)])
+    };
+    let reply_handle = ctx.send(reply).await?;
+
+    let pick = serenity::collector::ComponentInteractionCollector::new(ctx)
+        .author_id(ctx.author().id)
+        .filter(move |pick| pick.data.custom_id == menu_id)
+        .timeout(std::time::Duration::from_secs(120))
+        .await;
+    let pick = match pick {
+        Some(pick) => pick,
+        None => {
+            reply_handle
+                .edit(
+                    ctx,
+                    crate::CreateReply::default()
+                        .content(format!(
+                            "Subcommands of `{}`:",
+                            ctx.command().qualified_name
+                        ))
+                        .components(Vec::new()),
+                )
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let subcommand = match &pick.data.kind {
+        serenity::ComponentInteractionDataKind::StringSelect { values } => values
+            .first()
+            .and_then(|value| value.parse::<usize>().ok())
+            .and_then(|i| subcommands.get(i).copied()),
+        _ => None,
+    };
+    let subcommand = match subcommand {
+        Some(subcommand) => subcommand,
+        None => return Ok(()),
+    };
+
+    pick.create_response(
+        ctx.serenity_context(),
+        serenity::CreateInteractionResponse::UpdateMessage(
+            serenity::CreateInteractionResponseMessage::new()
+                .content(format!("`{}`", subcommand.qualified_name))
+                .components(Vec::new()),
+        ),
+    )
+    .await?;
+
+    if subcommand.parameters.is_empty() && subcommand.subcommands.is_empty() {
+        let mut parent_commands = ctx.parent_commands().to_vec();
+        parent_commands.push(ctx.command());
+        if run_subcommand(ctx, subcommand, &parent_commands).await {
+            return Ok(());
+        }
+    }
+
+    super::command_help(
+        ctx,
+        &subcommand.qualified_name,
+        super::HelpConfiguration::default(),
+    )
+    .await
+}
+
+/// Runs the given argument-less subcommand in place of the invoked command, including its checks
+/// and cooldowns
+///
+/// Returns false if the subcommand doesn't support this kind of invocation, e.g. if it's
+/// slash-only but was picked in a prefix command
+async fn run_subcommand<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+    subcommand: &'a crate::Command<U, E>,
+    parent_commands: &'a [&'a crate::Command<U, E>],
+) -> bool {
+    let subcommand_ctx = match ctx {
+        crate::Context::Prefix(ctx) => match subcommand.prefix_action {
+            Some(action) => crate::Context::Prefix(crate::PrefixContext {
+                invoked_command_name: &subcommand.name,
+                args: "",
+                parent_commands,
+                command: subcommand,
+                action,
+                ..ctx
+            }),
+            None => return false,
+        },
+        crate::Context::Application(ctx) => match subcommand.slash_action {
+            Some(_) => crate::Context::Application(crate::ApplicationContext {
+                args: &[],
+                parent_commands,
+                command: subcommand,
+                ..ctx
+            }),
+            None => return false,
+        },
+    };
+
+    let result = async {
+        crate::check_permissions_and_cooldown(subcommand_ctx).await?;
+        match subcommand_ctx {
+            crate::Context::Prefix(ctx) => (ctx.action)(ctx).await,
+            crate::Context::Application(ctx) => match subcommand.slash_action {
+                Some(action) => action(ctx).await,
+                None => Ok(()),
+            },
+        }
+    }
+    .await;
+    if let Err(error) = result {
+        (ctx.framework().options.on_error)(error).await;
+    }
+    true
+}
@@ -20,6 +20,44 @@ pub trait ChoiceParameter: Sized {
 
     /// Returns the localized name for the given locale, if one is set
     fn localized_name(&self, locale: &str) -> Option<&'static str>;
+
+    /// Like [`Self::from_name()`], but additionally accepts any unambiguous case-insensitive prefix
+    /// of a choice name, so that `ag` matches `Aggressive`. Exact matches always take precedence.
+    ///
+    /// Used for prefix command parameters marked with `#[fuzzy]`
+    fn from_name_fuzzy(name: &str) -> Result<Self, FuzzyChoiceError> {
+        if let Some(choice) = Self::from_name(name) {
+            return Ok(choice);
+        }
+
+        let name = name.to_lowercase();
+        let mut candidates = Self::list()
+            .into_iter()
+            .enumerate()
+            .filter(|(_, choice)| choice.name.to_lowercase().starts_with(&name));
+        match (candidates.next(), candidates.next()) {
+            (Some((index, _)), None) => Self::from_index(index).ok_or(FuzzyChoiceError::Invalid),
+            (Some(first), Some(second)) => Err(FuzzyChoiceError::Ambiguous(
+                std::iter::once(first)
+                    .chain(std::iter::once(second))
+                    .chain(candidates)
+                    .map(|(_, choice)| choice.name)
+                    .collect(),
+            )),
+            (None, _) => Err(FuzzyChoiceError::Invalid),
+        }
+    }
+}
+
+/// Error returned from [`ChoiceParameter::from_name_fuzzy()`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FuzzyChoiceError {
+    /// No choice name matches the input
+    Invalid,
+    /// The input is a prefix of multiple choice names, which are listed here
+    Ambiguous(Vec<String>),
+    #[doc(hidden)]
+    __NonExhaustive,
 }
 
 #[async_trait::async_trait]
@@ -79,3 +117,106 @@ impl<'a, T: ChoiceParameter> crate::PopArgument<'a> for T {
         ))
     }
 }
+
+/// Like [`crate::pop_prefix_argument!`] for choice parameters, but parses the choice with
+/// [`ChoiceParameter::from_name_fuzzy()`]. Used for `#[fuzzy]` parameters
+#[doc(hidden)]
+pub async fn pop_fuzzy_choice<'a, T: ChoiceParameter>(
+    args: &'a str,
+    attachment_index: usize,
+    ctx: &serenity::Context,
+    msg: &serenity::Message,
+) -> Result<(&'a str, usize, T), (Box<dyn std::error::Error + Send + Sync>, Option<String>)> {
+    let (args, attachment_index, s) =
+        crate::pop_prefix_argument!(String, args, attachment_index, ctx, msg).await?;
+
+    let choice = T::from_name_fuzzy(&s).map_err(|error| {
+        let error: Box<dyn std::error::Error + Send + Sync> = match error {
+            FuzzyChoiceError::Ambiguous(candidates) => Box::new(crate::AmbiguousChoice {
+                candidates,
+                __non_exhaustive: (),
+            }),
+            _ => Box::new(crate::InvalidChoice {
+                __non_exhaustive: (),
+            }),
+        };
+        (error, Some(s.clone()))
+    })?;
+    Ok((args, attachment_index, choice))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    enum Mode {
+        Aggressive,
+        Agile,
+        Passive,
+        Pass,
+    }
+
+    const MODE_NAMES: [&str; 4] = ["Aggressive", "Agile", "Passive", "Pass"];
+
+    impl ChoiceParameter for Mode {
+        fn list() -> Vec<crate::CommandParameterChoice> {
+            MODE_NAMES
+                .iter()
+                .map(|name| crate::CommandParameterChoice {
+                    name: name.to_string(),
+                    localizations: Default::default(),
+                    __non_exhaustive: (),
+                })
+                .collect()
+        }
+
+        fn from_index(index: usize) -> Option<Self> {
+            match index {
+                0 => Some(Self::Aggressive),
+                1 => Some(Self::Agile),
+                2 => Some(Self::Passive),
+                3 => Some(Self::Pass),
+                _ => None,
+            }
+        }
+
+        fn from_name(name: &str) -> Option<Self> {
+            let index = Self::list()
+                .iter()
+                .position(|choice| choice.name.eq_ignore_ascii_case(name))?;
+            Self::from_index(index)
+        }
+
+        fn name(&self) -> &'static str {
+            match self {
+                Self::Aggressive => MODE_NAMES[0],
+                Self::Agile => MODE_NAMES[1],
+                Self::Passive => MODE_NAMES[2],
+                Self::Pass => MODE_NAMES[3],
+            }
+        }
+
+        fn localized_name(&self, _: &str) -> Option<&'static str> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_from_name_fuzzy() {
+        assert_eq!(Mode::from_name_fuzzy("agile"), Ok(Mode::Agile));
+        assert_eq!(Mode::from_name_fuzzy("agg"), Ok(Mode::Aggressive));
+        assert_eq!(Mode::from_name_fuzzy("PASSI"), Ok(Mode::Passive));
+        // Exact match wins even though it's also a prefix of another choice
+        assert_eq!(Mode::from_name_fuzzy("pass"), Ok(Mode::Pass));
+        assert_eq!(
+            Mode::from_name_fuzzy("ag"),
+            Err(FuzzyChoiceError::Ambiguous(vec![
+                "Aggressive".into(),
+                "Agile".into()
+            ])),
+        );
+        assert_eq!(Mode::from_name_fuzzy("x"), Err(FuzzyChoiceError::Invalid));
+        assert_eq!(Mode::from_name_fuzzy("agg").unwrap().name(), "Aggressive");
+    }
+}
@@ -30,10 +30,61 @@ pub struct CooldownConfig {
     pub channel: Option<Duration>,
     /// This cooldown operates on a per-member basis
     pub member: Option<Duration>,
+    /// Whether [owners](crate::FrameworkOptions::owners) are exempt from this command's cooldowns
+    pub exempt_owners: bool,
+    /// Members with any of these roles are exempt from this command's cooldowns
+    pub exempt_roles: Vec<serenity::RoleId>,
     #[doc(hidden)]
     pub __non_exhaustive: (),
 }
 
+impl CooldownConfig {
+    /// Returns whether the author of this invocation is exempt from cooldowns, according to
+    /// [`Self::exempt_owners`] and [`Self::exempt_roles`]
+    ///
+    /// Exempt invocations neither check nor start cooldowns.
+    pub fn is_exempt<U, E>(&self, ctx: crate::Context<'_, U, E>) -> bool {
+        if self.exempt_owners && ctx.framework().options.owners.contains(&ctx.author().id) {
+            return true;
+        }
+        if self.exempt_roles.is_empty() {
+            return false;
+        }
+
+        // Both gateway messages and interactions from guilds contain the author's roles
+        let roles = match ctx {
+            crate::Context::Application(ctx) => ctx.interaction.member.as_ref().map(|m| &m.roles),
+            crate::Context::Prefix(ctx) => ctx.msg.member.as_ref().map(|m| &m.roles),
+        };
+        roles.is_some_and(|roles| roles.iter().any(|role| self.exempt_roles.contains(role)))
+    }
+
+    /// Returns the keys and durations of all cooldowns of the given command which apply to this
+    /// invocation
+    pub fn keys(&self, command: &str, ctx: &CooldownContext) -> Vec<(CooldownKey, Duration)> {
+        let mut scopes = vec![
+            (CooldownScope::Global, self.global),
+            (CooldownScope::User(ctx.user_id), self.user),
+            (CooldownScope::Channel(ctx.channel_id), self.channel),
+        ];
+        if let Some(guild_id) = ctx.guild_id {
+            scopes.push((CooldownScope::Guild(guild_id), self.guild));
+            scopes.push((CooldownScope::Member(ctx.user_id, guild_id), self.member));
+        }
+
+        scopes
+            .into_iter()
+            .filter_map(|(scope, duration)| {
+                let key = CooldownKey {
+                    command: command.to_owned(),
+                    scope,
+                };
+                Some((key, duration?))
+            })
+            .collect()
+    }
+}
+
 /// Tracks all types of cooldowns for a single command
 ///
 /// You probably don't need to use this directly. `#[poise::command]` automatically generates a
@@ -109,6 +160,30 @@ impl CooldownTracker {
             .max()
     }
 
+    /// Forgets all invocation timestamps whose cooldown has expired, according to the given
+    /// configuration
+    ///
+    /// Doesn't change the result of [`Self::remaining_cooldown`]; it just frees memory.
+    pub fn purge(&mut self, cooldown_durations: &CooldownConfig) {
+        fn is_active(cooldown: Option<Duration>, last_invocation: Instant) -> bool {
+            cooldown.is_some_and(|cooldown| last_invocation.elapsed() < cooldown)
+        }
+
+        if let Some(last_invocation) = self.global_invocation {
+            if !is_active(cooldown_durations.global, last_invocation) {
+                self.global_invocation = None;
+            }
+        }
+        self.user_invocations
+            .retain(|_, &mut t| is_active(cooldown_durations.user, t));
+        self.guild_invocations
+            .retain(|_, &mut t| is_active(cooldown_durations.guild, t));
+        self.channel_invocations
+            .retain(|_, &mut t| is_active(cooldown_durations.channel, t));
+        self.member_invocations
+            .retain(|_, &mut t| is_active(cooldown_durations.member, t));
+    }
+
     /// Indicates that a command has been executed and all associated cooldowns should start running
     pub fn start_cooldown(&mut self, ctx: CooldownContext) {
         let now = Instant::now();
@@ -124,6 +199,135 @@ impl CooldownTracker {
     }
 }
 
+/// Scope of a single cooldown, as part of a [`CooldownKey`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum CooldownScope {
+    /// See [`CooldownConfig::global`]
+    Global,
+    /// See [`CooldownConfig::user`]
+    User(serenity::UserId),
+    /// See [`CooldownConfig::guild`]
+    Guild(serenity::GuildId),
+    /// See [`CooldownConfig::channel`]
+    Channel(serenity::ChannelId),
+    /// See [`CooldownConfig::member`]
+    Member(serenity::UserId, serenity::GuildId),
+}
+
+/// Identifies a cooldown of a command in a single scope, for use with [`CooldownStorage`]
+///
+/// The [`std::fmt::Display`] impl produces a stable string representation which is suitable as a
+/// database or Redis key: the qualified command name, the scope and the IDs of the scope,
+/// separated by slashes.
+///
+/// ```rust
+/// # use poise::serenity_prelude as serenity;
+/// let key = poise::CooldownKey {
+///     command: "daily".into(),
+///     scope: poise::CooldownScope::Member(serenity::UserId::new(1), serenity::GuildId::new(2)),
+/// };
+/// assert_eq!(key.to_string(), "daily/member/1/2");
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct CooldownKey {
+    /// Qualified name of the command, see [`crate::Command::qualified_name`]
+    pub command: String,
+    /// Which cooldown of the command
+    pub scope: CooldownScope,
+}
+
+impl std::fmt::Display for CooldownKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.scope {
+            CooldownScope::Global => write!(f, "{}/global", self.command),
+            CooldownScope::User(user_id) => write!(f, "{}/user/{}", self.command, user_id),
+            CooldownScope::Guild(guild_id) => write!(f, "{}/guild/{}", self.command, guild_id),
+            CooldownScope::Channel(channel_id) => {
+                write!(f, "{}/channel/{}", self.command, channel_id)
+            }
+            CooldownScope::Member(user_id, guild_id) => {
+                write!(f, "{}/member/{}/{}", self.command, user_id, guild_id)
+            }
+        }
+    }
+}
+
+/// Persistent storage for cooldowns, so that they survive restarts
+///
+/// If set in [`crate::FrameworkOptions::cooldown_storage`], the framework checks and starts
+/// cooldowns through this trait instead of [`crate::Command::cooldowns`]. Implement it on top of a
+/// database or Redis to keep long cooldowns, like for daily rewards, across restarts.
+///
+/// The framework never deletes entries; let them expire on your side once they are older than the
+/// longest cooldown, e.g. with a Redis TTL.
+#[async_trait::async_trait]
+pub trait CooldownStorage: Send + Sync {
+    /// Returns the time of the last invocation that started this cooldown, if any
+    async fn last_invocation(&self, key: &CooldownKey) -> Option<std::time::SystemTime>;
+
+    /// Stores the time of an invocation that starts this cooldown
+    async fn set_last_invocation(&self, key: &CooldownKey, time: std::time::SystemTime);
+}
+
+/// A [`CooldownStorage`] which keeps the cooldowns in memory
+///
+/// Behaves like the default cooldown handling, so it's mainly useful as a starting point for your
+/// own implementation and for tests.
+#[derive(Default, Debug)]
+pub struct InMemoryCooldownStorage {
+    /// Time of the last invocation per cooldown
+    invocations: std::sync::Mutex<HashMap<CooldownKey, std::time::SystemTime>>,
+}
+
+#[async_trait::async_trait]
+impl CooldownStorage for InMemoryCooldownStorage {
+    async fn last_invocation(&self, key: &CooldownKey) -> Option<std::time::SystemTime> {
+        self.invocations.lock().unwrap().get(key).copied()
+    }
+
+    async fn set_last_invocation(&self, key: &CooldownKey, time: std::time::SystemTime) {
+        self.invocations.lock().unwrap().insert(key.clone(), time);
+    }
+}
+
+/// Queries the given storage and checks if all cooldowns of the command have expired. If not,
+/// Some is returned with the remaining cooldown. Like [`CooldownTracker::remaining_cooldown`]
+pub async fn remaining_cooldown_in_storage(
+    storage: &dyn CooldownStorage,
+    command: &str,
+    ctx: &CooldownContext,
+    config: &CooldownConfig,
+) -> Option<Duration> {
+    let now = std::time::SystemTime::now();
+    let mut remaining = None;
+    for (key, cooldown) in config.keys(command, ctx) {
+        let last_invocation = match storage.last_invocation(&key).await {
+            Some(x) => x,
+            None => continue,
+        };
+        // A last invocation in the future (clock changes) counts as just now
+        let duration_since = now.duration_since(last_invocation).unwrap_or_default();
+        if let Some(cooldown_left) = cooldown.checked_sub(duration_since) {
+            remaining = std::cmp::max(remaining, Some(cooldown_left));
+        }
+    }
+    remaining
+}
+
+/// Starts all cooldowns of the command in the given storage. Like
+/// [`CooldownTracker::start_cooldown`]
+pub async fn start_cooldown_in_storage(
+    storage: &dyn CooldownStorage,
+    command: &str,
+    ctx: &CooldownContext,
+    config: &CooldownConfig,
+) {
+    let now = std::time::SystemTime::now();
+    for (key, _) in config.keys(command, ctx) {
+        storage.set_last_invocation(&key, now).await;
+    }
+}
+
 impl<'a> From<&'a serenity::Message> for CooldownContext {
     fn from(message: &'a serenity::Message) -> Self {
         Self {
@@ -133,3 +337,46 @@ impl<'a> From<&'a serenity::Message> for CooldownContext {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cooldown_storage() {
+        let storage = InMemoryCooldownStorage::default();
+        let config = CooldownConfig {
+            user: Some(Duration::from_secs(3600)),
+            member: Some(Duration::from_secs(60)),
+            ..Default::default()
+        };
+        let ctx = |user_id| CooldownContext {
+            user_id: serenity::UserId::new(user_id),
+            guild_id: Some(serenity::GuildId::new(1)),
+            channel_id: serenity::ChannelId::new(1),
+        };
+
+        assert_eq!(config.keys("daily", &ctx(1)).len(), 2);
+        assert_eq!(
+            remaining_cooldown_in_storage(&storage, "daily", &ctx(1), &config).await,
+            None
+        );
+
+        start_cooldown_in_storage(&storage, "daily", &ctx(1), &config).await;
+        let remaining = remaining_cooldown_in_storage(&storage, "daily", &ctx(1), &config)
+            .await
+            .unwrap();
+        // The longest cooldown wins
+        assert!(remaining > Duration::from_secs(3590));
+
+        // Other users and commands are unaffected
+        assert_eq!(
+            remaining_cooldown_in_storage(&storage, "daily", &ctx(2), &config).await,
+            None
+        );
+        assert_eq!(
+            remaining_cooldown_in_storage(&storage, "weekly", &ctx(1), &config).await,
+            None
+        );
+    }
+}
@@ -4,11 +4,14 @@ use crate::serenity_prelude as serenity;
 
 /// Retrieves user permissions in the given channel. If unknown, returns None. If in DMs, returns
 /// `Permissions::all()`.
-async fn user_permissions(
+///
+/// If a member roles cache is given, the member is only fetched if their roles aren't cached.
+pub(crate) async fn user_permissions(
     ctx: &serenity::Context,
     guild_id: Option<serenity::GuildId>,
     channel_id: serenity::ChannelId,
     user_id: serenity::UserId,
+    member_roles_cache: Option<&std::sync::RwLock<crate::MemberRolesCache>>,
 ) -> Option<serenity::Permissions> {
     let guild_id = match guild_id {
         Some(x) => x,
@@ -29,9 +32,46 @@ async fn user_permissions(
         Err(_) => return None,
     };
 
-    let member = guild.member(ctx, user_id).await.ok()?;
+    let cached_member = member_roles_cache.and_then(|cache| {
+        let cache = cache.read().unwrap();
+        let (roles, communication_disabled_until) = cache.get(guild_id, user_id)?;
+        Some((roles.to_vec(), communication_disabled_until))
+    });
+    let member = match cached_member {
+        Some((roles, communication_disabled_until)) => {
+            // Permission calculation only looks at the user ID, roles and timeout
+            let mut member = serenity::Member::default();
+            member.guild_id = guild_id;
+            member.user.id = user_id;
+            member.roles = roles;
+            member.communication_disabled_until = communication_disabled_until;
+            member
+        }
+        None => {
+            let member = guild.member(ctx, user_id).await.ok()?;
+            if let Some(cache) = member_roles_cache {
+                cache.write().unwrap().insert(
+                    guild_id,
+                    user_id,
+                    member.roles.clone(),
+                    member.communication_disabled_until,
+                );
+            }
+            member
+        }
+    };
 
-    Some(guild.user_permissions_in(&channel, &member))
+    let mut permissions = guild.user_permissions_in(&channel, &member);
+    // Serenity doesn't account for timeouts: timed out members can only view channels and read
+    // message history. Administrators are exempt
+    let timed_out = member
+        .communication_disabled_until
+        .is_some_and(|until| until > serenity::Timestamp::now());
+    if timed_out && !permissions.administrator() {
+        permissions &=
+            serenity::Permissions::VIEW_CHANNEL | serenity::Permissions::READ_MESSAGE_HISTORY;
+    }
+    Some(permissions)
 }
 
 /// Retrieves the set of permissions that are lacking, relative to the given required permission set
@@ -51,6 +91,7 @@ async fn missing_permissions<U, E>(
         ctx.guild_id(),
         ctx.channel_id(),
         user,
+        ctx.framework().options.member_roles_cache.as_deref(),
     )
     .await;
     Some(required_permissions - permissions?)
@@ -62,6 +103,12 @@ async fn check_permissions_and_cooldown_single<'a, U, E>(
     ctx: crate::Context<'a, U, E>,
     cmd: &'a crate::Command<U, E>,
 ) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    if let Some(guild_id) = ctx.guild_id() {
+        if !cmd.is_enabled_in_guild(guild_id) {
+            return Err(crate::FrameworkError::CommandDisabled { ctx });
+        }
+    }
+
     // Skip command checks if `FrameworkOptions::skip_checks_for_owners` is set to true
     if ctx.framework().options.skip_checks_for_owners
         && ctx.framework().options().owners.contains(&ctx.author().id)
@@ -126,8 +173,17 @@ async fn check_permissions_and_cooldown_single<'a, U, E>(
         }
     }
 
-    // Before running any pre-command checks, make sure the bot has the permissions it needs
-    match missing_permissions(ctx, ctx.framework().bot_id, cmd.required_bot_permissions).await {
+    // Before running any pre-command checks, make sure the bot has the permissions it needs.
+    // Prefer the permissions computed by Discord, which can't be outdated like the cache. DMs
+    // aren't permission checked, like in `user_permissions`
+    let app_permissions = ctx.app_permissions().filter(|_| ctx.guild_id().is_some());
+    let missing_bot_permissions = match app_permissions {
+        Some(app_permissions) => Some(cmd.required_bot_permissions - app_permissions),
+        None => {
+            missing_permissions(ctx, ctx.framework().bot_id, cmd.required_bot_permissions).await
+        }
+    };
+    match missing_bot_permissions {
         Some(missing_permissions) if missing_permissions.is_empty() => {}
         Some(missing_permissions) => {
             return Err(crate::FrameworkError::MissingBotPermissions {
@@ -158,15 +214,38 @@ async fn check_permissions_and_cooldown_single<'a, U, E>(
     }
 
     if !ctx.framework().options().manual_cooldowns {
-        let cooldowns = cmd.cooldowns.lock().unwrap();
-        let config = cmd.cooldown_config.read().unwrap();
-        let remaining_cooldown = cooldowns.remaining_cooldown(ctx.cooldown_context(), &config);
+        let config = cmd.cooldown_config.read().unwrap().clone();
+        let remaining_cooldown = match (
+            config.is_exempt(ctx),
+            &ctx.framework().options().cooldown_storage,
+        ) {
+            (true, _) => None,
+            (false, Some(storage)) => {
+                crate::remaining_cooldown_in_storage(
+                    &**storage,
+                    &cmd.qualified_name,
+                    &ctx.cooldown_context(),
+                    &config,
+                )
+                .await
+            }
+            (false, None) => {
+                let cooldowns = cmd.cooldowns.lock().unwrap();
+                cooldowns.remaining_cooldown(ctx.cooldown_context(), &config)
+            }
+        };
         if let Some(remaining_cooldown) = remaining_cooldown {
             return Err(crate::FrameworkError::CooldownHit {
                 ctx,
                 remaining_cooldown,
             });
         }
+
+        let rate_limits = cmd.rate_limits.lock().unwrap();
+        let config = cmd.rate_limit_config.read().unwrap();
+        if let Some(retry_after) = rate_limits.retry_after(ctx.cooldown_context(), &config) {
+            return Err(crate::FrameworkError::RateLimited { ctx, retry_after });
+        }
     }
 
     Ok(())
@@ -188,3 +267,119 @@ pub async fn check_permissions_and_cooldown<'a, U, E>(
 
     Ok(())
 }
+
+/// Starts the cooldowns of the invoked command and takes a rate limit token, unless
+/// [`crate::FrameworkOptions::manual_cooldowns`] is set
+///
+/// Called by the code generated by `#[poise::command]` after argument parsing succeeded.
+#[doc(hidden)]
+pub async fn start_cooldowns<U, E>(ctx: crate::Context<'_, U, E>) {
+    if ctx.framework().options().manual_cooldowns {
+        return;
+    }
+
+    let command = ctx.command();
+    let config = command.cooldown_config.read().unwrap().clone();
+    if !config.is_exempt(ctx) {
+        if let Some(storage) = &ctx.framework().options().cooldown_storage {
+            let cooldown_ctx = ctx.cooldown_context();
+            crate::start_cooldown_in_storage(
+                &**storage,
+                &command.qualified_name,
+                &cooldown_ctx,
+                &config,
+            )
+            .await;
+        } else {
+            let mut cooldowns = command.cooldowns.lock().unwrap();
+            cooldowns.start_cooldown(ctx.cooldown_context());
+        }
+    }
+
+    let rate_limit_config = command.rate_limit_config.read().unwrap();
+    let mut rate_limits = command.rate_limits.lock().unwrap();
+    rate_limits.take_token(ctx.cooldown_context(), &rate_limit_config);
+}
+
+/// Runs [`crate::FrameworkOptions::guild_setup`] if this is the first command in this guild
+pub(crate) async fn run_guild_setup<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    let (guild_setup, guild_id) = match (ctx.framework().options.guild_setup, ctx.guild_id()) {
+        (Some(guild_setup), Some(guild_id)) => (guild_setup, guild_id),
+        _ => return Ok(()),
+    };
+
+    // Only lock the map to get this guild's cell, so that a slow setup only holds up commands in
+    // its own guild
+    let setup = ctx
+        .framework()
+        .options
+        .initialized_guilds
+        .lock()
+        .unwrap()
+        .entry(guild_id)
+        .or_default()
+        .clone();
+    // If another command is already running the setup, this waits for it. If that fails, the
+    // next waiting command retries it
+    setup
+        .get_or_try_init(|| guild_setup(ctx, guild_id))
+        .await
+        .map_err(|error| crate::FrameworkError::GuildSetup {
+            error,
+            guild_id,
+            ctx,
+        })?;
+    Ok(())
+}
+
+/// Runs the given command action future, cancelling it if it takes longer than
+/// [`crate::Command::timeout`] or [`crate::FrameworkOptions::command_timeout`]
+pub(crate) async fn run_with_timeout<'a, U, E>(
+    ctx: crate::Context<'a, U, E>,
+    action: impl std::future::Future<Output = Result<(), crate::FrameworkError<'a, U, E>>>,
+) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    let timeout = ctx
+        .command()
+        .timeout
+        .or(ctx.framework().options.command_timeout);
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, action)
+            .await
+            .unwrap_or(Err(crate::FrameworkError::CommandTimeout { ctx })),
+        None => action.await,
+    }
+}
+
+/// Acquires a permit for [`crate::FrameworkOptions::max_concurrent_invocations`], which must be
+/// held while the command runs. Returns None if there's no limit.
+///
+/// Fails with [`crate::FrameworkError::ConcurrencyLimitHit`] if the permits are exhausted and
+/// [`crate::ConcurrencyLimitBehavior::Reject`] is set. Otherwise, application commands are
+/// deferred before waiting, so the interaction doesn't expire in the queue.
+pub(crate) async fn acquire_invocation_permit<U, E>(
+    ctx: crate::Context<'_, U, E>,
+) -> Result<Option<tokio::sync::SemaphorePermit<'_>>, crate::FrameworkError<'_, U, E>> {
+    let options = ctx.framework().options;
+    let limit = match &options.max_concurrent_invocations {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+    if let Some(permit) = limit.try_acquire() {
+        return Ok(Some(permit));
+    }
+    if options.on_concurrency_limit == crate::ConcurrencyLimitBehavior::Reject {
+        return Err(crate::FrameworkError::ConcurrencyLimitHit { ctx });
+    }
+
+    if let crate::Context::Application(ctx) = ctx {
+        if !ctx.command.no_defer && !ctx.command.manual_response {
+            // Not fatal: if the interaction is still valid, the command can respond regardless
+            if let Err(e) = ctx.defer_response(ctx.command.ephemeral).await {
+                tracing::warn!("failed to defer interaction: {}", e);
+            }
+        }
+    }
+    Ok(Some(limit.acquire().await))
+}
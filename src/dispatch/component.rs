@@ -0,0 +1,156 @@
+//! Dispatches clicks on buttons created with [`command_custom_id`] onto the encoded command
+
+use crate::serenity_prelude as serenity;
+
+/// Marks component custom IDs which encode a command invocation
+const COMMAND_CUSTOM_ID_PREFIX: &str = "poise_command:";
+
+/// Maximum length of component custom IDs in characters, as enforced by Discord
+const MAX_CUSTOM_ID_LENGTH: usize = 100;
+
+/// Error returned by [`command_custom_id`] if the encoded invocation doesn't fit into a custom ID
+#[derive(Debug, Clone)]
+pub struct CustomIdTooLong {
+    /// Length of the custom ID in characters
+    pub length: usize,
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+impl std::fmt::Display for CustomIdTooLong {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "custom ID is {} characters long, but may have at most {}",
+            self.length, MAX_CUSTOM_ID_LENGTH,
+        )
+    }
+}
+impl std::error::Error for CustomIdTooLong {}
+
+/// Encodes a command invocation into a component custom ID, to create "quick action" buttons
+/// which run a command when clicked.
+///
+/// `command` is the name of the command, including parent commands for subcommands (like
+/// `"config show"`), and `args` are its arguments, written like in a prefix invocation. A click
+/// runs the command as a prefix command invoked by the clicking user, so the command needs a
+/// prefix implementation. Its checks, permissions and cooldowns apply as usual. The response is
+/// sent as a regular message in the channel, since there's no invocation message to reply to.
+///
+/// Clicks are handled by [`crate::dispatch_event`], which acknowledges the interaction and
+/// passes errors to [`crate::FrameworkOptions::on_error`] as usual.
+///
+/// Fails if the custom ID would exceed Discord's limit of 100 characters.
+///
+/// ```rust
+/// # use poise::serenity_prelude as serenity;
+/// let custom_id = poise::command_custom_id("roll", "1d20")?;
+/// let button = serenity::CreateButton::new(custom_id).label("Roll again");
+/// # Ok::<(), poise::CustomIdTooLong>(())
+/// ```
+pub fn command_custom_id(command: &str, args: &str) -> Result<String, CustomIdTooLong> {
+    let mut custom_id = format!("{}{}", COMMAND_CUSTOM_ID_PREFIX, command);
+    if !args.is_empty() {
+        custom_id.push(' ');
+        custom_id.push_str(args);
+    }
+
+    let length = custom_id.chars().count();
+    if length > MAX_CUSTOM_ID_LENGTH {
+        return Err(CustomIdTooLong {
+            length,
+            __non_exhaustive: (),
+        });
+    }
+    Ok(custom_id)
+}
+
+/// Returns the command invocation encoded by [`command_custom_id`], if this custom ID is one
+fn parse_command_custom_id(custom_id: &str) -> Option<&str> {
+    custom_id.strip_prefix(COMMAND_CUSTOM_ID_PREFIX)
+}
+
+/// Creates the message to dispatch a click on a button from [`command_custom_id`] with: sent by
+/// the clicking user (including their member data in guilds) in the channel of the button, with
+/// the encoded invocation as content.
+///
+/// Returns None if the clicked component doesn't encode a command.
+pub(crate) fn command_button_message(
+    interaction: &serenity::ComponentInteraction,
+) -> Option<serenity::Message> {
+    let invocation = parse_command_custom_id(&interaction.data.custom_id)?;
+
+    let mut msg = serenity::Message::default();
+    // The ID of the interaction is unique, so edit tracking can't confuse this message with a
+    // real one
+    msg.id = serenity::MessageId::new(interaction.id.get());
+    msg.timestamp = interaction.id.created_at();
+    msg.channel_id = interaction.channel_id;
+    msg.guild_id = interaction.guild_id;
+    msg.author = interaction.user.clone();
+    // Needed for checks like cooldown exempt roles and for partial_member()
+    msg.member = interaction
+        .member
+        .clone()
+        .map(|member| Box::new(member.into()));
+    msg.content = invocation.to_owned();
+    Some(msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_custom_id() {
+        let custom_id = command_custom_id("config show", "prefix").unwrap();
+        assert_eq!(
+            parse_command_custom_id(&custom_id),
+            Some("config show prefix")
+        );
+        let custom_id = command_custom_id("ping", "").unwrap();
+        assert_eq!(parse_command_custom_id(&custom_id), Some("ping"));
+        assert_eq!(parse_command_custom_id("ping"), None);
+
+        let max_args = "ä".repeat(MAX_CUSTOM_ID_LENGTH - COMMAND_CUSTOM_ID_PREFIX.len() - 5);
+        assert!(command_custom_id("ping", &max_args).is_ok());
+        let error = command_custom_id("ping", &format!("{}ä", max_args)).unwrap_err();
+        assert_eq!(error.length, MAX_CUSTOM_ID_LENGTH + 1);
+    }
+
+    #[test]
+    fn test_command_button_message() {
+        let interaction = |custom_id: &str, member: Option<serenity::Member>| {
+            let member = member.map(|member| ::serenity::json::to_value(member).unwrap());
+            let interaction = ::serenity::json::json!({
+                "id": "1000000000000000000",
+                "application_id": "1",
+                "type": 3,
+                "data": { "custom_id": custom_id, "component_type": 2 },
+                "guild_id": member.as_ref().map(|_| "2"),
+                "channel_id": "3",
+                "member": member,
+                "user": ::serenity::json::to_value(serenity::User::default()).unwrap(),
+                "token": "",
+                "version": 1,
+                "message": ::serenity::json::to_value(serenity::Message::default()).unwrap(),
+                "locale": "en-US",
+            });
+            ::serenity::json::from_value::<serenity::ComponentInteraction>(interaction).unwrap()
+        };
+        let custom_id = command_custom_id("ping", "").unwrap();
+
+        let mut member = serenity::Member::default();
+        member.user.id = serenity::UserId::new(4);
+        member.roles = vec![serenity::RoleId::new(5)];
+        let msg = command_button_message(&interaction(&custom_id, Some(member))).unwrap();
+        assert_eq!(msg.content, "ping");
+        assert_eq!(msg.author.id, serenity::UserId::new(4));
+        assert_eq!(msg.guild_id, Some(serenity::GuildId::new(2)));
+        let member = msg.member.expect("member data of the clicking user");
+        assert_eq!(member.roles, vec![serenity::RoleId::new(5)]);
+
+        let msg = command_button_message(&interaction(&custom_id, None)).unwrap();
+        assert!(msg.member.is_none());
+        assert!(command_button_message(&interaction("ping", None)).is_none());
+    }
+}
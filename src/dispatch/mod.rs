@@ -1,14 +1,17 @@
 //! Contains all code to dispatch incoming events onto framework commands
 
 mod common;
+mod component;
 mod prefix;
 mod slash;
 
 pub use common::*;
+pub use component::*;
 pub use prefix::*;
 pub use slash::*;
 
 use crate::serenity_prelude as serenity;
+use tracing::Instrument as _;
 
 // TODO: integrate serenity::Context in here? Every place where FrameworkContext is passed is also
 // passed serenity::Context
@@ -22,6 +25,8 @@ pub struct FrameworkContext<'a, U, E> {
     pub user_data: &'a U,
     /// Serenity shard manager. Can be used for example to shutdown the bot
     pub shard_manager: &'a std::sync::Arc<serenity::ShardManager>,
+    /// When the bot was started, for measuring uptime. See [`crate::Framework::started_at`]
+    pub started_at: std::time::Instant,
     // deliberately not non exhaustive because you need to create FrameworkContext from scratch
     // to run your own event loop
 }
@@ -48,6 +53,21 @@ impl<'a, U, E> FrameworkContext<'a, U, E> {
         self.shard_manager.clone()
     }
 
+    /// See [`crate::Framework::set_command_enabled_in_guild`]
+    pub fn set_command_enabled_in_guild(
+        &self,
+        qualified_name: &str,
+        guild_id: serenity::GuildId,
+        enabled: bool,
+    ) -> bool {
+        crate::framework::set_command_enabled_in_guild(
+            &self.options.commands,
+            qualified_name,
+            guild_id,
+            enabled,
+        )
+    }
+
     /// Retrieves user data
     ///
     /// This function exists for API compatiblity with [`crate::Framework`]. On this type, you can
@@ -58,29 +78,90 @@ impl<'a, U, E> FrameworkContext<'a, U, E> {
     }
 }
 
+/// Applies [`crate::PrefixFrameworkOptions::content_preprocessor`] to the given message, cloning
+/// it only if the content was actually changed
+fn preprocess_message<'a, U, E>(
+    framework: crate::FrameworkContext<'_, U, E>,
+    msg: &'a serenity::Message,
+) -> std::borrow::Cow<'a, serenity::Message> {
+    let preprocessor = match framework.options.prefix_options.content_preprocessor {
+        Some(x) => x,
+        None => return std::borrow::Cow::Borrowed(msg),
+    };
+    match preprocessor(&msg.content) {
+        std::borrow::Cow::Borrowed(_) => std::borrow::Cow::Borrowed(msg),
+        std::borrow::Cow::Owned(content) => {
+            let mut msg = msg.clone();
+            msg.content = content;
+            std::borrow::Cow::Owned(msg)
+        }
+    }
+}
+
+/// Creates the tracing span for a command invocation if the `tracing_spans` feature is enabled
+///
+/// The `command` field is filled in once the command is known and the `error` field when an
+/// error is handled
+fn invocation_span(
+    kind: &'static str,
+    guild_id: Option<serenity::GuildId>,
+    channel_id: serenity::ChannelId,
+    user_id: serenity::UserId,
+) -> tracing::Span {
+    #[cfg(feature = "tracing_spans")]
+    {
+        tracing::info_span!(
+            "command",
+            kind,
+            command = tracing::field::Empty,
+            guild_id = guild_id.map(|id| id.get()),
+            channel_id = channel_id.get(),
+            user_id = user_id.get(),
+            error = tracing::field::Empty,
+        )
+    }
+    #[cfg(not(feature = "tracing_spans"))]
+    {
+        let _ = (kind, guild_id, channel_id, user_id);
+        tracing::Span::none()
+    }
+}
+
 /// Central event handling function of this library
 pub async fn dispatch_event<U: Send + Sync, E>(
     framework: crate::FrameworkContext<'_, U, E>,
     ctx: &serenity::Context,
     event: serenity::FullEvent,
 ) {
+    if let Some(member_roles_cache) = &framework.options.member_roles_cache {
+        member_roles_cache.write().unwrap().process_event(&event);
+    }
+
+    let raw_event = &event;
     match &event {
         serenity::FullEvent::Message { new_message } => {
             let invocation_data = tokio::sync::Mutex::new(Box::new(()) as _);
             let mut parent_commands = Vec::new();
             let trigger = crate::MessageDispatchTrigger::MessageCreate;
-            if let Err(error) = prefix::dispatch_message(
-                framework,
-                ctx,
-                new_message,
-                trigger,
-                &invocation_data,
-                &mut parent_commands,
-            )
-            .await
-            {
-                error.handle(framework.options).await;
+            let msg = preprocess_message(framework, new_message);
+            let span = invocation_span("prefix", msg.guild_id, msg.channel_id, msg.author.id);
+            async {
+                if let Err(error) = prefix::dispatch_message_event(
+                    framework,
+                    ctx,
+                    &msg,
+                    trigger,
+                    &invocation_data,
+                    &mut parent_commands,
+                    Some(raw_event),
+                )
+                .await
+                {
+                    error.handle(framework.options).await;
+                };
             }
+            .instrument(span)
+            .await;
         }
         serenity::FullEvent::MessageUpdate { event, .. } => {
             if let Some(edit_tracker) = &framework.options.prefix_options.edit_tracker {
@@ -99,18 +180,26 @@ pub async fn dispatch_event<U: Send + Sync, E>(
                         true => crate::MessageDispatchTrigger::MessageEdit,
                         false => crate::MessageDispatchTrigger::MessageEditFromInvalid,
                     };
-                    if let Err(error) = prefix::dispatch_message(
-                        framework,
-                        ctx,
-                        &msg,
-                        trigger,
-                        &invocation_data,
-                        &mut parent_commands,
-                    )
-                    .await
-                    {
-                        error.handle(framework.options).await;
+                    let msg = preprocess_message(framework, &msg);
+                    let span =
+                        invocation_span("prefix", msg.guild_id, msg.channel_id, msg.author.id);
+                    async {
+                        if let Err(error) = prefix::dispatch_message_event(
+                            framework,
+                            ctx,
+                            &msg,
+                            trigger,
+                            &invocation_data,
+                            &mut parent_commands,
+                            Some(raw_event),
+                        )
+                        .await
+                        {
+                            error.handle(framework.options).await;
+                        };
                     }
+                    .instrument(span)
+                    .await;
                 }
             }
         }
@@ -133,43 +222,164 @@ pub async fn dispatch_event<U: Send + Sync, E>(
             interaction: serenity::Interaction::Command(interaction),
         } => {
             let invocation_data = tokio::sync::Mutex::new(Box::new(()) as _);
+            let has_sent_initial_response = std::sync::atomic::AtomicBool::new(false);
+            let options = interaction.data.options();
             let mut parent_commands = Vec::new();
-            if let Err(error) = slash::dispatch_interaction(
-                framework,
-                ctx,
-                interaction,
-                &std::sync::atomic::AtomicBool::new(false),
-                &invocation_data,
-                &interaction.data.options(),
-                &mut parent_commands,
-            )
-            .await
-            {
-                error.handle(framework.options).await;
+            let kind = match interaction.data.kind {
+                serenity::CommandType::ChatInput => "slash",
+                _ => "context_menu",
+            };
+            let span = invocation_span(
+                kind,
+                interaction.guild_id,
+                interaction.channel_id,
+                interaction.user.id,
+            );
+            async {
+                if let Err(error) = slash::dispatch_interaction_event(
+                    framework,
+                    ctx,
+                    interaction,
+                    &has_sent_initial_response,
+                    &invocation_data,
+                    &options,
+                    &mut parent_commands,
+                    Some(raw_event),
+                )
+                .await
+                {
+                    error.handle(framework.options).await;
+                };
             }
+            .instrument(span)
+            .await;
         }
         serenity::FullEvent::InteractionCreate {
             interaction: serenity::Interaction::Autocomplete(interaction),
         } => {
             let invocation_data = tokio::sync::Mutex::new(Box::new(()) as _);
+            let has_sent_initial_response = std::sync::atomic::AtomicBool::new(false);
+            let options = interaction.data.options();
             let mut parent_commands = Vec::new();
-            if let Err(error) = slash::dispatch_autocomplete(
+            let kind = "autocomplete";
+            let span = invocation_span(
+                kind,
+                interaction.guild_id,
+                interaction.channel_id,
+                interaction.user.id,
+            );
+            async {
+                if let Err(error) = slash::dispatch_autocomplete_event(
+                    framework,
+                    ctx,
+                    interaction,
+                    &has_sent_initial_response,
+                    &invocation_data,
+                    &options,
+                    &mut parent_commands,
+                    Some(raw_event),
+                )
+                .await
+                {
+                    error.handle(framework.options).await;
+                };
+            }
+            .instrument(span)
+            .await;
+        }
+        serenity::FullEvent::InteractionCreate {
+            interaction: raw_interaction @ serenity::Interaction::Component(interaction),
+        } => match component::command_button_message(interaction) {
+            Some(msg) => {
+                // The command responds with regular messages, so just acknowledge the click
+                if let Err(e) = interaction
+                    .create_response(ctx, serenity::CreateInteractionResponse::Acknowledge)
+                    .await
+                {
+                    tracing::warn!("failed to acknowledge command button: {}", e);
+                }
+
+                let invocation_data = tokio::sync::Mutex::new(Box::new(()) as _);
+                let mut parent_commands = Vec::new();
+                let span =
+                    invocation_span("component", msg.guild_id, msg.channel_id, msg.author.id);
+                async {
+                    if let Err(error) = prefix::dispatch_command_button(
+                        framework,
+                        ctx,
+                        &msg,
+                        &invocation_data,
+                        &mut parent_commands,
+                        raw_event,
+                    )
+                    .await
+                    {
+                        error.handle(framework.options).await;
+                    };
+                }
+                .instrument(span)
+                .await;
+            }
+            None => {
+                dispatch_unknown_interaction(
+                    framework,
+                    ctx,
+                    raw_interaction,
+                    &interaction.data.custom_id,
+                )
+                .await
+            }
+        },
+        serenity::FullEvent::InteractionCreate {
+            interaction: raw_interaction @ serenity::Interaction::Modal(interaction),
+        } => {
+            dispatch_unknown_interaction(
                 framework,
                 ctx,
-                interaction,
-                &std::sync::atomic::AtomicBool::new(false),
-                &invocation_data,
-                &interaction.data.options(),
-                &mut parent_commands,
+                raw_interaction,
+                &interaction.data.custom_id,
             )
             .await
-            {
-                error.handle(framework.options).await;
-            }
         }
         _ => {}
     }
 
+    let guild_hook_result = match &event {
+        serenity::FullEvent::GuildCreate { guild, is_new } => {
+            match framework.options.on_guild_create {
+                Some(on_guild_create) => on_guild_create(ctx, guild, *is_new, framework).await,
+                None => Ok(()),
+            }
+        }
+        // If the guild is unavailable, it's an outage; otherwise, the bot was removed
+        serenity::FullEvent::GuildDelete { incomplete, full } if !incomplete.unavailable => {
+            // Forget the guild setup state, so it doesn't pile up and the setup runs again if the
+            // bot is added back
+            framework
+                .options
+                .initialized_guilds
+                .lock()
+                .unwrap()
+                .remove(&incomplete.id);
+            match framework.options.on_guild_delete {
+                Some(on_guild_delete) => {
+                    on_guild_delete(ctx, incomplete.id, full.as_ref(), framework).await
+                }
+                None => Ok(()),
+            }
+        }
+        _ => Ok(()),
+    };
+    if let Err(error) = guild_hook_result {
+        let error = crate::FrameworkError::EventHandler {
+            error,
+            ctx,
+            event: &event,
+            framework,
+        };
+        (framework.options.on_error)(error).await;
+    }
+
     // Do this after the framework's Ready handling, so that get_user_data() doesnt
     // potentially block infinitely
     if let Err(error) =
@@ -184,3 +394,18 @@ pub async fn dispatch_event<U: Send + Sync, E>(
         (framework.options.on_error)(error).await;
     }
 }
+
+/// Calls [`crate::FrameworkOptions::on_unknown_interaction`] if the custom ID of the component or
+/// modal interaction doesn't belong to an active framework component
+async fn dispatch_unknown_interaction<U, E>(
+    framework: crate::FrameworkContext<'_, U, E>,
+    ctx: &serenity::Context,
+    interaction: &serenity::Interaction,
+    custom_id: &str,
+) {
+    if let Some(on_unknown_interaction) = framework.options.on_unknown_interaction {
+        if !framework.options.is_component_id_active(custom_id) {
+            on_unknown_interaction(ctx, interaction).await;
+        }
+    }
+}
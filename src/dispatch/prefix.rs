@@ -46,25 +46,16 @@ async fn strip_prefix<'a, U, E>(
         }
     }
 
-    if let Some((prefix, content)) = framework
-        .options
-        .prefix_options
-        .additional_prefixes
-        .iter()
-        .find_map(|prefix| match prefix {
-            &crate::Prefix::Literal(prefix) => Some((prefix, msg.content.strip_prefix(prefix)?)),
-            crate::Prefix::Regex(prefix) => {
-                let regex_match = prefix.find(&msg.content)?;
-                if regex_match.start() == 0 {
-                    Some(msg.content.split_at(regex_match.end()))
-                } else {
-                    None
-                }
+    for prefix in &framework.options.prefix_options.additional_prefixes {
+        if let Some((prefix, content)) = prefix.strip(&msg.content) {
+            let allowed = match framework.options.prefix_options.additional_prefix_check {
+                Some(check) => check(partial_ctx, prefix).await,
+                None => true,
+            };
+            if allowed {
+                return Some((prefix, content));
             }
-            crate::Prefix::__NonExhaustive => unreachable!(),
-        })
-    {
-        return Some((prefix, content));
+        }
     }
 
     if let Some(dynamic_prefix) = framework.options.prefix_options.stripped_dynamic_prefix {
@@ -110,7 +101,7 @@ async fn strip_prefix<'a, U, E>(
 /// subcommands.
 ///
 /// ```rust
-/// #[poise::command(prefix_command)]
+/// #[poise::command(prefix_command, aliases("cmd1"))]
 /// async fn command1(ctx: poise::Context<'_, (), ()>) -> Result<(), ()> { Ok(()) }
 /// #[poise::command(prefix_command, subcommands("command3"))]
 /// async fn command2(ctx: poise::Context<'_, (), ()>) -> Result<(), ()> { Ok(()) }
@@ -125,6 +116,12 @@ async fn strip_prefix<'a, U, E>(
 /// );
 /// assert!(parent_commands.is_empty());
 ///
+/// // The verbatim invoked name is returned, so aliases can be told apart from the primary name
+/// assert_eq!(
+///     poise::find_command(&commands, "cmd1 my arguments", false, &mut parent_commands),
+///     Some((&commands[0], "cmd1", "my arguments")),
+/// );
+///
 /// parent_commands.clear();
 /// assert_eq!(
 ///     poise::find_command(&commands, "command2 command3 my arguments", false, &mut parent_commands),
@@ -184,6 +181,12 @@ pub fn find_command<'a, U, E>(
 }
 
 /// Manually dispatches a message with the prefix framework
+///
+/// Returns `Ok(None)` if the message wasn't a command invocation to be executed: for example if
+/// it didn't start with a prefix, was sent by a bot, invoked a command without prefix
+/// implementation, or was an edit which shouldn't rerun the command. Returns the executed command
+/// if it ran successfully. Everything else, like unknown commands, failed checks or command errors,
+/// is returned as a [`crate::FrameworkError`].
 pub async fn dispatch_message<'a, U: Send + Sync, E>(
     framework: crate::FrameworkContext<'a, U, E>,
     ctx: &'a serenity::Context,
@@ -191,8 +194,32 @@ pub async fn dispatch_message<'a, U: Send + Sync, E>(
     trigger: crate::MessageDispatchTrigger,
     invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
     parent_commands: &'a mut Vec<&'a crate::Command<U, E>>,
-) -> Result<(), crate::FrameworkError<'a, U, E>> {
-    if let Some(ctx) = parse_invocation(
+) -> Result<Option<crate::CommandResult<'a, U, E>>, crate::FrameworkError<'a, U, E>> {
+    dispatch_message_event(
+        framework,
+        ctx,
+        msg,
+        trigger,
+        invocation_data,
+        parent_commands,
+        None,
+    )
+    .await
+}
+
+/// Like [`dispatch_message`], but also stores the event the message came from in
+/// [`crate::PrefixContext::raw_event`]
+#[allow(clippy::too_many_arguments)] // We need to pass them all in to create Context.
+pub(crate) async fn dispatch_message_event<'a, U: Send + Sync, E>(
+    framework: crate::FrameworkContext<'a, U, E>,
+    ctx: &'a serenity::Context,
+    msg: &'a serenity::Message,
+    trigger: crate::MessageDispatchTrigger,
+    invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
+    parent_commands: &'a mut Vec<&'a crate::Command<U, E>>,
+    raw_event: Option<&'a serenity::FullEvent>,
+) -> Result<Option<crate::CommandResult<'a, U, E>>, crate::FrameworkError<'a, U, E>> {
+    let mut ctx = match parse_invocation(
         framework,
         ctx,
         msg,
@@ -202,14 +229,107 @@ pub async fn dispatch_message<'a, U: Send + Sync, E>(
     )
     .await?
     {
-        crate::catch_unwind_maybe(run_invocation(ctx))
-            .await
-            .map_err(|payload| crate::FrameworkError::CommandPanic {
-                payload,
-                ctx: ctx.into(),
-            })??;
+        Some(ctx) => ctx,
+        None => return Ok(None),
+    };
+    ctx.raw_event = raw_event;
+    if is_disregarded_edit(ctx) {
+        return Ok(None);
     }
-    Ok(())
+
+    crate::catch_unwind_maybe(run_invocation(ctx))
+        .await
+        .map_err(|payload| crate::FrameworkError::CommandPanic {
+            payload,
+            ctx: ctx.into(),
+        })??;
+    Ok(Some(crate::CommandResult {
+        command: ctx.command,
+        prefix: ctx.prefix,
+        invoked_command_name: ctx.invoked_command_name,
+        trigger: ctx.trigger,
+        __non_exhaustive: (),
+    }))
+}
+
+/// Runs the command invocation encoded in a button from [`crate::command_custom_id`], given the
+/// message created from the click. Like [`dispatch_message`], but without prefix and filters for
+/// bot messages, since the encoded invocation doesn't have a prefix and the clicking user can't
+/// be a bot.
+pub(crate) async fn dispatch_command_button<'a, U: Send + Sync, E>(
+    framework: crate::FrameworkContext<'a, U, E>,
+    ctx: &'a serenity::Context,
+    msg: &'a serenity::Message,
+    invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
+    parent_commands: &'a mut Vec<&'a crate::Command<U, E>>,
+    raw_event: &'a serenity::FullEvent,
+) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    let trigger = crate::MessageDispatchTrigger::ComponentInteraction;
+    let (command, invoked_command_name, args) = find_command(
+        &framework.options.commands,
+        &msg.content,
+        framework.options.prefix_options.case_insensitive_commands,
+        parent_commands,
+    )
+    .ok_or(crate::FrameworkError::UnknownCommand {
+        ctx,
+        msg,
+        prefix: "",
+        msg_content: &msg.content,
+        framework,
+        invocation_data,
+        trigger,
+    })?;
+    tracing::Span::current().record("command", command.qualified_name.as_str());
+
+    let action = match command.prefix_action {
+        Some(x) => x,
+        None => {
+            tracing::warn!(
+                "button for `{}` clicked, but the command has no prefix implementation",
+                command.qualified_name
+            );
+            return Ok(());
+        }
+    };
+
+    let ctx = crate::PrefixContext {
+        serenity_context: ctx,
+        msg,
+        prefix: "",
+        invoked_command_name,
+        args,
+        framework,
+        data: framework.user_data,
+        parent_commands,
+        command,
+        invocation_data,
+        trigger,
+        action,
+        raw_event: Some(raw_event),
+        __non_exhaustive: (),
+    };
+    crate::catch_unwind_maybe(run_invocation(ctx))
+        .await
+        .map_err(|payload| crate::FrameworkError::CommandPanic {
+            payload,
+            ctx: ctx.into(),
+        })?
+}
+
+/// Finds the command invoked by a message whose prefix was already stripped, according to the
+/// framework options. The returned name ends up in [`crate::PrefixContext::invoked_command_name`]
+fn find_invoked_command<'a, U, E>(
+    options: &'a crate::FrameworkOptions<U, E>,
+    msg_content: &'a str,
+    parent_commands: &mut Vec<&'a crate::Command<U, E>>,
+) -> Option<(&'a crate::Command<U, E>, &'a str, &'a str)> {
+    find_command(
+        &options.commands,
+        msg_content,
+        options.prefix_options.case_insensitive_commands,
+        parent_commands,
+    )
 }
 
 /// Given a Message and some context data, parses prefix, command etc. out of the message and
@@ -247,21 +367,40 @@ pub async fn parse_invocation<'a, U: Send + Sync, E>(
     };
     let msg_content = msg_content.trim_start();
 
-    let (command, invoked_command_name, args) = find_command(
-        &framework.options.commands,
-        msg_content,
-        framework.options.prefix_options.case_insensitive_commands,
-        parent_commands,
-    )
-    .ok_or(crate::FrameworkError::UnknownCommand {
-        ctx,
-        msg,
-        prefix,
-        msg_content,
-        framework,
-        invocation_data,
-        trigger,
-    })?;
+    // An empty prefix (prefix-less channel) matches messages without text content, like
+    // attachment-only messages; those aren't invocations
+    if msg_content.is_empty() && prefix.is_empty() {
+        return Ok(None);
+    }
+    if msg_content.is_empty() {
+        if let Some(on_empty_prefix) = framework.options.prefix_options.on_empty_prefix {
+            let partial_ctx = crate::PartialContext {
+                guild_id: msg.guild_id,
+                channel_id: msg.channel_id,
+                author: &msg.author,
+                serenity_context: ctx,
+                framework,
+                data: framework.user_data,
+                __non_exhaustive: (),
+            };
+            on_empty_prefix(partial_ctx, prefix).await;
+            return Ok(None);
+        }
+    }
+
+    let (command, invoked_command_name, args) =
+        find_invoked_command(framework.options, msg_content, parent_commands).ok_or(
+            crate::FrameworkError::UnknownCommand {
+                ctx,
+                msg,
+                prefix,
+                msg_content,
+                framework,
+                invocation_data,
+                trigger,
+            },
+        )?;
+    tracing::Span::current().record("command", command.qualified_name.as_str());
 
     let action = match command.prefix_action {
         Some(x) => x,
@@ -282,22 +421,28 @@ pub async fn parse_invocation<'a, U: Send + Sync, E>(
         invocation_data,
         trigger,
         action,
+        raw_event: None,
         __non_exhaustive: (),
     }))
 }
 
+/// Checks if we should disregard this invocation because it was triggered by an edit
+fn is_disregarded_edit<U, E>(ctx: crate::PrefixContext<'_, U, E>) -> bool {
+    match ctx.trigger {
+        crate::MessageDispatchTrigger::MessageEdit => !ctx.command.invoke_on_edit,
+        crate::MessageDispatchTrigger::MessageEditFromInvalid => {
+            !ctx.framework.options.prefix_options.execute_untracked_edits
+        }
+        _ => false,
+    }
+}
+
 /// Given an existing parsed command invocation from [`parse_invocation`], run it, including all the
 /// before and after code like checks and built in filters from edit tracking
 pub async fn run_invocation<U, E>(
     ctx: crate::PrefixContext<'_, U, E>,
 ) -> Result<(), crate::FrameworkError<'_, U, E>> {
-    // Check if we should disregard this invocation if it was triggered by an edit
-    if ctx.trigger == crate::MessageDispatchTrigger::MessageEdit && !ctx.command.invoke_on_edit {
-        return Ok(());
-    }
-    if ctx.trigger == crate::MessageDispatchTrigger::MessageEditFromInvalid
-        && !ctx.framework.options.prefix_options.execute_untracked_edits
-    {
+    if is_disregarded_edit(ctx) {
         return Ok(());
     }
 
@@ -310,6 +455,11 @@ pub async fn run_invocation<U, E>(
     }
 
     super::common::check_permissions_and_cooldown(ctx.into()).await?;
+    // Before the command action, which starts the cooldowns; a failed setup mustn't cost the user
+    // a cooldown
+    super::common::run_guild_setup(ctx.into()).await?;
+
+    let _permit = super::common::acquire_invocation_permit(ctx.into()).await?;
 
     // Typing is broadcasted as long as this object is alive
     let _typing_broadcaster = if ctx.command.broadcast_typing {
@@ -332,9 +482,56 @@ pub async fn run_invocation<U, E>(
     }
 
     // Execute command
-    (ctx.action)(ctx).await?;
+    super::common::run_with_timeout(ctx.into(), (ctx.action)(ctx)).await?;
 
     (ctx.framework.options.post_command)(crate::Context::Prefix(ctx)).await;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invoked_command_name() {
+        fn invoked_name<'a>(
+            options: &'a crate::FrameworkOptions<(), String>,
+            msg_content: &'a str,
+        ) -> Option<(&'a str, &'a str, &'a str)> {
+            find_invoked_command(options, msg_content, &mut Vec::new())
+                .map(|(command, invoked_name, args)| (command.name.as_str(), invoked_name, args))
+        }
+
+        let mut options = crate::FrameworkOptions::<(), String> {
+            commands: vec![crate::Command {
+                name: "command1".into(),
+                aliases: vec!["cmd1".into()],
+                prefix_action: Some(|_| Box::pin(async { Ok(()) })),
+                ..Default::default()
+            }],
+            prefix_options: crate::PrefixFrameworkOptions {
+                case_insensitive_commands: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(
+            invoked_name(&options, "command1 args"),
+            Some(("command1", "command1", "args"))
+        );
+        assert_eq!(
+            invoked_name(&options, "cmd1 args"),
+            Some(("command1", "cmd1", "args"))
+        );
+        assert_eq!(invoked_name(&options, "CMD1 args"), None);
+
+        // The name is kept verbatim when matching case-insensitively
+        options.prefix_options.case_insensitive_commands = true;
+        assert_eq!(
+            invoked_name(&options, "CMD1 args"),
+            Some(("command1", "CMD1", "args"))
+        );
+    }
+}
@@ -3,14 +3,19 @@
 use crate::serenity_prelude as serenity;
 
 /// Check if the interaction with the given name and arguments matches any framework command
+///
+/// `guild_id` is only given for top-level commands, whose names may be overridden per guild. The
+/// original name always matches as well, e.g. if the command is also registered globally
 fn find_matching_command<'a, 'b, U, E>(
     interaction_name: &str,
     interaction_options: &'b [serenity::ResolvedOption<'b>],
     commands: &'a [crate::Command<U, E>],
     parent_commands: &mut Vec<&'a crate::Command<U, E>>,
+    guild_id: Option<serenity::GuildId>,
 ) -> Option<(&'a crate::Command<U, E>, &'b [serenity::ResolvedOption<'b>])> {
     commands.iter().find_map(|cmd| {
         if interaction_name != cmd.name
+            && interaction_name != cmd.name_in_guild(guild_id)
             && Some(interaction_name) != cmd.context_menu_name.as_deref()
         {
             return None;
@@ -26,7 +31,13 @@ fn find_matching_command<'a, 'b, U, E>(
                 })
         {
             parent_commands.push(cmd);
-            find_matching_command(sub_name, sub_interaction, &cmd.subcommands, parent_commands)
+            find_matching_command(
+                sub_name,
+                sub_interaction,
+                &cmd.subcommands,
+                parent_commands,
+                None,
+            )
         } else {
             Some((cmd, interaction_options))
         }
@@ -47,12 +58,14 @@ fn extract_command<'a, U, E>(
     invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
     options: &'a [serenity::ResolvedOption<'a>],
     parent_commands: &'a mut Vec<&'a crate::Command<U, E>>,
+    raw_event: Option<&'a serenity::FullEvent>,
 ) -> Result<crate::ApplicationContext<'a, U, E>, crate::FrameworkError<'a, U, E>> {
     let search_result = find_matching_command(
         &interaction.data.name,
         options,
         &framework.options.commands,
         parent_commands,
+        interaction.guild_id,
     );
     let (command, leaf_interaction_options) =
         search_result.ok_or(crate::FrameworkError::UnknownInteraction {
@@ -60,6 +73,7 @@ fn extract_command<'a, U, E>(
             framework,
             interaction,
         })?;
+    tracing::Span::current().record("command", command.qualified_name.as_str());
 
     Ok(crate::ApplicationContext {
         data: framework.user_data,
@@ -72,6 +86,7 @@ fn extract_command<'a, U, E>(
         parent_commands,
         has_sent_initial_response,
         invocation_data,
+        raw_event,
         __non_exhaustive: (),
     })
 }
@@ -97,17 +112,59 @@ pub async fn extract_command_and_run_checks<'a, U, E>(
         invocation_data,
         options,
         parent_commands,
+        None,
     )?;
     super::common::check_permissions_and_cooldown(ctx.into()).await?;
     Ok(ctx)
 }
 
+/// Runs the checks, then takes the concurrency permit, and only then the automatic defer.
+///
+/// A check error is sent as the initial response if nothing was sent yet, or else as a followup
+/// which replaces the deferred message and inherits its visibility. Deferring before the checks
+/// would thus make ephemeral check errors public for non-ephemeral commands. Taking the permit
+/// after the checks keeps invocations which are rejected anyways out of the queue.
+async fn prepare_command<P, Err>(
+    checks: impl std::future::Future<Output = Result<(), Err>>,
+    permit: impl std::future::Future<Output = Result<P, Err>>,
+    defer: impl std::future::Future<Output = ()>,
+) -> Result<P, Err> {
+    checks.await?;
+    let permit = permit.await?;
+    defer.await;
+    Ok(permit)
+}
+
+/// Defers the interaction if [`crate::FrameworkOptions::defer_all_commands`] applies to the
+/// command.
+async fn defer_if_configured<U, E>(ctx: crate::ApplicationContext<'_, U, E>) {
+    if ctx.framework.options.defer_all_commands
+        && !ctx.command.no_defer
+        && !ctx.command.manual_response
+    {
+        // Not fatal: if the interaction is still valid, the command can respond regardless
+        if let Err(e) = ctx.defer_response(ctx.command.ephemeral).await {
+            tracing::warn!("failed to defer interaction: {}", e);
+        }
+    }
+}
+
 /// Given the extracted application command data from [`extract_command`], runs the command,
 /// including all the before and after code like checks.
 async fn run_command<U, E>(
     ctx: crate::ApplicationContext<'_, U, E>,
 ) -> Result<(), crate::FrameworkError<'_, U, E>> {
-    super::common::check_permissions_and_cooldown(ctx.into()).await?;
+    let _permit = prepare_command(
+        async {
+            super::common::check_permissions_and_cooldown(ctx.into()).await?;
+            // Before the command action, which starts the cooldowns; a failed setup mustn't cost
+            // the user a cooldown
+            super::common::run_guild_setup(ctx.into()).await
+        },
+        super::common::acquire_invocation_permit(ctx.into()),
+        defer_if_configured(ctx),
+    )
+    .await?;
 
     (ctx.framework.options.pre_command)(crate::Context::Application(ctx)).await;
 
@@ -124,7 +181,7 @@ async fn run_command<U, E>(
                 .command
                 .slash_action
                 .ok_or(command_structure_mismatch_error)?;
-            action(ctx).await
+            super::common::run_with_timeout(ctx.into(), action(ctx)).await
         }
         serenity::CommandType::User => {
             match (
@@ -134,7 +191,9 @@ async fn run_command<U, E>(
                 (
                     Some(crate::ContextMenuCommandAction::User(action)),
                     Some(serenity::ResolvedTarget::User(user, _)),
-                ) => action(ctx, (*user).clone()).await,
+                ) => {
+                    super::common::run_with_timeout(ctx.into(), action(ctx, (*user).clone())).await
+                }
                 _ => return Err(command_structure_mismatch_error),
             }
         }
@@ -146,7 +205,10 @@ async fn run_command<U, E>(
                 (
                     Some(crate::ContextMenuCommandAction::Message(action)),
                     Some(serenity::ResolvedTarget::Message(message)),
-                ) => action(ctx, (*message).clone()).await,
+                ) => {
+                    super::common::run_with_timeout(ctx.into(), action(ctx, (*message).clone()))
+                        .await
+                }
                 _ => return Err(command_structure_mismatch_error),
             }
         }
@@ -174,6 +236,35 @@ pub async fn dispatch_interaction<'a, U, E>(
     // Need to pass this in from outside because of lifetime issues
     options: &'a [serenity::ResolvedOption<'a>],
     parent_commands: &'a mut Vec<&'a crate::Command<U, E>>,
+) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    dispatch_interaction_event(
+        framework,
+        ctx,
+        interaction,
+        has_sent_initial_response,
+        invocation_data,
+        options,
+        parent_commands,
+        None,
+    )
+    .await
+}
+
+/// Like [`dispatch_interaction`], but also stores the event the interaction came from in
+/// [`crate::ApplicationContext::raw_event`]
+#[allow(clippy::too_many_arguments)] // We need to pass them all in to create Context.
+pub(crate) async fn dispatch_interaction_event<'a, U, E>(
+    framework: crate::FrameworkContext<'a, U, E>,
+    ctx: &'a serenity::Context,
+    interaction: &'a serenity::CommandInteraction,
+    // Need to pass this in from outside because of lifetime issues
+    has_sent_initial_response: &'a std::sync::atomic::AtomicBool,
+    // Need to pass this in from outside because of lifetime issues
+    invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
+    // Need to pass this in from outside because of lifetime issues
+    options: &'a [serenity::ResolvedOption<'a>],
+    parent_commands: &'a mut Vec<&'a crate::Command<U, E>>,
+    raw_event: Option<&'a serenity::FullEvent>,
 ) -> Result<(), crate::FrameworkError<'a, U, E>> {
     let ctx = extract_command(
         framework,
@@ -184,6 +275,7 @@ pub async fn dispatch_interaction<'a, U, E>(
         invocation_data,
         options,
         parent_commands,
+        raw_event,
     )?;
 
     crate::catch_unwind_maybe(run_command(ctx))
@@ -269,6 +361,33 @@ pub async fn dispatch_autocomplete<'a, U, E>(
     invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
     options: &'a [serenity::ResolvedOption<'a>],
     parent_commands: &'a mut Vec<&'a crate::Command<U, E>>,
+) -> Result<(), crate::FrameworkError<'a, U, E>> {
+    dispatch_autocomplete_event(
+        framework,
+        ctx,
+        interaction,
+        has_sent_initial_response,
+        invocation_data,
+        options,
+        parent_commands,
+        None,
+    )
+    .await
+}
+
+/// Like [`dispatch_autocomplete`], but also stores the event the interaction came from in
+/// [`crate::ApplicationContext::raw_event`]
+#[allow(clippy::too_many_arguments)] // We need to pass them all in to create Context.
+pub(crate) async fn dispatch_autocomplete_event<'a, U, E>(
+    framework: crate::FrameworkContext<'a, U, E>,
+    ctx: &'a serenity::Context,
+    interaction: &'a serenity::CommandInteraction,
+    // Need to pass the following in from outside because of lifetime issues
+    has_sent_initial_response: &'a std::sync::atomic::AtomicBool,
+    invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
+    options: &'a [serenity::ResolvedOption<'a>],
+    parent_commands: &'a mut Vec<&'a crate::Command<U, E>>,
+    raw_event: Option<&'a serenity::FullEvent>,
 ) -> Result<(), crate::FrameworkError<'a, U, E>> {
     let ctx = extract_command(
         framework,
@@ -279,6 +398,7 @@ pub async fn dispatch_autocomplete<'a, U, E>(
         invocation_data,
         options,
         parent_commands,
+        raw_event,
     )?;
 
     crate::catch_unwind_maybe(run_autocomplete(ctx))
@@ -290,3 +410,32 @@ pub async fn dispatch_autocomplete<'a, U, E>(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_prepare_command() {
+        use std::sync::atomic::{AtomicBool, Ordering::SeqCst};
+
+        let permit_taken = AtomicBool::new(false);
+        let deferred = AtomicBool::new(false);
+        let permit = || async {
+            permit_taken.store(true, SeqCst);
+            Ok("permit")
+        };
+        let defer = || async { deferred.store(true, SeqCst) };
+
+        // A failing check must be reported before queueing or deferring anything
+        let result = prepare_command(async { Err("check failed") }, permit(), defer()).await;
+        assert_eq!(result, Err("check failed"));
+        assert!(!permit_taken.load(SeqCst));
+        assert!(!deferred.load(SeqCst));
+
+        let result = prepare_command(async { Ok(()) }, permit(), defer()).await;
+        assert_eq!(result, Ok("permit"));
+        assert!(permit_taken.load(SeqCst));
+        assert!(deferred.load(SeqCst));
+    }
+}
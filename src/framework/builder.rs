@@ -27,6 +27,16 @@ pub struct FrameworkBuilder<U, E> {
     commands: Vec<crate::Command<U, E>>,
     /// See [`Self::initialize_owners()`]
     initialize_owners: bool,
+    /// See [`Self::delete_stale_commands()`]
+    delete_stale_commands: bool,
+    /// See [`Self::initial_activity()`]
+    initial_activity: Option<serenity::ActivityData>,
+    /// See [`Self::cache_member_roles()`]
+    member_roles_cache: Option<std::sync::RwLock<crate::MemberRolesCache>>,
+    /// See [`Self::client_settings()`]
+    client_settings: Option<
+        fn(serenity::ClientBuilder, &crate::FrameworkOptions<U, E>) -> serenity::ClientBuilder,
+    >,
 }
 
 impl<U, E> Default for FrameworkBuilder<U, E> {
@@ -36,6 +46,10 @@ impl<U, E> Default for FrameworkBuilder<U, E> {
             options: Default::default(),
             commands: Default::default(),
             initialize_owners: true,
+            delete_stale_commands: false,
+            initial_activity: None,
+            member_roles_cache: None,
+            client_settings: None,
         }
     }
 }
@@ -66,7 +80,8 @@ impl<U, E> FrameworkBuilder<U, E> {
     }
 
     /// Whether to add this bot application's owner and team members to
-    /// [`crate::FrameworkOptions::owners`] automatically
+    /// [`crate::FrameworkOptions::owners`] automatically. Has no effect if owners were set
+    /// explicitly in [`crate::FrameworkOptions::owners`].
     ///
     /// `true` by default
     pub fn initialize_owners(mut self, initialize_owners: bool) -> Self {
@@ -74,6 +89,106 @@ impl<U, E> FrameworkBuilder<U, E> {
         self
     }
 
+    /// Whether the registration builtins delete application commands which were removed from the
+    /// code, see [`crate::FrameworkOptions::delete_stale_commands`]
+    ///
+    /// `false` by default
+    #[must_use]
+    pub fn delete_stale_commands(mut self, delete_stale_commands: bool) -> Self {
+        self.delete_stale_commands = delete_stale_commands;
+        self
+    }
+
+    /// Activity to set on each shard once it's connected, so you don't have to call
+    /// [`serenity::Context::set_activity`] in the setup callback yourself
+    #[must_use]
+    pub fn initial_activity(mut self, activity: serenity::ActivityData) -> Self {
+        self.initial_activity = Some(activity);
+        self
+    }
+
+    /// Cache the roles of up to `max_entries` guild members for up to `max_age` each, to speed up
+    /// permission checks. Requires the `GUILD_MEMBERS` intent.
+    ///
+    /// Disabled by default. See [`crate::FrameworkOptions::member_roles_cache`]
+    #[must_use]
+    pub fn cache_member_roles(mut self, max_entries: usize, max_age: std::time::Duration) -> Self {
+        self.member_roles_cache = Some(crate::MemberRolesCache::new(max_entries, max_age));
+        self
+    }
+
+    /// Modifies the [`serenity::ClientBuilder`] created by [`Self::client_builder`] right before
+    /// the framework is attached to it, e.g. to add event handlers, type map entries or intents
+    /// which depend on the framework options
+    ///
+    /// ```rust
+    /// # use poise::serenity_prelude as serenity;
+    /// # let builder = poise::Framework::<(), ()>::builder();
+    /// builder.client_settings(|client_builder, options| {
+    ///     let mut intents = client_builder.get_intents();
+    ///     if options.commands.iter().any(|c| c.prefix_action.is_some()) {
+    ///         intents |= serenity::GatewayIntents::MESSAGE_CONTENT;
+    ///     }
+    ///     client_builder.intents(intents)
+    /// })
+    /// # ;
+    /// ```
+    #[must_use]
+    pub fn client_settings(
+        mut self,
+        client_settings: fn(
+            serenity::ClientBuilder,
+            &crate::FrameworkOptions<U, E>,
+        ) -> serenity::ClientBuilder,
+    ) -> Self {
+        self.client_settings = Some(client_settings);
+        self
+    }
+
+    /// Build the framework and create a [`serenity::ClientBuilder`] with the framework attached,
+    /// applying [`Self::client_settings`]
+    ///
+    /// Unless [`crate::FrameworkOptions::add_automatic_intents`] is disabled, the intents needed
+    /// by the configured commands are added to `intents`
+    /// ([`crate::FrameworkOptions::automatic_intents`]), and the added ones are logged.
+    ///
+    /// Await the returned builder to create the client:
+    /// ```rust,no_run
+    /// # use poise::serenity_prelude as serenity;
+    /// # async fn _test(builder: poise::FrameworkBuilder<(), ()>) -> Result<(), serenity::Error> {
+    /// let mut client = builder
+    ///     .client_builder("...", serenity::GatewayIntents::non_privileged())
+    ///     .await?;
+    /// client.start().await?;
+    /// # Ok(()) }
+    /// ```
+    pub fn client_builder(
+        mut self,
+        token: impl AsRef<str>,
+        mut intents: serenity::GatewayIntents,
+    ) -> serenity::ClientBuilder
+    where
+        U: Send + Sync + 'static,
+        E: Send + Sync + 'static,
+    {
+        let client_settings = self.client_settings.take();
+        let framework = self.build();
+
+        if framework.options().add_automatic_intents {
+            let added_intents = framework.options().automatic_intents() - intents;
+            if !added_intents.is_empty() {
+                tracing::info!("Automatically adding gateway intents {:?}", added_intents);
+                intents |= added_intents;
+            }
+        }
+
+        let mut client_builder = serenity::ClientBuilder::new(token, intents);
+        if let Some(client_settings) = client_settings {
+            client_builder = client_settings(client_builder, framework.options());
+        }
+        client_builder.framework(framework)
+    }
+
     /// Build the framework with the specified configuration.
     ///
     /// For more information, see [`FrameworkBuilder`]
@@ -90,6 +205,15 @@ impl<U, E> FrameworkBuilder<U, E> {
         // Build framework options by concatenating user-set options with commands and owners
         options.commands.extend(self.commands);
         options.initialize_owners = self.initialize_owners;
+        if self.delete_stale_commands {
+            options.delete_stale_commands = true;
+        }
+        if let Some(activity) = self.initial_activity {
+            options.initial_activity = Some(activity);
+        }
+        if let Some(member_roles_cache) = self.member_roles_cache {
+            options.member_roles_cache = Some(std::sync::Arc::new(member_roles_cache));
+        }
 
         // Create framework with specified settings
         crate::Framework::new(options, setup)
@@ -12,19 +12,37 @@ mod builder;
 ///
 /// Technically, this is just an optional abstraction over [`crate::dispatch_event`] with some
 /// additional conveniences built-in:
+/// - removes commands whose [`crate::Command::available`] returns false:
+///   [`remove_unavailable_commands`]
 /// - fills in correct values for [`crate::Command::qualified_name`]: [`set_qualified_names`]
-/// - spawns a background task to periodically clear edit tracker cache
+/// - periodically purges outdated data like the edit tracker cache, see [`Purgeable`]
 /// - sets up user data on the first Ready event
 /// - keeps track of shard manager and bot ID automatically
 ///
+/// Starting the bot is left to serenity's [`serenity::Client`], so all of its start methods work
+/// with the framework. For example, to split shards across multiple processes, give each process
+/// its own range with [`serenity::Client::start_shard_range`]. Data purging and the shard manager
+/// behave the same regardless of how the client was started, since they are set up in
+/// [`serenity::Framework::init`].
+/// ```rust,no_run
+/// # use poise::serenity_prelude as serenity;
+/// # async fn _foo(framework: poise::Framework<(), serenity::Error>) -> Result<(), serenity::Error> {
+/// let mut client = serenity::ClientBuilder::new("...", serenity::GatewayIntents::non_privileged())
+///     .framework(framework)
+///     .await?;
+/// // This process runs shards 0 to 3 out of 16
+/// client.start_shard_range(0..4, 16).await?;
+/// # Ok(()) }
+/// ```
+///
 /// You can build a bot without [`Framework`]: see the `manual_dispatch` example in the repository
 pub struct Framework<U, E> {
     /// Stores user data. Is initialized on first Ready event
     user_data: std::sync::OnceLock<U>,
     /// Stores bot ID. Is initialized on first Ready event
     bot_id: std::sync::OnceLock<serenity::UserId>,
-    /// Stores the framework options
-    options: crate::FrameworkOptions<U, E>,
+    /// Stores the framework options. Shared with the purge task once the framework has started
+    options: Arc<crate::FrameworkOptions<U, E>>,
 
     /// Initialized to Some during construction; so shouldn't be None at any observable point
     shard_manager: Option<Arc<serenity::ShardManager>>,
@@ -44,7 +62,9 @@ pub struct Framework<U, E> {
     >,
 
     /// Handle to the background task in order to `abort()` it on `Drop`
-    edit_tracker_purge_task: Option<tokio::task::JoinHandle<()>>,
+    purge_task: Option<tokio::task::JoinHandle<()>>,
+    /// When this framework was constructed
+    started_at: std::time::Instant,
 }
 
 impl<U, E> Framework<U, E> {
@@ -69,6 +89,10 @@ impl<U, E> Framework<U, E> {
     /// user ID or connected guilds can be made available to the user data setup function. The user
     /// data setup is not allowed to return Result because there would be no reasonable
     /// course of action on error.
+    ///
+    /// The callback runs exactly once, on the first Ready event, even if it fails. Ready events
+    /// from gateway reconnects or other shards don't run it again; use
+    /// [`crate::FrameworkOptions::on_ready`] for logic that should run on every Ready.
     pub fn new<F>(options: crate::FrameworkOptions<U, E>, setup: F) -> Self
     where
         F: Send
@@ -86,9 +110,10 @@ impl<U, E> Framework<U, E> {
             user_data: std::sync::OnceLock::new(),
             bot_id: std::sync::OnceLock::new(),
             setup: std::sync::Mutex::new(Some(Box::new(setup))),
-            edit_tracker_purge_task: None,
+            purge_task: None,
+            started_at: std::time::Instant::now(),
             shard_manager: None,
-            options,
+            options: Arc::new(options),
         }
     }
 
@@ -97,6 +122,76 @@ impl<U, E> Framework<U, E> {
         &self.options
     }
 
+    /// Returns the commands for modification, e.g. to let plugin modules add their commands after
+    /// the framework has been built.
+    ///
+    /// Only available before the framework has started, i.e. before it's passed to
+    /// [`serenity::ClientBuilder::framework`] or before [`serenity::Framework::init`] is called.
+    /// Commands are prepared on startup (see the [`Framework`] docs for what that entails), so
+    /// changes made here are treated like commands passed to [`crate::FrameworkOptions`].
+    ///
+    /// ```rust
+    /// # use poise::serenity_prelude as serenity;
+    /// # type Context<'a> = poise::Context<'a, (), serenity::Error>;
+    /// #[poise::command(prefix_command)]
+    /// async fn plugin_command(ctx: Context<'_>) -> Result<(), serenity::Error> {
+    ///     Ok(())
+    /// }
+    ///
+    /// let mut framework = poise::Framework::builder()
+    ///     .options(poise::FrameworkOptions::default())
+    ///     .setup(|_, _, _| Box::pin(async { Ok(()) }))
+    ///     .build();
+    /// framework.commands_mut().push(plugin_command());
+    /// assert_eq!(framework.options().commands.len(), 1);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the framework has already started
+    pub fn commands_mut(&mut self) -> &mut Vec<crate::Command<U, E>> {
+        assert!(
+            self.shard_manager.is_none(),
+            "commands can't be modified after the framework has started"
+        );
+        &mut Arc::get_mut(&mut self.options)
+            .expect("framework options are only shared after the framework has started")
+            .commands
+    }
+
+    /// Returns the application commands that [`crate::builtins::register_globally`] would register,
+    /// without making any HTTP requests.
+    ///
+    /// Serialize the result to inspect the exact payload, for example to snapshot-test your
+    /// command definitions:
+    /// ```rust
+    /// # use poise::serenity_prelude as serenity;
+    /// /// Check if the bot is alive
+    /// #[poise::command(slash_command)]
+    /// async fn ping(ctx: poise::Context<'_, (), serenity::Error>) -> Result<(), serenity::Error> {
+    ///     Ok(())
+    /// }
+    ///
+    /// let framework = poise::Framework::builder()
+    ///     .options(poise::FrameworkOptions { commands: vec![ping()], ..Default::default() })
+    ///     .setup(|_, _, _| Box::pin(async { Ok(()) }))
+    ///     .build();
+    ///
+    /// let payload = ::serenity::json::to_value(framework.registration_payload()).unwrap();
+    /// assert_eq!(payload[0]["name"], "ping");
+    /// assert_eq!(payload[0]["description"], "Check if the bot is alive");
+    /// ```
+    pub fn registration_payload(&self) -> Vec<serenity::CreateCommand> {
+        crate::builtins::create_application_commands(&self.options.commands)
+    }
+
+    /// Returns when this framework was created, for measuring the bot's uptime.
+    ///
+    /// Unaffected by gateway reconnects and repeated Ready events.
+    pub fn started_at(&self) -> std::time::Instant {
+        self.started_at
+    }
+
     /// Returns the serenity's client shard manager.
     // Returns a reference so you can plug it into [`FrameworkContext`]
     pub fn shard_manager(&self) -> &Arc<serenity::ShardManager> {
@@ -105,6 +200,39 @@ impl<U, E> Framework<U, E> {
             .expect("framework should have started")
     }
 
+    /// Dispatches an event to the framework. Normally, serenity calls this for you via the
+    /// [`serenity::Framework`] implementation.
+    ///
+    /// [`Framework`] doesn't take over serenity's event handlers, so you can register your own
+    /// [`serenity::EventHandler`] alongside it. This method is for when you want to keep full
+    /// control and forward events from your own handler instead of passing the framework to
+    /// [`serenity::ClientBuilder::framework`]. In that case:
+    /// - call [`serenity::Framework::init`] with the built client before starting it
+    /// - forward [`serenity::FullEvent::Ready`], which runs the setup callback. Other events wait
+    ///   until setup is done
+    /// - forward every other event you receive, too. Prefix commands need `Message`, slash
+    ///   commands need `InteractionCreate`, and edit tracking needs `MessageUpdate` and
+    ///   `MessageDelete`
+    pub async fn dispatch_event(&self, ctx: serenity::Context, event: serenity::FullEvent)
+    where
+        U: Send + Sync,
+    {
+        raw_dispatch_event(self, ctx, event).await
+    }
+
+    /// Enables or disables a command in the given guild, looked up by its qualified name (i.e.
+    /// including parent command names, like `"config set"`)
+    ///
+    /// Returns `false` if no such command exists. See [`crate::Command::set_enabled_in_guild`]
+    pub fn set_command_enabled_in_guild(
+        &self,
+        qualified_name: &str,
+        guild_id: serenity::GuildId,
+        enabled: bool,
+    ) -> bool {
+        set_command_enabled_in_guild(&self.options.commands, qualified_name, guild_id, enabled)
+    }
+
     /// Retrieves user data, or blocks until it has been initialized (once the Ready event has been
     /// received).
     pub async fn user_data(&self) -> &U {
@@ -117,36 +245,53 @@ impl<U, E> Framework<U, E> {
     }
 }
 
-impl<U, E> Drop for Framework<U, E> {
-    fn drop(&mut self) {
-        if let Some(task) = &mut self.edit_tracker_purge_task {
-            task.abort()
-        }
-    }
-}
-
 #[serenity::async_trait]
-impl<U: Send + Sync, E: Send + Sync> serenity::Framework for Framework<U, E> {
+impl<U: Send + Sync + 'static, E: Send + Sync + 'static> serenity::Framework for Framework<U, E> {
     async fn init(&mut self, client: &serenity::Client) {
-        set_qualified_names(&mut self.options.commands);
+        let options = Arc::get_mut(&mut self.options)
+            .expect("framework must not be initialized more than once");
+        remove_unavailable_commands(&mut options.commands);
+        set_qualified_names(&mut options.commands);
+        if let Some(localizer) = options.command_localizer.clone() {
+            crate::localize_commands(&mut options.commands, &*localizer).await;
+        }
 
         message_content_intent_sanity_check(
-            &self.options.prefix_options,
+            &options.prefix_options,
             client.shard_manager.intents(),
         );
+        required_intents_sanity_check(&options.commands, client.shard_manager.intents());
+        if let Err(error) = crate::builtins::validate_application_commands(&options.commands) {
+            tracing::error!("Error: {error}; registering application commands will fail");
+        }
+        for (command, parameter) in
+            crate::builtins::missing_parameter_descriptions(&options.commands)
+        {
+            tracing::warn!(
+                "Parameter `{parameter}` of slash command `{command}` has no description; a placeholder will be shown"
+            );
+        }
+        if options.member_roles_cache.is_some()
+            && !client
+                .shard_manager
+                .intents()
+                .contains(serenity::GatewayIntents::GUILD_MEMBERS)
+        {
+            tracing::warn!(
+                "Warning: GUILD_MEMBERS intent not set; member roles cache will not receive updates"
+            );
+        }
 
         self.shard_manager = Some(client.shard_manager.clone());
 
-        if self.options.initialize_owners {
-            if let Err(e) = insert_owners_from_http(&client.http, &mut self.options.owners).await {
+        // Explicitly configured owners take precedence over the application info
+        if options.initialize_owners && options.owners.is_empty() {
+            if let Err(e) = insert_owners_from_http(&client.http, &mut options.owners).await {
                 tracing::warn!("Failed to insert owners from HTTP: {e}");
             }
         }
 
-        if let Some(edit_tracker) = &self.options.prefix_options.edit_tracker {
-            self.edit_tracker_purge_task =
-                Some(spawn_edit_tracker_purge_task(edit_tracker.clone()));
-        }
+        self.purge_task = Some(spawn_purge_task(self.options.clone()));
     }
 
     async fn dispatch(&self, ctx: serenity::Context, event: serenity::FullEvent) {
@@ -165,25 +310,35 @@ async fn raw_dispatch_event<U, E>(
 {
     if let serenity::FullEvent::Ready { data_about_bot } = &event {
         let _: Result<_, _> = framework.bot_id.set(data_about_bot.user.id);
-        let setup = Option::take(&mut *framework.setup.lock().unwrap());
-        if let Some(setup) = setup {
-            match setup(&ctx, data_about_bot, framework).await {
-                Ok(user_data) => {
-                    let _: Result<_, _> = framework.user_data.set(user_data);
-                }
-                Err(error) => {
-                    (framework.options.on_error)(crate::FrameworkError::Setup {
-                        error,
-                        framework,
-                        data_about_bot,
-                        ctx: &ctx,
-                    })
-                    .await
-                }
+        // Every shard receives its own Ready, so this covers all shards
+        if let Some(activity) = &framework.options.initial_activity {
+            ctx.set_activity(Some(activity.clone()));
+        }
+        let setup_result = run_setup_once(&framework.setup, |setup| {
+            setup(&ctx, data_about_bot, framework)
+        })
+        .await;
+        match setup_result {
+            Some(Ok(user_data)) => {
+                let _: Result<_, _> = framework.user_data.set(user_data);
             }
-        } else {
-            // ignoring duplicate Discord bot ready event
-            // (happens regularly when bot is online for long period of time)
+            Some(Err(error)) => {
+                (framework.options.on_error)(crate::FrameworkError::Setup {
+                    error,
+                    framework,
+                    data_about_bot,
+                    ctx: &ctx,
+                })
+                .await
+            }
+            None => {
+                // ignoring duplicate Discord bot ready event
+                // (happens regularly when bot is online for long period of time)
+            }
+        }
+
+        if let Some(on_ready) = framework.options.on_ready {
+            on_ready(&ctx, data_about_bot).await;
         }
     }
 
@@ -197,10 +352,23 @@ async fn raw_dispatch_event<U, E>(
         options: &framework.options,
         user_data,
         shard_manager: framework.shard_manager(),
+        started_at: framework.started_at,
     };
     crate::dispatch_event(framework, &ctx, event).await;
 }
 
+/// Runs the setup callback with `run`, unless it has already been run. Returns None in that case
+///
+/// Taking the callback out guarantees that it runs at most once, no matter how many Ready events
+/// arrive from reconnects or additional shards, even while the first setup is still running.
+async fn run_setup_once<S, F: std::future::Future>(
+    setup: &std::sync::Mutex<Option<S>>,
+    run: impl FnOnce(S) -> F,
+) -> Option<F::Output> {
+    let setup = setup.lock().unwrap().take()?;
+    Some(run(setup).await)
+}
+
 /// Traverses commands recursively and sets [`crate::Command::qualified_name`] to its actual value
 pub fn set_qualified_names<U, E>(commands: &mut [crate::Command<U, E>]) {
     /// Fills in `qualified_name` fields by appending command name to the parent command name
@@ -215,6 +383,61 @@ pub fn set_qualified_names<U, E>(commands: &mut [crate::Command<U, E>]) {
     }
 }
 
+/// Traverses commands recursively and removes those whose [`crate::Command::available`] returns
+/// false, including their subcommands
+///
+/// ```rust
+/// fn beta_enabled() -> bool {
+///     false // e.g. read a runtime flag or environment variable
+/// }
+///
+/// #[poise::command(slash_command)]
+/// async fn stable(ctx: poise::Context<'_, (), ()>) -> Result<(), ()> { Ok(()) }
+/// #[poise::command(slash_command, available = "beta_enabled")]
+/// async fn beta(ctx: poise::Context<'_, (), ()>) -> Result<(), ()> { Ok(()) }
+///
+/// let mut commands = vec![stable(), beta()];
+/// poise::remove_unavailable_commands(&mut commands);
+/// assert_eq!(commands.len(), 1);
+/// ```
+pub fn remove_unavailable_commands<U, E>(commands: &mut Vec<crate::Command<U, E>>) {
+    commands.retain(|command| (command.available)());
+    for command in commands {
+        remove_unavailable_commands(&mut command.subcommands);
+    }
+}
+
+/// Finds a command or subcommand by its [`crate::Command::qualified_name`]
+fn find_command_by_qualified_name<'a, U, E>(
+    commands: &'a [crate::Command<U, E>],
+    qualified_name: &str,
+) -> Option<&'a crate::Command<U, E>> {
+    commands.iter().find_map(|command| {
+        if command.qualified_name == qualified_name {
+            Some(command)
+        } else {
+            find_command_by_qualified_name(&command.subcommands, qualified_name)
+        }
+    })
+}
+
+/// Shared implementation of [`Framework::set_command_enabled_in_guild`] and
+/// [`crate::FrameworkContext::set_command_enabled_in_guild`]
+pub(crate) fn set_command_enabled_in_guild<U, E>(
+    commands: &[crate::Command<U, E>],
+    qualified_name: &str,
+    guild_id: serenity::GuildId,
+    enabled: bool,
+) -> bool {
+    match find_command_by_qualified_name(commands, qualified_name) {
+        Some(command) => {
+            command.set_enabled_in_guild(guild_id, enabled);
+            true
+        }
+        None => false,
+    }
+}
+
 /// Prints a warning on stderr if a prefix is configured but `MESSAGE_CONTENT` is not set
 fn message_content_intent_sanity_check<U, E>(
     prefix_options: &crate::PrefixFrameworkOptions<U, E>,
@@ -231,8 +454,28 @@ fn message_content_intent_sanity_check<U, E>(
     }
 }
 
+/// Prints a warning for every command whose [`crate::Command::required_intents`] are not all set
+fn required_intents_sanity_check<U, E>(
+    commands: &[crate::Command<U, E>],
+    intents: serenity::GatewayIntents,
+) {
+    for command in commands {
+        let missing_intents = command.required_intents - intents;
+        if !missing_intents.is_empty() {
+            tracing::warn!(
+                "Warning: command `{}` requires intents {:?} which are not set",
+                command.qualified_name,
+                missing_intents,
+            );
+        }
+        required_intents_sanity_check(&command.subcommands, intents);
+    }
+}
+
 /// Runs [`serenity::Http::get_current_application_info`] and inserts owner data into
 /// [`crate::FrameworkOptions::owners`]
+///
+/// If the application is owned by a team, all members who accepted the team invite are inserted.
 pub async fn insert_owners_from_http(
     http: &serenity::Http,
     owners: &mut std::collections::HashSet<serenity::UserId>,
@@ -245,9 +488,8 @@ pub async fn insert_owners_from_http(
 
     if let Some(team) = application_info.team {
         for member in team.members {
-            // This `if` currently always evaluates to true but it becomes important once
-            // Discord implements more team roles than Admin
-            if member.permissions.iter().any(|p| p == "*") {
+            // Pending invitees aren't part of the team yet
+            if member.membership_state == serenity::MembershipState::Accepted {
                 owners.insert(member.user.id);
             }
         }
@@ -256,22 +498,74 @@ pub async fn insert_owners_from_http(
     Ok(())
 }
 
-/// Spawns a background task that periodically purges outdated entries from the edit tracker cache
+/// Data structures which accumulate entries over time and need to be cleaned up regularly to not
+/// gobble up unlimited memory
 ///
-/// Important to avoid the edit tracker gobbling up unlimited memory
+/// [`Framework`] calls this on its [`crate::FrameworkOptions`] every
+/// [`crate::FrameworkOptions::purge_interval`], which in turn purges all framework-owned caches.
+/// If you dispatch events manually without [`Framework`], call it yourself periodically.
+pub trait Purgeable {
+    /// Removes outdated entries
+    fn purge(&self);
+}
+
+impl<U, E> Drop for Framework<U, E> {
+    fn drop(&mut self) {
+        if let Some(task) = &mut self.purge_task {
+            task.abort()
+        }
+    }
+}
+
+/// Spawns a background task that purges outdated framework data every
+/// [`crate::FrameworkOptions::purge_interval`]
 ///
 /// NOT PUB because it's not useful to outside users because it requires a full blown Framework
-/// Because e.g. taking a `PrefixFrameworkOptions` reference won't work because tokio tasks need to be
+/// Because e.g. taking a `FrameworkOptions` reference won't work because tokio tasks need to be
 /// 'static
-fn spawn_edit_tracker_purge_task(
-    edit_tracker: Arc<std::sync::RwLock<crate::EditTracker>>,
+fn spawn_purge_task<U: Send + Sync + 'static, E: Send + Sync + 'static>(
+    options: Arc<crate::FrameworkOptions<U, E>>,
 ) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
+        let mut interval = tokio::time::interval(options.purge_interval);
+        // The first tick completes immediately, but there's nothing to purge yet
+        interval.tick().await;
         loop {
-            edit_tracker.write().unwrap().purge();
-
-            // not sure if the purging interval should be configurable
-            tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+            interval.tick().await;
+            options.purge();
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_setup_runs_once() {
+        let runs = std::sync::atomic::AtomicUsize::new(0);
+        let setup = std::sync::Mutex::new(Some(|| {
+            runs.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        }));
+        let on_ready = || {
+            run_setup_once(&setup, |setup| async move {
+                // Give the other Ready event a chance to arrive while this setup is running
+                tokio::task::yield_now().await;
+                setup();
+            })
+        };
+
+        // Two shards get ready at the same time, then one of them reconnects
+        let (first, second) = futures_util::future::join(on_ready(), on_ready()).await;
+        let reconnect = on_ready().await;
+
+        assert_eq!(runs.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(
+            [first, second, reconnect]
+                .iter()
+                .filter(|x| x.is_some())
+                .count(),
+            1
+        );
+    }
+}
@@ -393,12 +393,15 @@ pub mod choice_parameter;
 pub mod cooldown;
 pub mod dispatch;
 pub mod framework;
+pub mod member_roles;
 pub mod modal;
 pub mod prefix_argument;
+pub mod rate_limit;
 pub mod reply;
 pub mod slash_argument;
 pub mod structs;
 pub mod track_edits;
+pub mod translation;
 mod util;
 pub mod macros {
     //! Procedural macros used in poise, like [`command`]
@@ -408,8 +411,9 @@ pub mod macros {
 
 #[doc(no_inline)]
 pub use {
-    choice_parameter::*, cooldown::*, dispatch::*, framework::*, macros::*, modal::*,
-    prefix_argument::*, reply::*, slash_argument::*, structs::*, track_edits::*,
+    choice_parameter::*, cooldown::*, dispatch::*, framework::*, macros::*, member_roles::*,
+    modal::*, prefix_argument::*, rate_limit::*, reply::*, slash_argument::*, structs::*,
+    track_edits::*, translation::*,
 };
 
 /// See [`builtins`]
@@ -466,4 +470,11 @@ mod tests {
     fn _test_framework_error_send_sync<U: Send + Sync + 'static, E: Send + Sync + 'static>() {
         _assert_send_sync::<crate::FrameworkError<'_, U, E>>();
     }
+
+    fn _assert_error<T: std::error::Error>() {}
+
+    // User data shouldn't need to implement Debug for FrameworkError to be an Error
+    fn _test_framework_error_error<U, E: std::error::Error + 'static>() {
+        _assert_error::<crate::FrameworkError<'_, U, E>>();
+    }
 }
@@ -0,0 +1,205 @@
+//! An optional cache of guild member roles, used to avoid fetching members for every permission
+//! check of commands with [`crate::Command::required_permissions`].
+
+use crate::serenity_prelude as serenity;
+
+/// Key of a cached member
+type MemberKey = (serenity::GuildId, serenity::UserId);
+
+/// A single cached member's role list
+#[derive(Debug)]
+struct CachedRoles {
+    /// Role IDs of the member
+    roles: Vec<serenity::RoleId>,
+    /// Until when the member is timed out, which overrides their role permissions
+    communication_disabled_until: Option<serenity::Timestamp>,
+    /// When this entry was last inserted or updated
+    last_update: std::time::Instant,
+    /// Position of this entry in [`MemberRolesCache::by_age`]
+    generation: u64,
+}
+
+/// Stores the role IDs of guild members, keyed by guild and user.
+///
+/// The cache is filled when a member is fetched for a permission check and kept up to date with
+/// `GuildMemberAddition`, `GuildMemberUpdate` and `GuildMemberRemoval` events. Those events
+/// require the privileged `GUILD_MEMBERS` intent; without it, entries can only expire.
+///
+/// Staleness: role changes are applied as soon as the corresponding gateway event is dispatched.
+/// Events missed while disconnected are not replayed though, so an entry may be outdated for up to
+/// the configured maximum age, after which it's ignored and eventually purged. Only role IDs are
+/// cached, along with timeouts; the permissions of each role are always read from the guild.
+#[derive(Debug)]
+pub struct MemberRolesCache {
+    /// Maximum number of cached members. When full, the least recently updated entry is evicted
+    max_entries: usize,
+    /// Duration after which an entry is considered stale and can be purged
+    max_age: std::time::Duration,
+    /// Cached role lists
+    cache: std::collections::HashMap<MemberKey, CachedRoles>,
+    /// Cached members by generation, i.e. from least to most recently updated, for eviction
+    by_age: std::collections::BTreeMap<u64, MemberKey>,
+    /// Generation of the next inserted or updated entry
+    next_generation: u64,
+}
+
+impl MemberRolesCache {
+    /// Create a cache which holds up to `max_entries` members for up to `max_age` each.
+    ///
+    /// Note: like [`crate::EditTracker`], stale entries are only removed when [`Self::purge`] is
+    /// called. If you supply the created cache to [`crate::Framework`], the framework will take
+    /// care of that periodically.
+    pub fn new(max_entries: usize, max_age: std::time::Duration) -> std::sync::RwLock<Self> {
+        std::sync::RwLock::new(Self {
+            max_entries,
+            max_age,
+            cache: std::collections::HashMap::new(),
+            by_age: std::collections::BTreeMap::new(),
+            next_generation: 0,
+        })
+    }
+
+    /// Returns the cached roles of the given member and until when they're timed out, if present
+    /// and not stale
+    pub fn get(
+        &self,
+        guild_id: serenity::GuildId,
+        user_id: serenity::UserId,
+    ) -> Option<(&[serenity::RoleId], Option<serenity::Timestamp>)> {
+        let entry = self.cache.get(&(guild_id, user_id))?;
+        if entry.last_update.elapsed() >= self.max_age {
+            return None;
+        }
+        Some((&entry.roles, entry.communication_disabled_until))
+    }
+
+    /// Inserts or updates the roles and timeout of the given member
+    pub fn insert(
+        &mut self,
+        guild_id: serenity::GuildId,
+        user_id: serenity::UserId,
+        roles: Vec<serenity::RoleId>,
+        communication_disabled_until: Option<serenity::Timestamp>,
+    ) {
+        if self.max_entries == 0 {
+            return;
+        }
+
+        let key = (guild_id, user_id);
+        match self.cache.get(&key) {
+            Some(entry) => {
+                self.by_age.remove(&entry.generation);
+            }
+            None if self.cache.len() >= self.max_entries => {
+                if let Some((_, oldest)) = self.by_age.pop_first() {
+                    self.cache.remove(&oldest);
+                }
+            }
+            None => {}
+        }
+
+        let generation = self.next_generation;
+        self.next_generation += 1;
+        self.by_age.insert(generation, key);
+        self.cache.insert(
+            key,
+            CachedRoles {
+                roles,
+                communication_disabled_until,
+                last_update: std::time::Instant::now(),
+                generation,
+            },
+        );
+    }
+
+    /// Removes the given member from the cache
+    pub fn remove(&mut self, guild_id: serenity::GuildId, user_id: serenity::UserId) {
+        if let Some(entry) = self.cache.remove(&(guild_id, user_id)) {
+            self.by_age.remove(&entry.generation);
+        }
+    }
+
+    /// Updates the cache according to the given event, if it's a guild member event
+    pub fn process_event(&mut self, event: &serenity::FullEvent) {
+        match event {
+            serenity::FullEvent::GuildMemberAddition { new_member } => {
+                self.insert(
+                    new_member.guild_id,
+                    new_member.user.id,
+                    new_member.roles.clone(),
+                    new_member.communication_disabled_until,
+                );
+            }
+            serenity::FullEvent::GuildMemberUpdate { event, .. } => {
+                self.insert(
+                    event.guild_id,
+                    event.user.id,
+                    event.roles.clone(),
+                    event.communication_disabled_until,
+                );
+            }
+            serenity::FullEvent::GuildMemberRemoval { guild_id, user, .. } => {
+                self.remove(*guild_id, user.id);
+            }
+            _ => {}
+        }
+    }
+
+    /// Forget all members that haven't been updated within the maximum age
+    pub fn purge(&mut self) {
+        let max_age = self.max_age;
+        let by_age = &mut self.by_age;
+        self.cache.retain(|_, entry| {
+            let keep = entry.last_update.elapsed() < max_age;
+            if !keep {
+                by_age.remove(&entry.generation);
+            }
+            keep
+        });
+    }
+}
+
+impl crate::Purgeable for std::sync::RwLock<MemberRolesCache> {
+    fn purge(&self) {
+        self.write().unwrap().purge();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_member_roles_cache() {
+        let guild = serenity::GuildId::new(1);
+        let user = |id| serenity::UserId::new(id);
+        let role = serenity::RoleId::new(10);
+
+        let mut cache = MemberRolesCache::new(2, std::time::Duration::from_secs(60))
+            .into_inner()
+            .unwrap();
+        let timeout = serenity::Timestamp::from_unix_timestamp(1_700_000_000).unwrap();
+        cache.insert(guild, user(1), vec![role], None);
+        cache.insert(guild, user(2), Vec::new(), Some(timeout));
+        assert_eq!(cache.get(guild, user(1)), Some((&[role][..], None)));
+        assert_eq!(cache.get(guild, user(2)), Some((&[][..], Some(timeout))));
+
+        // Inserting beyond capacity evicts the least recently updated member
+        cache.insert(guild, user(1), Vec::new(), None);
+        cache.insert(guild, user(3), Vec::new(), None);
+        assert_eq!(cache.get(guild, user(2)), None);
+        assert!(cache.get(guild, user(1)).is_some());
+        assert!(cache.get(guild, user(3)).is_some());
+        assert_eq!(cache.by_age.len(), 2);
+
+        cache.remove(guild, user(1));
+        assert_eq!(cache.get(guild, user(1)), None);
+
+        // Stale entries are ignored and purged
+        cache.max_age = std::time::Duration::ZERO;
+        assert_eq!(cache.get(guild, user(3)), None);
+        cache.purge();
+        assert!(cache.cache.is_empty());
+        assert!(cache.by_age.is_empty());
+    }
+}
@@ -94,10 +94,23 @@ pub async fn execute_modal<U: Send + Sync, E, M: Modal>(
     timeout: Option<std::time::Duration>,
 ) -> Result<Option<M>, serenity::Error> {
     let interaction = ctx.interaction;
+    let modal_custom_id = format!(
+        "{}{}",
+        ctx.framework
+            .options
+            .component_id_prefix
+            .as_deref()
+            .unwrap_or(""),
+        interaction.id
+    );
+    let _claim = ctx
+        .framework
+        .options
+        .claim_component_ids(modal_custom_id.clone());
     let response = execute_modal_generic(
         ctx.serenity_context,
         |resp| interaction.create_response(ctx, resp),
-        interaction.id.to_string(),
+        modal_custom_id,
         defaults,
         timeout,
     )
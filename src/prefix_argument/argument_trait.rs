@@ -2,7 +2,7 @@
 //! the auto-deref specialization emulation code to e.g. support more strings for bool parameters
 //! instead of the `FromStr` ones
 
-use super::{pop_string, InvalidBool, MissingAttachment, TooFewArguments};
+use super::{pop_string, AmbiguousName, InvalidBool, MissingAttachment, TooFewArguments};
 use crate::serenity_prelude as serenity;
 use std::marker::PhantomData;
 
@@ -135,3 +135,253 @@ impl<'a> PopArgumentHack<'a, serenity::Attachment> for &PhantomData<serenity::At
         Ok((args, attachment_index + 1, attachment))
     }
 }
+
+/// Returns how many entities in the guild are named `name` (case-insensitively), so that
+/// ambiguous name lookups can be rejected. IDs and mentions are never ambiguous.
+async fn count_name_matches<T: NameLookup>(
+    ctx: &serenity::Context,
+    guild_id: Option<serenity::GuildId>,
+    name: &str,
+) -> usize {
+    match guild_id {
+        Some(guild_id) if !name.starts_with('<') && name.parse::<u64>().is_err() => {
+            T::count_named(ctx, guild_id, name).await
+        }
+        _ => 0,
+    }
+}
+
+/// Types whose [`serenity::ArgumentConvert`] impl looks them up by name, which can be ambiguous
+#[async_trait::async_trait]
+trait NameLookup {
+    /// Returns how many entities in the guild match the name. Errors count as zero matches; the
+    /// subsequent [`serenity::ArgumentConvert`] call will report them
+    async fn count_named(ctx: &serenity::Context, guild_id: serenity::GuildId, name: &str)
+        -> usize;
+}
+
+#[async_trait::async_trait]
+impl NameLookup for serenity::Role {
+    async fn count_named(
+        ctx: &serenity::Context,
+        guild_id: serenity::GuildId,
+        name: &str,
+    ) -> usize {
+        #[cfg(feature = "cache")]
+        if let Some(guild) = ctx.cache.guild(guild_id) {
+            return guild
+                .roles
+                .values()
+                .filter(|role| role.name.eq_ignore_ascii_case(name))
+                .count();
+        }
+        match ctx.http.get_guild_roles(guild_id).await {
+            Ok(roles) => roles
+                .iter()
+                .filter(|role| role.name.eq_ignore_ascii_case(name))
+                .count(),
+            Err(_) => 0,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl NameLookup for serenity::GuildChannel {
+    async fn count_named(
+        ctx: &serenity::Context,
+        guild_id: serenity::GuildId,
+        name: &str,
+    ) -> usize {
+        #[cfg(feature = "cache")]
+        if let Some(guild) = ctx.cache.guild(guild_id) {
+            return guild
+                .channels
+                .values()
+                .filter(|channel| channel.name.eq_ignore_ascii_case(name))
+                .count();
+        }
+        match guild_id.channels(&ctx.http).await {
+            Ok(channels) => channels
+                .values()
+                .filter(|channel| channel.name.eq_ignore_ascii_case(name))
+                .count(),
+            Err(_) => 0,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl NameLookup for serenity::Channel {
+    async fn count_named(
+        ctx: &serenity::Context,
+        guild_id: serenity::GuildId,
+        name: &str,
+    ) -> usize {
+        serenity::GuildChannel::count_named(ctx, guild_id, name).await
+    }
+}
+
+/// Whether the member's username or nickname is `name` (case-insensitively), like in serenity's
+/// [`serenity::ArgumentConvert`] impl for members
+fn member_has_name(member: &serenity::Member, name: &str) -> bool {
+    member.user.name.eq_ignore_ascii_case(name)
+        || member
+            .nick
+            .as_deref()
+            .is_some_and(|nick| nick.eq_ignore_ascii_case(name))
+}
+
+/// Result of looking up an entity by name
+#[derive(Debug)]
+enum NameMatch<T> {
+    /// No entity has the name
+    None,
+    /// Exactly one entity has the name
+    Unique(T),
+    /// Multiple entities have the name
+    Ambiguous(usize),
+}
+
+/// Finds the candidates which have the name according to `has_name`
+fn match_name<T>(
+    candidates: impl IntoIterator<Item = T>,
+    has_name: impl Fn(&T) -> bool,
+) -> NameMatch<T> {
+    let mut matches = candidates.into_iter().filter(|x| has_name(x));
+    match (matches.next(), matches.count()) {
+        (None, _) => NameMatch::None,
+        (Some(x), 0) => NameMatch::Unique(x),
+        (Some(_), others) => NameMatch::Ambiguous(others + 1),
+    }
+}
+
+/// Like the other guild model types, but members are looked up by name with a single member
+/// search instead of counting matches first, because members can only be searched via HTTP
+#[async_trait::async_trait]
+impl<'a> PopArgumentHack<'a, serenity::Member> for &PhantomData<serenity::Member> {
+    async fn pop_from(
+        self,
+        args: &'a str,
+        attachment_index: usize,
+        ctx: &serenity::Context,
+        msg: &serenity::Message,
+    ) -> Result<
+        (&'a str, usize, serenity::Member),
+        (Box<dyn std::error::Error + Send + Sync>, Option<String>),
+    > {
+        let (rest, string) =
+            pop_string(args).map_err(|_| (TooFewArguments::default().into(), None))?;
+
+        // IDs, mentions and user tags aren't ambiguous, so serenity can handle them
+        let guild_id = match msg.guild_id {
+            Some(guild_id)
+                if !string.starts_with('<')
+                    && string.parse::<u64>().is_err()
+                    && serenity::utils::parse_user_tag(&string).is_none() =>
+            {
+                guild_id
+            }
+            _ => {
+                return PhantomData::<serenity::Member>
+                    .pop_from(args, attachment_index, ctx, msg)
+                    .await
+            }
+        };
+
+        // Same search that serenity's Member ArgumentConvert impl does
+        let members = guild_id
+            .search_members(&ctx.http, &string, Some(100))
+            .await
+            .unwrap_or_default();
+        match match_name(members, |member| member_has_name(member, &string)) {
+            NameMatch::Unique(member) => Ok((rest.trim_start(), attachment_index, member)),
+            NameMatch::Ambiguous(matches) => {
+                let error = AmbiguousName {
+                    matches,
+                    __non_exhaustive: (),
+                };
+                Err((error.into(), Some(string)))
+            }
+            NameMatch::None => Err((
+                serenity::MemberParseError::NotFoundOrMalformed.into(),
+                Some(string),
+            )),
+        }
+    }
+}
+
+/// Implements `PopArgumentHack` for a guild model type which is parsed via its
+/// [`serenity::ArgumentConvert`] impl, but errors with [`AmbiguousName`] if multiple entities
+/// match the given name
+macro_rules! impl_pop_argument_for_named {
+    ($($type:ty),*) => { $(
+        #[async_trait::async_trait]
+        impl<'a> PopArgumentHack<'a, $type> for &PhantomData<$type> {
+            async fn pop_from(
+                self,
+                args: &'a str,
+                attachment_index: usize,
+                ctx: &serenity::Context,
+                msg: &serenity::Message,
+            ) -> Result<
+                (&'a str, usize, $type),
+                (Box<dyn std::error::Error + Send + Sync>, Option<String>),
+            > {
+                let (_, string) =
+                    pop_string(args).map_err(|_| (TooFewArguments::default().into(), None))?;
+
+                let matches = count_name_matches::<$type>(ctx, msg.guild_id, &string).await;
+                if matches > 1 {
+                    let error = AmbiguousName {
+                        matches,
+                        __non_exhaustive: (),
+                    };
+                    return Err((error.into(), Some(string)));
+                }
+
+                // Not ambiguous, so fall back to the generic serenity::ArgumentConvert impl
+                PhantomData::<$type>
+                    .pop_from(args, attachment_index, ctx, msg)
+                    .await
+            }
+        }
+    )* };
+}
+impl_pop_argument_for_named!(serenity::Role, serenity::GuildChannel, serenity::Channel);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_name() {
+        let member = |name: &str, nick: Option<&str>| {
+            let mut member = serenity::Member::default();
+            member.user.name = name.into();
+            member.nick = nick.map(Into::into);
+            member
+        };
+        let members = vec![
+            member("alice", None),
+            member("bob", Some("Alice")),
+            member("carol", Some("caroline")),
+        ];
+
+        let has_name = |name: &'static str| move |m: &&serenity::Member| member_has_name(m, name);
+        let found = |name| match match_name(&members, has_name(name)) {
+            NameMatch::Unique(member) => Some(member.user.name.as_str()),
+            _ => None,
+        };
+        assert!(matches!(
+            match_name(&members, has_name("ALICE")),
+            NameMatch::Ambiguous(2)
+        ));
+        assert_eq!(found("Caroline"), Some("carol"));
+        assert_eq!(found("carol"), Some("carol"));
+        // Only full names match, even though the member search matches prefixes
+        assert!(matches!(
+            match_name(&members, has_name("car")),
+            NameMatch::None
+        ));
+    }
+}
@@ -0,0 +1,211 @@
+//! Parsing code for [`HumanDuration`] and [`HumanTimestamp`], command parameter types for human
+//! readable durations and points in time
+
+use super::*;
+use std::convert::TryFrom as _;
+
+/// Error thrown when parsing a malformed [`HumanDuration`]
+#[derive(Default, Debug, Clone)]
+pub struct InvalidDuration {
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+impl std::fmt::Display for InvalidDuration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Expected a duration like `10m` or `2h30m`")
+    }
+}
+impl std::error::Error for InvalidDuration {}
+
+/// Error thrown when parsing a malformed [`HumanTimestamp`]
+#[derive(Default, Debug, Clone)]
+pub struct InvalidTimestamp {
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+impl std::fmt::Display for InvalidTimestamp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(
+            "Expected a time like `in 2h`, `30m ago`, `2024-01-31T12:00:00Z` or a Discord timestamp",
+        )
+    }
+}
+impl std::error::Error for InvalidTimestamp {}
+
+/// A command parameter type for durations like `10m`, `2h30m` or `1 day 12 hours`
+///
+/// Each component is a whole number followed by a unit: `s`, `m`, `h`, `d` or `w`, or their long
+/// forms like `min`, `hours` or `weeks`. Usable in prefix and slash commands; in slash commands,
+/// it's a string option which is parsed when the command is invoked.
+///
+/// ```rust
+/// # use std::time::Duration;
+/// # use poise::HumanDuration;
+/// assert_eq!(
+///     "2h30m".parse::<HumanDuration>().unwrap().0,
+///     Duration::from_secs(2 * 60 * 60 + 30 * 60),
+/// );
+/// assert!("2 fortnights".parse::<HumanDuration>().is_err());
+/// ```
+#[derive(Default, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub struct HumanDuration(pub std::time::Duration);
+
+impl std::str::FromStr for HumanDuration {
+    type Err = InvalidDuration;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rest = s.trim();
+        if rest.is_empty() {
+            return Err(InvalidDuration::default());
+        }
+
+        let mut secs: u64 = 0;
+        while !rest.is_empty() {
+            let number_len = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let number = rest[..number_len]
+                .parse::<u64>()
+                .map_err(|_| InvalidDuration::default())?;
+            rest = rest[number_len..].trim_start();
+
+            let unit_len = rest
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(rest.len());
+            let unit_secs = match &*rest[..unit_len].to_ascii_lowercase() {
+                "s" | "sec" | "secs" | "second" | "seconds" => 1,
+                "m" | "min" | "mins" | "minute" | "minutes" => 60,
+                "h" | "hr" | "hrs" | "hour" | "hours" => 60 * 60,
+                "d" | "day" | "days" => 24 * 60 * 60,
+                "w" | "week" | "weeks" => 7 * 24 * 60 * 60,
+                _ => return Err(InvalidDuration::default()),
+            };
+            rest = rest[unit_len..].trim_start();
+
+            secs = number
+                .checked_mul(unit_secs)
+                .and_then(|component| secs.checked_add(component))
+                .ok_or(InvalidDuration::default())?;
+        }
+
+        Ok(Self(std::time::Duration::from_secs(secs)))
+    }
+}
+
+impl std::fmt::Display for HumanDuration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut secs = self.0.as_secs();
+        if secs == 0 {
+            return f.write_str("0s");
+        }
+        for (unit, unit_secs) in [("d", 24 * 60 * 60), ("h", 60 * 60), ("m", 60), ("s", 1)] {
+            if secs >= unit_secs {
+                write!(f, "{}{}", secs / unit_secs, unit)?;
+                secs %= unit_secs;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A command parameter type for points in time, relative or absolute
+///
+/// Accepted formats:
+/// - `now`
+/// - a [`HumanDuration`] in the future, like `2h`, `in 2h` or `in 1 day`
+/// - a [`HumanDuration`] in the past, like `30m ago`
+/// - an RFC 3339 timestamp, like `2024-01-31T12:00:00Z`
+/// - a Discord timestamp, like `<t:1706702400:R>`, or plain Unix seconds
+///
+/// Relative times are resolved against the time of parsing. Usable in prefix and slash commands;
+/// in slash commands, it's a string option which is parsed when the command is invoked. In prefix
+/// commands, formats containing spaces must be quoted, like `"in 2h"`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct HumanTimestamp(pub serenity::Timestamp);
+
+impl std::str::FromStr for HumanTimestamp {
+    type Err = InvalidTimestamp;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let from_unix = |secs: i64| {
+            serenity::Timestamp::from_unix_timestamp(secs).map_err(|_| InvalidTimestamp::default())
+        };
+        let offset_now = |duration: &str, sign: i64| {
+            let duration = duration
+                .parse::<HumanDuration>()
+                .map_err(|_| InvalidTimestamp::default())?;
+            let secs =
+                i64::try_from(duration.0.as_secs()).map_err(|_| InvalidTimestamp::default())?;
+            let now = serenity::Timestamp::now().unix_timestamp();
+            from_unix(
+                now.checked_add(sign * secs)
+                    .ok_or(InvalidTimestamp::default())?,
+            )
+        };
+
+        let timestamp = if s.eq_ignore_ascii_case("now") {
+            serenity::Timestamp::now()
+        } else if let Some(secs) = s
+            .strip_prefix("<t:")
+            .and_then(|s| s.strip_suffix('>'))
+            .map(|s| s.split(':').next().unwrap_or(s))
+        {
+            from_unix(secs.parse().map_err(|_| InvalidTimestamp::default())?)?
+        } else if let Ok(secs) = s.parse::<i64>() {
+            from_unix(secs)?
+        } else if let Ok(timestamp) = s.parse::<serenity::Timestamp>() {
+            timestamp
+        } else if let Some(duration) = s.strip_suffix("ago") {
+            offset_now(duration, -1)?
+        } else if let Some(duration) = s.strip_prefix("in ") {
+            offset_now(duration, 1)?
+        } else {
+            offset_now(s, 1)?
+        };
+        Ok(Self(timestamp))
+    }
+}
+
+impl std::fmt::Display for HumanTimestamp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_human_time() {
+        let duration = |s: &str| s.parse::<HumanDuration>().map(|d| d.0.as_secs()).ok();
+        assert_eq!(duration("10m"), Some(600));
+        assert_eq!(duration("1 day 12 Hours"), Some(36 * 60 * 60));
+        assert_eq!(duration("1w1s"), Some(7 * 24 * 60 * 60 + 1));
+        assert_eq!(duration(""), None);
+        assert_eq!(duration("10"), None);
+        assert_eq!(duration("m"), None);
+        assert_eq!(duration("-5m"), None);
+        assert_eq!(duration("99999999999999999999w"), None);
+        assert_eq!(
+            HumanDuration(std::time::Duration::from_secs(90061)).to_string(),
+            "1d1h1m1s"
+        );
+
+        let timestamp = |s: &str| {
+            s.parse::<HumanTimestamp>()
+                .map(|t| t.0.unix_timestamp())
+                .ok()
+        };
+        let now = serenity::Timestamp::now().unix_timestamp();
+        assert_eq!(timestamp("<t:1706702400:R>"), Some(1706702400));
+        assert_eq!(timestamp("<t:1706702400>"), Some(1706702400));
+        assert_eq!(timestamp("1706702400"), Some(1706702400));
+        assert_eq!(timestamp("2024-01-31T12:00:00Z"), Some(1706702400));
+        assert!(timestamp("in 2h").is_some_and(|t| (t - now - 7200).abs() <= 1));
+        assert!(timestamp("2h").is_some_and(|t| (t - now - 7200).abs() <= 1));
+        assert!(timestamp("30m ago").is_some_and(|t| (now - t - 1800).abs() <= 1));
+        assert_eq!(timestamp("tomorrow"), None);
+    }
+}
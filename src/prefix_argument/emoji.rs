@@ -0,0 +1,213 @@
+//! Parsing code for [`EmojiArgument`], a command parameter type for unicode and custom emojis
+
+use super::*;
+
+/// Error thrown when parsing a malformed [`EmojiArgument`]
+#[derive(Default, Debug, Clone)]
+pub struct InvalidEmoji {
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+impl std::fmt::Display for InvalidEmoji {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Expected an emoji like 🎉 or a custom emoji")
+    }
+}
+impl std::error::Error for InvalidEmoji {}
+
+/// A command parameter type for emojis, e.g. for reaction role commands
+///
+/// Accepts unicode emojis like `🎉` or `👍🏽`, custom emoji mentions like `<:ferris:123>` or
+/// `<a:ferris:123>`, and raw custom emoji IDs. Usable in prefix and slash commands; in slash
+/// commands, it's a string option which is parsed when the command is invoked.
+///
+/// Custom emojis of the guild the command was invoked in are resolved, so their name and
+/// animated state are known even if only the ID was given. Custom emojis from other guilds can
+/// still be used by mention; raw IDs of them are accepted too, but lack name and animated state.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum EmojiArgument {
+    /// A unicode emoji
+    Unicode(String),
+    /// A custom emoji
+    Custom {
+        /// ID of the emoji
+        id: serenity::EmojiId,
+        /// Name of the emoji, if known
+        name: Option<String>,
+        /// Whether the emoji is animated
+        animated: bool,
+    },
+}
+
+impl EmojiArgument {
+    /// Parses the emoji syntax, without resolving custom emojis
+    fn parse(s: &str) -> Option<Self> {
+        if let Some(emoji) = serenity::parse_emoji(s) {
+            return Some(Self::Custom {
+                id: emoji.id,
+                name: Some(emoji.name),
+                animated: emoji.animated,
+            });
+        }
+        // Snowflakes of emojis have at least 17 digits; this keeps short numbers from being
+        // mistaken for IDs
+        let is_snowflake = s.len() >= 17 && s.bytes().all(|b| b.is_ascii_digit());
+        if let Some(id) = is_snowflake
+            .then(|| s.parse::<std::num::NonZeroU64>().ok())
+            .flatten()
+        {
+            return Some(Self::Custom {
+                id: serenity::EmojiId::from(id),
+                name: None,
+                animated: false,
+            });
+        }
+        if is_unicode_emoji(s) {
+            return Some(Self::Unicode(s.to_owned()));
+        }
+        None
+    }
+}
+
+/// Checks whether the given string consists of a single unicode emoji, including skin tone
+/// modifiers, flags, keycaps and ZWJ sequences like 👨‍👩‍👧
+///
+/// This checks for code points in the emoji blocks rather than against the full list of emojis,
+/// so it accepts some unassigned code points and doesn't limit the length of sequences.
+fn is_unicode_emoji(s: &str) -> bool {
+    let is_pictograph = |c: char| {
+        matches!(c as u32,
+            0x1F000..=0x1FAFF // Pictographs, emoticons, transport, flags, skin tones, ...
+            | 0x2300..=0x23FF // Miscellaneous technical, e.g. ⌚
+            | 0x2600..=0x27BF // Miscellaneous symbols and dingbats, e.g. ☀ and ✅
+            | 0x2B00..=0x2BFF // Miscellaneous symbols and arrows, e.g. ⭐
+            | 0x2190..=0x21FF // Arrows, e.g. ↩
+            | 0x00A9 | 0x00AE | 0x203C | 0x2049 | 0x2122 | 0x2139 | 0x24C2
+            | 0x25AA..=0x25FE | 0x2934 | 0x2935 | 0x3030 | 0x303D | 0x3297 | 0x3299
+        )
+    };
+    let is_modifier = |c: char| {
+        matches!(
+            c as u32,
+            0x200D // Zero width joiner
+            | 0xFE0E | 0xFE0F // Variation selectors
+            | 0x20E3 // Combining enclosing keycap
+            | 0xE0020..=0xE007F // Tags, used in subdivision flags
+        )
+    };
+    let is_keycap_base = |c: char| c.is_ascii_digit() || c == '#' || c == '*';
+
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) if is_pictograph(c) => chars.all(|c| is_pictograph(c) || is_modifier(c)),
+        // Keycaps like 1️⃣ consist of an ASCII character, an optional variation selector and the
+        // enclosing keycap
+        Some(c) if is_keycap_base(c) => {
+            let rest = chars.as_str();
+            rest == "\u{20E3}" || rest == "\u{FE0F}\u{20E3}"
+        }
+        _ => false,
+    }
+}
+
+#[async_trait::async_trait]
+impl serenity::ArgumentConvert for EmojiArgument {
+    type Err = InvalidEmoji;
+
+    async fn convert(
+        ctx: impl serenity::CacheHttp,
+        guild_id: Option<serenity::GuildId>,
+        _channel_id: Option<serenity::ChannelId>,
+        s: &str,
+    ) -> Result<Self, Self::Err> {
+        let emoji = Self::parse(s.trim()).ok_or_else(InvalidEmoji::default)?;
+
+        let (id, guild_id) = match (&emoji, guild_id) {
+            (Self::Custom { id, .. }, Some(guild_id)) => (*id, guild_id),
+            _ => return Ok(emoji),
+        };
+
+        #[cfg(feature = "cache")]
+        let cached = ctx
+            .cache()
+            .and_then(|cache| Some(cache.guild(guild_id)?.emojis.get(&id)?.clone()));
+        #[cfg(not(feature = "cache"))]
+        let cached: Option<serenity::Emoji> = None;
+
+        let guild_emoji = match cached {
+            Some(guild_emoji) => Some(guild_emoji),
+            None => guild_id.emoji(ctx.http(), id).await.ok(),
+        };
+        Ok(match guild_emoji {
+            Some(guild_emoji) => Self::Custom {
+                id,
+                name: Some(guild_emoji.name),
+                animated: guild_emoji.animated,
+            },
+            None => emoji,
+        })
+    }
+}
+
+impl From<EmojiArgument> for serenity::ReactionType {
+    fn from(emoji: EmojiArgument) -> Self {
+        match emoji {
+            EmojiArgument::Unicode(emoji) => Self::Unicode(emoji),
+            EmojiArgument::Custom { id, name, animated } => Self::Custom { animated, id, name },
+        }
+    }
+}
+
+impl std::fmt::Display for EmojiArgument {
+    /// Formats the emoji so that Discord renders it
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unicode(emoji) => f.write_str(emoji),
+            Self::Custom { id, name, animated } => write!(
+                f,
+                "<{}:{}:{}>",
+                if *animated { "a" } else { "" },
+                name.as_deref().unwrap_or("_"),
+                id
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_emoji() {
+        for emoji in ["🎉", "👍🏽", "👨‍👩‍👧", "🇩🇪", "❤️", "1️⃣", "#⃣"].iter().copied()
+        {
+            assert_eq!(
+                EmojiArgument::parse(emoji),
+                Some(EmojiArgument::Unicode(emoji.into())),
+            );
+        }
+        for not_emoji in ["", "a", "1", "🎉a", "hello", "<:a>", "0"].iter().copied() {
+            assert_eq!(EmojiArgument::parse(not_emoji), None, "{}", not_emoji);
+        }
+
+        let custom = EmojiArgument::parse("<a:ferris:1193253734857637918>").unwrap();
+        assert_eq!(
+            custom,
+            EmojiArgument::Custom {
+                id: serenity::EmojiId::new(1193253734857637918),
+                name: Some("ferris".into()),
+                animated: true,
+            },
+        );
+        assert_eq!(custom.to_string(), "<a:ferris:1193253734857637918>");
+        assert_eq!(
+            EmojiArgument::parse("1193253734857637918"),
+            Some(EmojiArgument::Custom {
+                id: serenity::EmojiId::new(1193253734857637918),
+                name: None,
+                animated: false,
+            }),
+        );
+    }
+}
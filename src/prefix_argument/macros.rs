@@ -59,7 +59,7 @@ macro_rules! _parse_prefix {
             let token: Option<$type> = None;
             $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ]);
         } else {
-            let input = $args.trim_start();
+            let input = $crate::rest_argument(&$msg.content, $args);
             match <$type as $crate::serenity_prelude::ArgumentConvert>::convert(
                 $ctx, $msg.guild_id, Some($msg.channel_id), input
             ).await {
@@ -73,6 +73,22 @@ macro_rules! _parse_prefix {
         }
     };
 
+    // Consume #[fuzzy] Option<T> greedy-first, where T is a choice parameter
+    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
+        (#[fuzzy] Option<$type:ty $(,)?>)
+        $( $rest:tt )*
+    ) => {
+        match $crate::pop_fuzzy_choice::<$type>(&$args, $attachment_index, $ctx, $msg).await {
+            Ok(($args, $attachment_index, token)) => {
+                let token: Option<$type> = Some(token);
+                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
+            },
+            Err(e) => $error = e,
+        }
+        let token: Option<$type> = None;
+        $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
+    };
+
     // Consume Vec<T> greedy-first
     ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
         (Vec<$type:ty $(,)?>)
@@ -109,6 +125,48 @@ macro_rules! _parse_prefix {
         }
     };
 
+    // Consume #[until_flag("flag", ...)] Option<T> until one of the given flags
+    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
+        (#[until_flag( $($flag:literal),* )] Option<$type:ty $(,)?>)
+        $( $rest:tt )*
+    ) => {
+        let (input, $args) = $crate::split_until_flag($args, &[ $($flag),* ]);
+        if input.is_empty() {
+            let token: Option<$type> = None;
+            $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
+        } else {
+            match <$type as $crate::serenity_prelude::ArgumentConvert>::convert(
+                $ctx, $msg.guild_id, Some($msg.channel_id), input
+            ).await {
+                Ok(token) => {
+                    let token = Some(token);
+                    $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
+                },
+                Err(e) => $error = (e.into(), Some(input.to_owned())),
+            }
+        }
+    };
+
+    // Consume #[until_flag("flag", ...)] T until one of the given flags
+    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
+        (#[until_flag( $($flag:literal),* )] $type:ty)
+        $( $rest:tt )*
+    ) => {
+        let (input, $args) = $crate::split_until_flag($args, &[ $($flag),* ]);
+        if input.is_empty() {
+            $error = ($crate::TooFewArguments::default().into(), None);
+        } else {
+            match <$type as $crate::serenity_prelude::ArgumentConvert>::convert(
+                $ctx, $msg.guild_id, Some($msg.channel_id), input
+            ).await {
+                Ok(token) => {
+                    $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
+                },
+                Err(e) => $error = (e.into(), Some(input.to_owned())),
+            }
+        }
+    };
+
     // deliberately no `#[rest] &str` here because &str isn't supported anywhere else and this
     // inconsistency and also the further implementation work makes it not worth it.
 
@@ -117,10 +175,10 @@ macro_rules! _parse_prefix {
         // question to my former self: why the $(poise::)* ?
         (#[rest] $(poise::)* $type:ty)
     ) => {
-        let input = $args.trim_start();
-        if input.is_empty() {
+        if $args.trim_start().is_empty() {
             $error = ($crate::TooFewArguments::default().into(), None);
         } else {
+            let input = $crate::rest_argument(&$msg.content, $args);
             match <$type as $crate::serenity_prelude::ArgumentConvert>::convert(
                 $ctx, $msg.guild_id, Some($msg.channel_id), input
             ).await {
@@ -151,6 +209,19 @@ macro_rules! _parse_prefix {
         }
     };
 
+    // Consume #[fuzzy] T, where T is a choice parameter
+    ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
+        (#[fuzzy] $type:ty)
+        $( $rest:tt )*
+    ) => {
+        match $crate::pop_fuzzy_choice::<$type>(&$args, $attachment_index, $ctx, $msg).await {
+            Ok(($args, $attachment_index, token)) => {
+                $crate::_parse_prefix!($ctx $msg $args $attachment_index => [ $error $($preamble)* token ] $($rest)* );
+            },
+            Err(e) => $error = e,
+        }
+    };
+
     // Consume T
     ( $ctx:ident $msg:ident $args:ident $attachment_index:ident => [ $error:ident $($preamble:tt)* ]
         ($type:ty)
@@ -218,7 +289,7 @@ assert_eq!(
 #[macro_export]
 macro_rules! parse_prefix_args {
     ($ctx:expr, $msg:expr, $args:expr, $attachment_index:expr => $(
-        $( #[$attr:ident] )?
+        $( #[$attr:ident $( ( $( $attr_arg:literal ),* ) )? ] )?
         ( $($type:tt)* )
     ),* $(,)? ) => {
         async {
@@ -235,7 +306,7 @@ macro_rules! parse_prefix_args {
             $crate::_parse_prefix!(
                 ctx msg args attachment_index => [error]
                 $(
-                    ($( #[$attr] )? $($type)*)
+                    ($( #[$attr $( ( $( $attr_arg ),* ) )? ] )? $($type)*)
                 )*
             );
             Err(error)
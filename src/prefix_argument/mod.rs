@@ -5,6 +5,12 @@
 mod code_block;
 pub use code_block::*;
 
+mod duration;
+pub use duration::*;
+
+mod emoji;
+pub use emoji::*;
+
 mod key_value_args;
 pub use key_value_args::*;
 
@@ -20,7 +26,7 @@ use crate::serenity_prelude as serenity;
 /// escaping.
 ///
 /// Leading whitespace will be trimmed; trailing whitespace is not consumed.
-fn pop_string(args: &str) -> Result<(&str, String), crate::TooFewArguments> {
+pub(crate) fn pop_string(args: &str) -> Result<(&str, String), crate::TooFewArguments> {
     // TODO: consider changing the behavior to parse quotes literally if they're in the middle
     // of the string:
     // - `"hello world"` => `hello world`
@@ -59,6 +65,142 @@ fn pop_string(args: &str) -> Result<(&str, String), crate::TooFewArguments> {
     Ok((chars.as_str(), output))
 }
 
+/// Returns the verbatim text of a `#[rest]` parameter, given the message content and the
+/// remaining arguments. Used by [`crate::parse_prefix_args!`].
+///
+/// The preceding parsing steps trim all leading whitespace off the remaining arguments, which
+/// would lose the indentation of the first line if the rest starts on a new line. So if `args` is
+/// a suffix of `content`, only the whitespace separating it from the preceding argument on the
+/// same line and at most one line break are stripped. Whitespace and line breaks within the rest
+/// are never touched.
+#[doc(hidden)]
+pub fn rest_argument<'a>(content: &'a str, args: &'a str) -> &'a str {
+    let args = args.trim_start();
+    if !content.ends_with(args) {
+        return args;
+    }
+
+    let preceding = &content[..content.len() - args.len()];
+    let rest = &content[preceding.trim_end().len()..];
+    let rest = rest.trim_start_matches(|c: char| c.is_whitespace() && c != '\n' && c != '\r');
+    rest.strip_prefix("\r\n")
+        .or_else(|| rest.strip_prefix('\n'))
+        .unwrap_or(rest)
+}
+
+/// Splits off the text before the first word which matches one of the given flags
+/// (case-insensitively), for `#[until_flag]` parameters. Used by [`crate::parse_prefix_args!`].
+///
+/// Returns the text, with surrounding whitespace trimmed, and the rest of the arguments starting
+/// at the flag. Words are tokenized like with other arguments, so a quoted flag like `"silent"` is
+/// part of the text, including its quotes.
+#[doc(hidden)]
+pub fn split_until_flag<'a>(args: &'a str, flags: &[&str]) -> (&'a str, &'a str) {
+    let args = args.trim_start();
+    let mut remaining = args;
+    loop {
+        let token_start = remaining.trim_start();
+        let rest = match pop_string(token_start) {
+            Ok((rest, _)) => rest,
+            Err(_) => return (args.trim_end(), ""),
+        };
+        let raw_token = &token_start[..token_start.len() - rest.len()];
+        if flags
+            .iter()
+            .any(|flag| raw_token.eq_ignore_ascii_case(flag))
+        {
+            let text = &args[..args.len() - token_start.len()];
+            return (text.trim_end(), token_start);
+        }
+        remaining = rest;
+    }
+}
+
+/// The error type of [`crate::parse_prefix_args`]: the error and the input which caused it
+type ParseError = (Box<dyn std::error::Error + Send + Sync>, Option<String>);
+
+/// Implements [`crate::PrefixFrameworkOptions::on_extra_arguments`] for the code generated by the
+/// [`crate::command`] macro.
+///
+/// If `parsed` failed with [`TooManyArguments`] and `behavior` allows it, the arguments are
+/// parsed again by `reparse`, which has an additional catch-all parameter. Its value is dropped,
+/// or passed to `append` for [`crate::ExtraArgumentsBehavior::AppendToLastArgument`]. `append`
+/// is `None` if the last parameter can't take extra arguments.
+#[doc(hidden)]
+pub async fn handle_extra_arguments<T, F>(
+    behavior: crate::ExtraArgumentsBehavior,
+    parsed: Result<T, ParseError>,
+    reparse: impl FnOnce() -> F,
+    append: Option<fn(&mut T, String)>,
+) -> Result<T, ParseError>
+where
+    F: std::future::Future<Output = Result<(T, Option<String>), ParseError>>,
+{
+    let error = match parsed {
+        Err(error) if error.0.is::<TooManyArguments>() => error,
+        parsed => return parsed,
+    };
+    let append = match (behavior, append) {
+        (crate::ExtraArgumentsBehavior::Ignore, _) => None,
+        (crate::ExtraArgumentsBehavior::AppendToLastArgument, Some(append)) => Some(append),
+        _ => return Err(error),
+    };
+
+    match reparse().await {
+        Ok((mut args, extra)) => {
+            if let (Some(append), Some(extra)) = (append, extra) {
+                append(&mut args, extra);
+            }
+            Ok(args)
+        }
+        // Report the original error, which is not about the catch-all parameter
+        Err(_) => Err(error),
+    }
+}
+
+/// Converts arguments separated by `delimiter` into the whitespace-separated and quoted form
+/// that the prefix argument parsers understand. Used for [`crate::Command::delimiter`].
+///
+/// Each argument is trimmed and quoted, so it may contain whitespace. Quotes and backslashes are
+/// taken literally. If `max_arguments` is given, only that many arguments are split off and the
+/// remaining string is appended verbatim, for a trailing `#[rest]` parameter.
+///
+/// ```rust
+/// assert_eq!(
+///     poise::requote_delimited_args("a , b c,d", ',', None),
+///     r#""a" "b c" "d""#,
+/// );
+/// assert_eq!(
+///     poise::requote_delimited_args("a, b c, d", ',', Some(1)),
+///     r#""a" b c, d"#,
+/// );
+/// ```
+pub fn requote_delimited_args(args: &str, delimiter: char, max_arguments: Option<usize>) -> String {
+    let mut output = String::new();
+    let mut remaining = args.trim();
+    let mut num_arguments = 0;
+    while !remaining.is_empty() {
+        if max_arguments.is_some_and(|max| num_arguments >= max) {
+            output.push_str(remaining);
+            return output;
+        }
+
+        let (argument, rest) = remaining.split_once(delimiter).unwrap_or((remaining, ""));
+        output.push('"');
+        for c in argument.trim().chars() {
+            if c == '"' || c == '\\' {
+                output.push('\\');
+            }
+            output.push(c);
+        }
+        output.push_str("\" ");
+        remaining = rest.trim_start();
+        num_arguments += 1;
+    }
+    output.truncate(output.trim_end().len());
+    output
+}
+
 /// Error thrown if user passes too many arguments to a command
 #[derive(Default, Debug)]
 pub struct TooManyArguments {
@@ -112,6 +254,49 @@ impl std::fmt::Display for InvalidChoice {
 }
 impl std::error::Error for InvalidChoice {}
 
+/// Error thrown when the user input for a `#[fuzzy]` ChoiceParameter-derived enum is a prefix of
+/// multiple choice names
+#[derive(Default, Debug)]
+pub struct AmbiguousChoice {
+    /// Names of all choices that start with the user input
+    pub candidates: Vec<String>,
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+impl std::fmt::Display for AmbiguousChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Ambiguous choice, could be any of: ")?;
+        for (i, candidate) in self.candidates.iter().enumerate() {
+            if i != 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "`{}`", candidate)?;
+        }
+        Ok(())
+    }
+}
+impl std::error::Error for AmbiguousChoice {}
+
+/// Error thrown when the name given for a role, channel or member matches multiple of them in the
+/// guild
+#[derive(Default, Debug)]
+pub struct AmbiguousName {
+    /// How many entities have this name
+    pub matches: usize,
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+impl std::fmt::Display for AmbiguousName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} matches for this name; please use a mention or ID instead",
+            self.matches
+        )
+    }
+}
+impl std::error::Error for AmbiguousName {}
+
 /// Error thrown when the user enters a string that is not recognized as a boolean
 #[derive(Default, Debug)]
 pub struct InvalidBool {
@@ -144,3 +329,141 @@ fn test_pop_string() {
         assert_eq!(pop_string(string).unwrap().1, arg);
     }
 }
+
+#[cfg(test)]
+#[test]
+fn test_requote_delimited_args() {
+    for &(args, expected) in &[
+        ("", &[][..]),
+        ("one", &["one"]),
+        (
+            "first option, second option,third",
+            &["first option", "second option", "third"],
+        ),
+        (r#"say "hi", C:\dir"#, &[r#"say "hi""#, r"C:\dir"]),
+        ("a,,b,", &["a", "", "b"]),
+    ] {
+        let requoted = requote_delimited_args(args, ',', None);
+        let mut remaining = requoted.as_str();
+        let mut tokens = Vec::new();
+        while let Ok((rest, token)) = pop_string(remaining) {
+            tokens.push(token);
+            remaining = rest;
+        }
+        assert_eq!(tokens, expected);
+    }
+
+    // The rest is kept verbatim
+    assert_eq!(
+        requote_delimited_args("poll title,  option 1, option 2", ',', Some(1)),
+        r#""poll title" option 1, option 2"#,
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_rest_argument() {
+    let content = "~embed title \n  {\n    \"a\":  1\n\n  }\n";
+    // The title has been popped, and the remaining arguments trimmed
+    let args = &content["~embed title \n  ".len()..];
+    assert_eq!(rest_argument(content, args), "  {\n    \"a\":  1\n\n  }\n");
+
+    assert_eq!(
+        rest_argument("~say  hello  world", "hello  world"),
+        "hello  world"
+    );
+    assert_eq!(
+        rest_argument("~say\r\n\tindented", "indented"),
+        "\tindented"
+    );
+    assert_eq!(rest_argument("~say\n\n\ttext", "text"), "\n\ttext");
+    // Arguments which aren't from the message content are just trimmed
+    assert_eq!(rest_argument("~say hello", " unrelated"), "unrelated");
+}
+
+#[cfg(test)]
+#[test]
+fn test_split_until_flag() {
+    let flags = &["silent", "dm"];
+    assert_eq!(
+        split_until_flag(" spamming in  general silent dm", flags),
+        ("spamming in  general", "silent dm"),
+    );
+    assert_eq!(split_until_flag("Silent", flags), ("", "Silent"));
+    assert_eq!(
+        split_until_flag("no flags here ", flags),
+        ("no flags here", ""),
+    );
+    assert_eq!(
+        split_until_flag(r#"the "silent" treatment DM"#, flags),
+        (r#"the "silent" treatment"#, "DM"),
+    );
+    assert_eq!(
+        split_until_flag("silently dmed", flags),
+        ("silently dmed", ""),
+    );
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_handle_extra_arguments() {
+    use crate::ExtraArgumentsBehavior;
+
+    fn error<E: std::error::Error + Send + Sync + 'static>(error: E) -> ParseError {
+        (Box::new(error), None)
+    }
+    fn append(args: &mut (u32, String), extra: String) {
+        args.1.push(' ');
+        args.1.push_str(&extra);
+    }
+    // Parses "1 one two three" into (u32, String), like the generated code with two parameters
+    async fn parse(
+        behavior: ExtraArgumentsBehavior,
+        parsed: Result<(u32, String), ParseError>,
+        append: Option<fn(&mut (u32, String), String)>,
+    ) -> (Result<(u32, String), ParseError>, usize) {
+        let reparses = std::sync::atomic::AtomicUsize::new(0);
+        let reparse = || async {
+            reparses.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(((1, "one".to_owned()), Some("two three".to_owned())))
+        };
+        let result = handle_extra_arguments(behavior, parsed, reparse, append).await;
+        (result, reparses.into_inner())
+    }
+    let too_many = || Err(error(TooManyArguments::default()));
+
+    let (result, reparses) = parse(ExtraArgumentsBehavior::Ignore, too_many(), Some(append)).await;
+    assert_eq!((result.unwrap(), reparses), ((1, "one".into()), 1));
+    let (result, reparses) = parse(
+        ExtraArgumentsBehavior::AppendToLastArgument,
+        too_many(),
+        Some(append),
+    )
+    .await;
+    assert_eq!(
+        (result.unwrap(), reparses),
+        ((1, "one two three".into()), 1)
+    );
+
+    // Trailing parameters which can't take the extra arguments behave like Error
+    let (result, reparses) = parse(
+        ExtraArgumentsBehavior::AppendToLastArgument,
+        too_many(),
+        None,
+    )
+    .await;
+    assert!(result.unwrap_err().0.is::<TooManyArguments>());
+    assert_eq!(reparses, 0);
+    let (result, reparses) = parse(ExtraArgumentsBehavior::Error, too_many(), Some(append)).await;
+    assert!(result.unwrap_err().0.is::<TooManyArguments>());
+    assert_eq!(reparses, 0);
+
+    // Other errors, like a malformed argument, are reported as is
+    let malformed = Err(error(InvalidBool::default()));
+    let (result, reparses) = parse(ExtraArgumentsBehavior::Ignore, malformed, Some(append)).await;
+    assert!(result.unwrap_err().0.is::<InvalidBool>());
+    assert_eq!(reparses, 0);
+    let parsed = Ok((2, "parsed".to_owned()));
+    let (result, reparses) = parse(ExtraArgumentsBehavior::Ignore, parsed, Some(append)).await;
+    assert_eq!((result.unwrap(), reparses), ((2, "parsed".into()), 0));
+}
@@ -0,0 +1,208 @@
+//! Infrastructure for token bucket rate limits, a more flexible alternative to cooldowns
+
+use crate::serenity_prelude as serenity;
+use crate::CooldownContext;
+// Same convenience imports as in the cooldown module
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// A token bucket: holds up to `capacity` tokens, each invocation takes one, and one token is
+/// refilled every `refill_per`.
+///
+/// This allows bursts of up to `capacity` invocations, after which invocations are throttled to
+/// one per `refill_per`. A capacity of one behaves like a plain cooldown of `refill_per`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct RateLimit {
+    /// Maximum number of tokens in the bucket, i.e. the maximum burst size
+    pub capacity: u32,
+    /// Time it takes to refill a single token
+    pub refill_per: Duration,
+}
+
+impl RateLimit {
+    /// Time frame, relative to now, which may be "in debt" before the bucket is empty
+    fn burst_window(self) -> Duration {
+        self.refill_per * self.capacity.saturating_sub(1)
+    }
+}
+
+/// Configuration struct for [`RateLimitTracker`]
+#[derive(Default, Clone, PartialEq, Eq, Debug, Hash)]
+pub struct RateLimitConfig {
+    /// This rate limit operates on a global basis
+    pub global: Option<RateLimit>,
+    /// This rate limit operates on a per-user basis
+    pub user: Option<RateLimit>,
+    /// This rate limit operates on a per-guild basis
+    pub guild: Option<RateLimit>,
+    /// This rate limit operates on a per-channel basis
+    pub channel: Option<RateLimit>,
+    /// This rate limit operates on a per-member basis
+    pub member: Option<RateLimit>,
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+/// Tracks the token buckets of all rate limit scopes for a single command
+///
+/// Each bucket is stored as the point in time at which it will be full again. Buckets which are
+/// full are indistinguishable from buckets which were never used, so they can be purged.
+///
+/// You probably don't need to use this directly; the framework checks and updates
+/// [`crate::Command::rate_limits`] automatically.
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
+pub struct RateLimitTracker {
+    /// Bucket of the global rate limit
+    global_bucket: Option<Instant>,
+    /// Buckets per user
+    user_buckets: HashMap<serenity::UserId, Instant>,
+    /// Buckets per guild
+    guild_buckets: HashMap<serenity::GuildId, Instant>,
+    /// Buckets per channel
+    channel_buckets: HashMap<serenity::ChannelId, Instant>,
+    /// Buckets per member (user and guild)
+    member_buckets: HashMap<(serenity::UserId, serenity::GuildId), Instant>,
+}
+
+impl RateLimitTracker {
+    /// Create a new rate limit tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queries the rate limit buckets and checks if all of them have a token left and command
+    /// execution may proceed. If not, Some is returned with the time until a token is available
+    /// in all buckets
+    pub fn retry_after(&self, ctx: CooldownContext, config: &RateLimitConfig) -> Option<Duration> {
+        let mut buckets = vec![
+            (config.global, self.global_bucket),
+            (config.user, self.user_buckets.get(&ctx.user_id).copied()),
+            (
+                config.channel,
+                self.channel_buckets.get(&ctx.channel_id).copied(),
+            ),
+        ];
+
+        if let Some(guild_id) = ctx.guild_id {
+            buckets.push((config.guild, self.guild_buckets.get(&guild_id).copied()));
+            buckets.push((
+                config.member,
+                self.member_buckets.get(&(ctx.user_id, guild_id)).copied(),
+            ));
+        }
+
+        let now = Instant::now();
+        buckets
+            .iter()
+            .filter_map(|&(rate_limit, full_at)| {
+                let debt = full_at?.saturating_duration_since(now);
+                let retry_after = debt.checked_sub(rate_limit?.burst_window())?;
+                Some(retry_after).filter(|retry_after| !retry_after.is_zero())
+            })
+            .max()
+    }
+
+    /// Indicates that a command has been executed and a token should be taken from all associated
+    /// buckets
+    pub fn take_token(&mut self, ctx: CooldownContext, config: &RateLimitConfig) {
+        fn take(bucket: &mut Instant, rate_limit: RateLimit, now: Instant) {
+            *bucket = std::cmp::max(*bucket, now) + rate_limit.refill_per;
+        }
+        fn take_keyed<K: std::hash::Hash + Eq>(
+            buckets: &mut HashMap<K, Instant>,
+            key: K,
+            rate_limit: Option<RateLimit>,
+            now: Instant,
+        ) {
+            if let Some(rate_limit) = rate_limit {
+                take(buckets.entry(key).or_insert(now), rate_limit, now);
+            }
+        }
+
+        let now = Instant::now();
+        if let Some(rate_limit) = config.global {
+            take(self.global_bucket.get_or_insert(now), rate_limit, now);
+        }
+        take_keyed(&mut self.user_buckets, ctx.user_id, config.user, now);
+        take_keyed(
+            &mut self.channel_buckets,
+            ctx.channel_id,
+            config.channel,
+            now,
+        );
+
+        if let Some(guild_id) = ctx.guild_id {
+            take_keyed(&mut self.guild_buckets, guild_id, config.guild, now);
+            take_keyed(
+                &mut self.member_buckets,
+                (ctx.user_id, guild_id),
+                config.member,
+                now,
+            );
+        }
+    }
+
+    /// Forgets all buckets which have been refilled completely
+    ///
+    /// Doesn't change the result of [`Self::retry_after`]; it just frees memory.
+    pub fn purge(&mut self) {
+        let now = Instant::now();
+        if self.global_bucket.is_some_and(|full_at| full_at <= now) {
+            self.global_bucket = None;
+        }
+        self.user_buckets.retain(|_, &mut full_at| full_at > now);
+        self.guild_buckets.retain(|_, &mut full_at| full_at > now);
+        self.channel_buckets.retain(|_, &mut full_at| full_at > now);
+        self.member_buckets.retain(|_, &mut full_at| full_at > now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket() {
+        let config = RateLimitConfig {
+            user: Some(RateLimit {
+                capacity: 3,
+                refill_per: Duration::from_secs(10),
+            }),
+            ..Default::default()
+        };
+        let ctx = |user_id| CooldownContext {
+            user_id: serenity::UserId::new(user_id),
+            ..Default::default()
+        };
+        let mut tracker = RateLimitTracker::new();
+
+        // A burst of up to the capacity is allowed
+        for _ in 0..3 {
+            assert_eq!(tracker.retry_after(ctx(1), &config), None);
+            tracker.take_token(ctx(1), &config);
+        }
+        let retry_after = tracker.retry_after(ctx(1), &config).unwrap();
+        assert!(retry_after > Duration::from_secs(9) && retry_after <= Duration::from_secs(10));
+
+        // Other users have their own bucket
+        assert_eq!(tracker.retry_after(ctx(2), &config), None);
+
+        // Non-full buckets are kept
+        tracker.purge();
+        assert!(tracker.retry_after(ctx(1), &config).is_some());
+
+        // Buckets without refill time never run empty and can be purged right away
+        let config = RateLimitConfig {
+            global: Some(RateLimit {
+                capacity: 1,
+                refill_per: Duration::ZERO,
+            }),
+            ..Default::default()
+        };
+        let mut tracker = RateLimitTracker::new();
+        tracker.take_token(ctx(1), &config);
+        assert_eq!(tracker.retry_after(ctx(1), &config), None);
+        tracker.purge();
+        assert_eq!(tracker, RateLimitTracker::new());
+    }
+}
@@ -46,9 +46,10 @@ impl CreateReply {
         self
     }
 
-    /// Add an attachment.
+    /// Add an attachment, for example generated in memory with
+    /// [`serenity::CreateAttachment::bytes`].
     ///
-    /// This will not have an effect in a slash command's initial response!
+    /// When editing a message, the new attachments replace the existing ones.
     pub fn attachment(mut self, attachment: serenity::CreateAttachment) -> Self {
         self.attachments.push(attachment);
         self
@@ -73,14 +74,62 @@ impl CreateReply {
     /// Makes this message an inline reply to another message like [`serenity::Message::reply`]
     /// (prefix-only, because slash commands are always inline replies anyways).
     ///
-    /// To disable the ping, set [`Self::allowed_mentions`] with
-    /// [`serenity::CreateAllowedMentions::replied_user`] set to false.
+    /// Whether the reply pings the author follows [`crate::FrameworkOptions::default_reply_ping`].
+    /// To override it for a single message, set [`Self::allowed_mentions`] with
+    /// [`serenity::CreateAllowedMentions::replied_user`].
     pub fn reply(mut self, reply: bool) -> Self {
         self.reply = reply;
         self
     }
 }
 
+impl CreateReply {
+    /// Applies the reply defaults configured on the command, like [`crate::Command::ephemeral`].
+    /// Settings made on this builder take precedence
+    pub(crate) fn with_command_defaults<U, E>(mut self, command: &crate::Command<U, E>) -> Self {
+        if command.ephemeral {
+            self.ephemeral.get_or_insert(true);
+        }
+        self
+    }
+
+    /// Applies the framework's default allowed mentions and, for inline replies, the default
+    /// reply ping. Allowed mentions set on this builder take precedence, and so does a
+    /// [`serenity::CreateAllowedMentions::replied_user`] set in the default allowed mentions
+    pub(crate) fn with_mention_defaults(
+        mut self,
+        allowed_mentions: Option<&serenity::CreateAllowedMentions>,
+        default_reply_ping: bool,
+    ) -> Self {
+        if self.allowed_mentions.is_some() {
+            return self;
+        }
+        self.allowed_mentions = allowed_mentions.cloned();
+        if self.reply {
+            let allowed_mentions = self.allowed_mentions.take().unwrap_or_else(|| {
+                // Discord's behavior when no allowed mentions are given
+                serenity::CreateAllowedMentions::new()
+                    .all_users(true)
+                    .all_roles(true)
+                    .everyone(true)
+            });
+            self.allowed_mentions = Some(if sets_replied_user(&allowed_mentions) {
+                allowed_mentions
+            } else {
+                allowed_mentions.replied_user(default_reply_ping)
+            });
+        }
+        self
+    }
+}
+
+/// Whether [`serenity::CreateAllowedMentions::replied_user`] was called on the builder. Its fields
+/// are private, but unset fields are skipped when serializing
+fn sets_replied_user(allowed_mentions: &serenity::CreateAllowedMentions) -> bool {
+    ::serenity::json::to_value(allowed_mentions)
+        .is_ok_and(|value| value.get("replied_user").is_some())
+}
+
 /// Methods to create a message builder from any type from this [`CreateReply`]. Used by poise
 /// internally to actually send a response to Discord
 impl CreateReply {
@@ -157,7 +206,7 @@ impl CreateReply {
         let crate::CreateReply {
             content,
             embeds,
-            attachments: _, // no support for attachment edits in serenity yet
+            attachments,
             components,
             ephemeral: _, // can't edit ephemerality in retrospect
             allowed_mentions,
@@ -174,6 +223,11 @@ impl CreateReply {
         if let Some(allowed_mentions) = allowed_mentions {
             builder = builder.allowed_mentions(allowed_mentions);
         }
+        // Only touch attachments if there are any, to not accidentally remove existing ones, e.g.
+        // when editing a deferred response
+        for attachment in attachments {
+            builder = builder.new_attachment(attachment);
+        }
 
         builder.embeds(embeds)
     }
@@ -246,3 +300,118 @@ impl CreateReply {
         builder.embeds(embeds)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ephemeral_command() {
+        let mut command = crate::Command::<(), ()> {
+            ephemeral: true,
+            ..Default::default()
+        };
+        let flags = |command: &crate::Command<(), ()>, reply: CreateReply| {
+            let response = reply
+                .with_command_defaults(command)
+                .to_slash_initial_response(serenity::CreateInteractionResponseMessage::new());
+            ::serenity::json::to_value(response).unwrap()["flags"].clone()
+        };
+        let ephemeral = serenity::InteractionResponseFlags::EPHEMERAL.bits();
+
+        // What Context::say sends
+        assert_eq!(
+            flags(&command, CreateReply::default().content("secret")),
+            ephemeral
+        );
+        // Explicitly public replies stay public
+        assert_eq!(
+            flags(
+                &command,
+                CreateReply::default().content("public").ephemeral(false)
+            ),
+            0
+        );
+
+        command.ephemeral = false;
+        assert!(flags(&command, CreateReply::default().content("public")).is_null());
+    }
+
+    #[test]
+    fn test_reply_ping() {
+        let replied_user = |reply: CreateReply,
+                            allowed_mentions: Option<&serenity::CreateAllowedMentions>,
+                            default_reply_ping: bool| {
+            let reply = reply.with_mention_defaults(allowed_mentions, default_reply_ping);
+            ::serenity::json::to_value(reply.allowed_mentions).unwrap()["replied_user"].clone()
+        };
+        let reply = || CreateReply::default().content("hi").reply(true);
+        let users = serenity::CreateAllowedMentions::new().all_users(true);
+
+        assert_eq!(replied_user(reply(), Some(&users), true), true);
+        assert_eq!(replied_user(reply(), Some(&users), false), false);
+        assert_eq!(replied_user(reply(), None, false), false);
+        // Explicit settings take precedence over the default
+        let no_ping = users.clone().replied_user(false);
+        assert_eq!(replied_user(reply(), Some(&no_ping), true), false);
+        let reply_with_ping = reply().allowed_mentions(users.clone().replied_user(true));
+        assert_eq!(replied_user(reply_with_ping, Some(&users), false), true);
+        // Replies don't ping by default
+        let options = crate::FrameworkOptions::<(), String>::default();
+        assert_eq!(
+            replied_user(
+                reply(),
+                options.allowed_mentions.as_ref(),
+                options.default_reply_ping
+            ),
+            false
+        );
+        // Messages which aren't inline replies are left alone
+        let message = CreateReply::default().content("hi");
+        assert!(replied_user(message, Some(&users), true).is_null());
+    }
+
+    #[test]
+    fn test_attachments() {
+        let data = b"name,score\nferris,100\n".to_vec();
+        let reply = CreateReply::default().attachment(serenity::CreateAttachment::bytes(
+            data.clone(),
+            "scores.csv",
+        ));
+
+        // The builders' fields are private, so we check their Debug output
+        let check = |builder: &dyn std::fmt::Debug| {
+            let debug = format!("{:?}", builder);
+            assert!(
+                debug.contains("\"scores.csv\""),
+                "filename missing: {}",
+                debug
+            );
+            assert!(
+                debug.contains(&format!("{:?}", data)),
+                "data missing: {}",
+                debug
+            );
+        };
+
+        let msg_reference = serenity::MessageReference::from((
+            serenity::ChannelId::new(1),
+            serenity::MessageId::new(1),
+        ));
+        check(&reply.clone().to_prefix(msg_reference));
+        check(&reply.clone().to_prefix_edit(serenity::EditMessage::new()));
+        check(
+            &reply
+                .clone()
+                .to_slash_initial_response(serenity::CreateInteractionResponseMessage::new()),
+        );
+        check(
+            &reply
+                .clone()
+                .to_slash_initial_response_edit(serenity::EditInteractionResponse::new()),
+        );
+        check(
+            &reply.to_slash_followup_response(serenity::CreateInteractionResponseFollowup::new()),
+        );
+    }
+}
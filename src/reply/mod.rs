@@ -3,6 +3,12 @@
 mod builder;
 pub use builder::*;
 
+mod response_handle;
+pub use response_handle::*;
+
+mod retry;
+pub use retry::*;
+
 mod send_reply;
 pub use send_reply::*;
 
@@ -89,7 +95,35 @@ impl ReplyHandle<'_> {
         }
     }
 
+    /// Determines which message or interaction response this handle points to, and thereby which
+    /// API is used to edit or delete it
+    fn target(&self) -> ReplyTarget<'_> {
+        match &self.0 {
+            ReplyHandleInner::Prefix(msg) => ReplyTarget::Message(msg),
+            ReplyHandleInner::Application {
+                http,
+                interaction,
+                followup: None,
+            } => ReplyTarget::InitialResponse { http, interaction },
+            ReplyHandleInner::Application {
+                http,
+                interaction,
+                followup: Some(msg),
+            } => ReplyTarget::Followup {
+                http,
+                interaction,
+                message_id: msg.id,
+            },
+            ReplyHandleInner::Autocomplete => panic!("reply is a no-op in autocomplete context"),
+        }
+    }
+
     /// Edits the message that this [`ReplyHandle`] points to
+    ///
+    /// Prefix command responses are edited as normal messages. Application command responses are
+    /// edited via the interaction token, which also works for ephemeral responses. Whether a
+    /// response is ephemeral can't be changed after sending, so [`CreateReply::ephemeral`] is
+    /// ignored here.
     // TODO: return the edited Message object?
     // TODO: should I eliminate the ctx parameter by storing it in self instead? Would infect
     //  ReplyHandle with <U, E> type parameters
@@ -98,10 +132,11 @@ impl ReplyHandle<'_> {
         ctx: crate::Context<'_, U, E>,
         builder: CreateReply,
     ) -> Result<(), serenity::Error> {
-        let reply = ctx.reply_builder(builder);
+        let mut reply = ctx.reply_builder(builder);
+        reply.ephemeral = None;
 
-        match &self.0 {
-            ReplyHandleInner::Prefix(msg) => {
+        match self.target() {
+            ReplyTarget::Message(msg) => {
                 msg.clone()
                     .edit(ctx.serenity_context(), {
                         // Clear builder so that adding embeds or attachments won't add on top of
@@ -113,49 +148,112 @@ impl ReplyHandle<'_> {
                     })
                     .await?;
             }
-            ReplyHandleInner::Application {
-                http,
-                interaction,
-                followup: None,
-            } => {
+            ReplyTarget::InitialResponse { http, interaction } => {
                 let builder =
                     reply.to_slash_initial_response_edit(serenity::EditInteractionResponse::new());
 
                 interaction.edit_response(http, builder).await?;
             }
-            ReplyHandleInner::Application {
+            ReplyTarget::Followup {
                 http,
                 interaction,
-                followup: Some(msg),
+                message_id,
             } => {
                 let builder = reply
                     .to_slash_followup_response(serenity::CreateInteractionResponseFollowup::new());
 
-                interaction.edit_followup(http, msg.id, builder).await?;
+                interaction.edit_followup(http, message_id, builder).await?;
             }
-            ReplyHandleInner::Autocomplete => panic!("reply is a no-op in autocomplete context"),
         }
         Ok(())
     }
 
     /// Deletes this message
     pub async fn delete<U, E>(&self, ctx: crate::Context<'_, U, E>) -> Result<(), serenity::Error> {
-        match &self.0 {
-            ReplyHandleInner::Prefix(msg) => msg.delete(ctx.serenity_context()).await?,
-            ReplyHandleInner::Application {
-                http: _,
+        match self.target() {
+            ReplyTarget::Message(msg) => msg.delete(ctx.serenity_context()).await?,
+            ReplyTarget::InitialResponse { interaction, .. } => {
+                interaction.delete_response(ctx).await?;
+            }
+            ReplyTarget::Followup {
                 interaction,
-                followup,
-            } => match followup {
-                Some(followup) => {
-                    interaction.delete_followup(ctx, followup.id).await?;
-                }
-                None => {
-                    interaction.delete_response(ctx).await?;
-                }
-            },
-            ReplyHandleInner::Autocomplete => panic!("delete is a no-op in autocomplete context"),
+                message_id,
+                ..
+            } => {
+                interaction.delete_followup(ctx, message_id).await?;
+            }
         }
         Ok(())
     }
 }
+
+/// What a [`ReplyHandle`] points to. See [`ReplyHandle::target`]
+#[derive(Debug)]
+enum ReplyTarget<'a> {
+    /// A normal message, edited and deleted via the channel message endpoints
+    Message(&'a serenity::Message),
+    /// The initial interaction response, edited and deleted via the interaction token
+    InitialResponse {
+        /// See [`ReplyHandleInner::Application::http`]
+        http: &'a serenity::Http,
+        /// The interaction which was responded to
+        interaction: &'a serenity::CommandInteraction,
+    },
+    /// An interaction followup, edited and deleted via the interaction token and message ID
+    Followup {
+        /// See [`ReplyHandleInner::Application::http`]
+        http: &'a serenity::Http,
+        /// The interaction which was responded to
+        interaction: &'a serenity::CommandInteraction,
+        /// ID of the followup message
+        message_id: serenity::MessageId,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reply_target() {
+        let http = serenity::Http::new("");
+        let interaction: serenity::CommandInteraction =
+            ::serenity::json::from_value(::serenity::json::json!({
+                "id": "1",
+                "application_id": "2",
+                "type": 2,
+                "data": { "id": "3", "name": "ping", "type": 1 },
+                "channel_id": "4",
+                "user": { "id": "5", "username": "ferris", "discriminator": "0000" },
+                "token": "token",
+                "version": 1,
+                "locale": "en-US",
+            }))
+            .unwrap();
+        let mut followup = serenity::Message::default();
+        followup.id = serenity::MessageId::new(6);
+
+        let prefix = ReplyHandle(ReplyHandleInner::Prefix(Box::new(followup.clone())));
+        assert!(matches!(prefix.target(), ReplyTarget::Message(msg) if msg.id.get() == 6));
+
+        let initial = ReplyHandle(ReplyHandleInner::Application {
+            http: &http,
+            interaction: &interaction,
+            followup: None,
+        });
+        assert!(matches!(
+            initial.target(),
+            ReplyTarget::InitialResponse { interaction, .. } if interaction.token == "token"
+        ));
+
+        let followup = ReplyHandle(ReplyHandleInner::Application {
+            http: &http,
+            interaction: &interaction,
+            followup: Some(Box::new(followup)),
+        });
+        assert!(matches!(
+            followup.target(),
+            ReplyTarget::Followup { message_id, .. } if message_id.get() == 6
+        ));
+    }
+}
@@ -0,0 +1,85 @@
+//! Contains [`ResponseHandle`], the return value of [`crate::Context::begin_response`]
+
+use crate::serenity_prelude as serenity;
+
+/// A response which is still being worked on. Returned by [`crate::Context::begin_response`]
+///
+/// Until [finished](Self::finish), the user sees that the bot is working: application commands
+/// show Discord's "thinking" state, prefix commands show a typing indicator. If the handle is
+/// dropped without finishing, the typing indicator stops, while a deferred interaction stays in
+/// the "thinking" state until it expires or something else responds.
+#[must_use = "The response is never sent if it isn't finished"]
+#[derive(derivative::Derivative)]
+#[derivative(Debug(bound = ""))]
+pub struct ResponseHandle<'a, U, E> {
+    /// Context of the command which is responding
+    ctx: crate::Context<'a, U, E>,
+    /// Whether the interaction was deferred by [`Self::begin`], so the deferred response can be
+    /// edited into the actual response
+    deferred: bool,
+    /// The typing broadcast, if this is a prefix command
+    typing: Option<serenity::Typing>,
+}
+
+impl<'a, U, E> ResponseHandle<'a, U, E> {
+    /// Defers the interaction or starts typing, see [`crate::Context::begin_response`]
+    pub(crate) async fn begin(ctx: crate::Context<'a, U, E>) -> Result<Self, serenity::Error> {
+        let mut deferred = false;
+        let mut typing = None;
+        match ctx {
+            crate::Context::Application(ctx) => {
+                // If something was sent already, the response goes into a followup instead
+                deferred = ctx.interaction_type != crate::CommandInteractionType::Autocomplete
+                    && !ctx
+                        .has_sent_initial_response
+                        .load(std::sync::atomic::Ordering::SeqCst);
+                if deferred {
+                    ctx.defer_response(ctx.command.ephemeral).await?;
+                }
+            }
+            crate::Context::Prefix(ctx) => {
+                typing = Some(ctx.msg.channel_id.start_typing(&ctx.serenity_context.http));
+            }
+        }
+
+        Ok(Self {
+            ctx,
+            deferred,
+            typing,
+        })
+    }
+
+    /// Sends the response: the deferred interaction response is edited into it, or, in prefix
+    /// commands, the typing indicator is stopped and the response is sent like
+    /// [`crate::send_reply`].
+    ///
+    /// Whether the response is ephemeral was decided when deferring, according to
+    /// [`crate::Command::ephemeral`], so [`crate::CreateReply::ephemeral`] is ignored for deferred
+    /// responses.
+    pub async fn finish(
+        self,
+        builder: crate::CreateReply,
+    ) -> Result<crate::ReplyHandle<'a>, serenity::Error> {
+        if let Some(typing) = self.typing {
+            typing.stop();
+        }
+
+        match self.ctx {
+            crate::Context::Application(ctx) if self.deferred => {
+                let builder = ctx
+                    .reply_builder(builder)
+                    .to_slash_initial_response_edit(serenity::EditInteractionResponse::new());
+                ctx.interaction
+                    .edit_response(ctx.serenity_context, builder)
+                    .await?;
+
+                Ok(super::ReplyHandle(super::ReplyHandleInner::Application {
+                    http: &ctx.serenity_context.http,
+                    interaction: ctx.interaction,
+                    followup: None,
+                }))
+            }
+            ctx => crate::send_reply(ctx, builder).await,
+        }
+    }
+}
@@ -0,0 +1,217 @@
+//! Retrying reply sends that failed due to transient errors, see [`ReplyRetry`]
+
+use crate::serenity_prelude as serenity;
+
+/// Configures automatic retries of replies which failed to send due to transient errors, i.e.
+/// Discord server errors (5xx) or timeouts and connection failures. Client errors (4xx), like
+/// missing permissions or invalid message contents, are never retried. Set via
+/// [`crate::FrameworkOptions::reply_retry`].
+///
+/// Applies to messages sent with [`crate::send_reply`] and friends: prefix replies and
+/// application command responses and followups. Edits aren't retried.
+///
+/// A request which timed out may have reached Discord nonetheless, so in rare cases a retry
+/// sends a message twice. Retrying the initial response of an interaction then fails, because the
+/// interaction was already responded to.
+#[derive(Debug, Clone)]
+pub struct ReplyRetry {
+    /// How often a prefix reply or an interaction followup is retried at most. Default 3
+    pub max_retries: u32,
+    /// How often the initial response to an interaction is retried at most. Discord only accepts
+    /// it within three seconds of the invocation, so keep this and [`Self::backoff`] small;
+    /// retries which would be sent after that are skipped. Default 1
+    pub max_initial_response_retries: u32,
+    /// Delay before the first retry; it's doubled for every further retry. Default 500ms
+    pub backoff: std::time::Duration,
+    /// Upper bound of the delay between two retries. Default 8s
+    pub max_backoff: std::time::Duration,
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl Default for ReplyRetry {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            max_initial_response_retries: 1,
+            backoff: std::time::Duration::from_millis(500),
+            max_backoff: std::time::Duration::from_secs(8),
+            __non_exhaustive: (),
+        }
+    }
+}
+
+impl ReplyRetry {
+    /// Delay before the retry with the given zero-based index
+    fn delay(&self, retry: u32) -> std::time::Duration {
+        self.backoff
+            .checked_mul(2_u32.saturating_pow(retry))
+            .map_or(self.max_backoff, |delay| delay.min(self.max_backoff))
+    }
+}
+
+/// An error from sending a message with [`send_with_retry`]
+pub(super) trait SendError: std::fmt::Display {
+    /// Whether sending the message may succeed when tried again
+    fn is_retryable(&self) -> bool;
+}
+
+impl SendError for serenity::Error {
+    fn is_retryable(&self) -> bool {
+        match self {
+            serenity::Error::Http(serenity::HttpError::UnsuccessfulRequest(response)) => {
+                response.status_code.is_server_error()
+            }
+            serenity::Error::Http(serenity::HttpError::Request(error)) => {
+                error.is_timeout() || error.is_connect()
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Which kind of message is sent with [`send_with_retry`], as they are retried differently
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum RetryKind {
+    /// Initial response to an interaction, which Discord only accepts until the deadline
+    InitialResponse {
+        /// See [`initial_response_deadline`]
+        deadline: std::time::SystemTime,
+    },
+    /// Any other message
+    Message,
+}
+
+/// When Discord stops accepting the initial response to the given interaction: three seconds
+/// after it was created
+pub(super) fn initial_response_deadline(
+    interaction_id: serenity::InteractionId,
+) -> std::time::SystemTime {
+    // Like serenity's `created_at`, but with millisecond precision
+    const DISCORD_EPOCH_MILLIS: u64 = 1_420_070_400_000;
+    let created_at =
+        std::time::Duration::from_millis((interaction_id.get() >> 22) + DISCORD_EPOCH_MILLIS);
+    std::time::SystemTime::UNIX_EPOCH + created_at + std::time::Duration::from_secs(3)
+}
+
+/// Runs `send` and, if it fails with a retryable error, runs it again as configured in `config`
+pub(super) async fn send_with_retry<T, E, F>(
+    config: Option<&ReplyRetry>,
+    kind: RetryKind,
+    mut send: impl FnMut() -> F,
+) -> Result<T, E>
+where
+    E: SendError,
+    F: std::future::Future<Output = Result<T, E>>,
+{
+    let mut retry = 0;
+    loop {
+        let error = match send().await {
+            Ok(x) => return Ok(x),
+            Err(error) => error,
+        };
+
+        let config = match config {
+            Some(x) => x,
+            None => return Err(error),
+        };
+        let max_retries = match kind {
+            RetryKind::InitialResponse { .. } => config.max_initial_response_retries,
+            RetryKind::Message => config.max_retries,
+        };
+        if retry >= max_retries || !error.is_retryable() {
+            return Err(error);
+        }
+
+        let delay = config.delay(retry);
+        if let RetryKind::InitialResponse { deadline } = kind {
+            // Discord would reject the retry anyways
+            if std::time::SystemTime::now() + delay >= deadline {
+                return Err(error);
+            }
+        }
+        tracing::warn!("failed to send reply ({}), retrying in {:?}", error, delay);
+        tokio::time::sleep(delay).await;
+        retry += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay() {
+        let config = ReplyRetry::default();
+        let delays = (0..6).map(|retry| config.delay(retry)).collect::<Vec<_>>();
+        assert_eq!(
+            delays,
+            [500, 1000, 2000, 4000, 8000, 8000].map(std::time::Duration::from_millis),
+        );
+        assert_eq!(config.delay(u32::MAX), config.max_backoff);
+    }
+
+    /// Stands in for an HTTP error response with the given status code
+    struct StatusError(u16);
+
+    impl std::fmt::Display for StatusError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "HTTP {}", self.0)
+        }
+    }
+
+    impl SendError for StatusError {
+        fn is_retryable(&self) -> bool {
+            self.0 >= 500
+        }
+    }
+
+    /// Sends with retries, where the first `failures` attempts fail with `status`. Returns
+    /// whether sending succeeded in the end and how often it was attempted
+    async fn send(kind: RetryKind, status: u16, failures: u32) -> (bool, u32) {
+        let config = ReplyRetry {
+            backoff: std::time::Duration::from_millis(1),
+            ..Default::default()
+        };
+        let mut attempts = 0;
+        let result = send_with_retry(Some(&config), kind, || {
+            attempts += 1;
+            std::future::ready(match attempts <= failures {
+                true => Err(StatusError(status)),
+                false => Ok(()),
+            })
+        })
+        .await;
+        (result.is_ok(), attempts)
+    }
+
+    #[tokio::test]
+    async fn test_send_with_retry() {
+        let in_time = RetryKind::InitialResponse {
+            deadline: std::time::SystemTime::now() + std::time::Duration::from_secs(60),
+        };
+        let too_late = RetryKind::InitialResponse {
+            deadline: std::time::SystemTime::now(),
+        };
+
+        // Server errors are retried up to the configured maximum
+        assert_eq!(send(RetryKind::Message, 500, 1).await, (true, 2));
+        assert_eq!(send(RetryKind::Message, 502, u32::MAX).await, (false, 4));
+        assert_eq!(send(in_time, 500, u32::MAX).await, (false, 2));
+        // Client errors aren't
+        assert_eq!(send(RetryKind::Message, 403, u32::MAX).await, (false, 1));
+        assert_eq!(send(in_time, 404, u32::MAX).await, (false, 1));
+        // Initial responses after the deadline would be rejected anyways
+        assert_eq!(send(too_late, 500, u32::MAX).await, (false, 1));
+    }
+
+    #[test]
+    fn test_initial_response_deadline() {
+        // Created at 2016-04-30 11:18:25.796 UTC
+        let interaction_id = serenity::InteractionId::new(175928847299117063);
+        assert_eq!(
+            initial_response_deadline(interaction_id),
+            std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_millis(1462015108796),
+        );
+    }
+}
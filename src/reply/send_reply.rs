@@ -1,5 +1,6 @@
 //! All functions to actually send a reply
 
+use super::retry::{send_with_retry, RetryKind};
 use crate::serenity_prelude as serenity;
 
 /// Send a message in the given context: normal message if prefix command, interaction response
@@ -7,6 +8,11 @@ use crate::serenity_prelude as serenity;
 ///
 /// If you just want to send a string, use [`say_reply`].
 ///
+/// Failures are never swallowed: if Discord rejects the message (e.g. the bot lacks permission to
+/// send messages in the channel, or the interaction token has expired), the error is returned.
+/// Propagate it with `?` from your command to have it reach [`crate::FrameworkOptions::on_error`]
+/// as [`crate::FrameworkError::Command`].
+///
 /// Note: panics when called in an autocomplete context!
 ///
 /// ```rust,no_run
@@ -61,34 +67,34 @@ pub async fn send_application_reply<U, E>(
         return Ok(super::ReplyHandle(super::ReplyHandleInner::Autocomplete));
     }
 
-    let has_sent_initial_response = ctx
-        .has_sent_initial_response
-        .load(std::sync::atomic::Ordering::SeqCst);
-
-    let followup = if has_sent_initial_response {
-        Some(Box::new({
+    let retry = ctx.framework.options.reply_retry.as_ref();
+    let initial_response = RetryKind::InitialResponse {
+        deadline: super::retry::initial_response_deadline(ctx.interaction.id),
+    };
+    let followup = send_to_reply_endpoint(
+        ctx.command.manual_response,
+        ctx.has_sent_initial_response,
+        send_with_retry(retry, initial_response, || {
             let builder = builder
-                .to_slash_followup_response(serenity::CreateInteractionResponseFollowup::new());
-
-            ctx.interaction
-                .create_followup(ctx.serenity_context, builder)
-                .await?
-        }))
-    } else {
-        let builder =
-            builder.to_slash_initial_response(serenity::CreateInteractionResponseMessage::new());
+                .clone()
+                .to_slash_initial_response(serenity::CreateInteractionResponseMessage::new());
 
-        ctx.interaction
-            .create_response(
+            ctx.interaction.create_response(
                 ctx.serenity_context,
                 serenity::CreateInteractionResponse::Message(builder),
             )
-            .await?;
-        ctx.has_sent_initial_response
-            .store(true, std::sync::atomic::Ordering::SeqCst);
+        }),
+        send_with_retry(retry, RetryKind::Message, || {
+            let builder = builder
+                .clone()
+                .to_slash_followup_response(serenity::CreateInteractionResponseFollowup::new());
 
-        None
-    };
+            ctx.interaction
+                .create_followup(ctx.serenity_context, builder)
+        }),
+    )
+    .await?
+    .map(Box::new);
 
     Ok(super::ReplyHandle(super::ReplyHandleInner::Application {
         http: &ctx.serenity_context.http,
@@ -97,6 +103,65 @@ pub async fn send_application_reply<U, E>(
     }))
 }
 
+/// Sends an application command reply to the right interaction endpoint: the initial response,
+/// or a followup if the initial response (or a defer) was already sent or the command sends it
+/// itself ([`crate::Command::manual_response`]). Only the chosen future is polled.
+///
+/// Returns the followup message, if the reply was sent as a followup
+async fn send_to_reply_endpoint<T>(
+    manual_response: bool,
+    has_sent_initial_response: &std::sync::atomic::AtomicBool,
+    send_initial_response: impl std::future::Future<Output = Result<(), serenity::Error>>,
+    send_followup: impl std::future::Future<Output = Result<T, serenity::Error>>,
+) -> Result<Option<T>, serenity::Error> {
+    if manual_response || has_sent_initial_response.load(std::sync::atomic::Ordering::SeqCst) {
+        return Ok(Some(send_followup.await?));
+    }
+
+    send_initial_response.await?;
+    has_sent_initial_response.store(true, std::sync::atomic::Ordering::SeqCst);
+    Ok(None)
+}
+
+/// Sends two messages in sequence: `initial`, then `followup`, e.g. a public response and an
+/// ephemeral note.
+///
+/// In application commands, `initial` is the interaction response (or the first followup, if the
+/// interaction was already responded to or deferred) and `followup` is always sent as a
+/// [followup](serenity::CommandInteraction::create_followup). In prefix commands, `initial` is
+/// sent like [`send_reply`] and `followup` is sent as a separate message, so it never overwrites
+/// `initial` in [`crate::Command::reuse_response`] commands.
+///
+/// Note: panics when called in an autocomplete context!
+pub async fn send_reply_then_followup<U, E>(
+    ctx: crate::Context<'_, U, E>,
+    initial: crate::CreateReply,
+    followup: crate::CreateReply,
+) -> Result<(crate::ReplyHandle<'_>, crate::ReplyHandle<'_>), serenity::Error> {
+    let initial = send_reply(ctx, initial).await?;
+    let followup = match ctx {
+        crate::Context::Prefix(ctx) => {
+            let builder = ctx.reply_builder(followup);
+            super::ReplyHandle(super::ReplyHandleInner::Prefix(Box::new(
+                send_with_retry(
+                    ctx.framework.options.reply_retry.as_ref(),
+                    RetryKind::Message,
+                    || {
+                        ctx.msg.channel_id.send_message(
+                            ctx.serenity_context,
+                            builder.clone().to_prefix(ctx.msg.into()),
+                        )
+                    },
+                )
+                .await?,
+            )))
+        }
+        // The initial reply has set has_sent_initial_response, so this is sent as a followup
+        crate::Context::Application(ctx) => send_application_reply(ctx, followup).await?,
+    };
+    Ok((initial, followup))
+}
+
 /// Prefix-specific reply function. For more details, see [`crate::send_reply`].
 pub async fn send_prefix_reply<U, E>(
     ctx: crate::PrefixContext<'_, U, E>,
@@ -122,8 +187,8 @@ pub async fn send_prefix_reply<U, E>(
         None
     };
 
-    Ok(Box::new(if let Some(mut response) = existing_response {
-        response
+    if let Some(mut response) = existing_response {
+        let edit_result = response
             .edit(ctx.serenity_context, {
                 // Reset the message. We don't want leftovers of the previous message (e.g. user
                 // sends a message with `.content("abc")` in a track_edits command, and the edited
@@ -135,23 +200,48 @@ pub async fn send_prefix_reply<U, E>(
                     .components(Vec::new())
                     .remove_all_attachments();
 
-                builder.to_prefix_edit(b)
+                builder.clone().to_prefix_edit(b)
             })
-            .await?;
+            .await;
 
-        // If the entry still exists after the await, update it to the new contents
-        // We don't check ctx.command.reuse_response because it's true anyways in this branch
-        if let Some(mut edit_tracker) = lock_edit_tracker() {
-            edit_tracker.set_bot_response(ctx.msg, response.clone(), ctx.command.track_deletion);
+        match edit_result {
+            Ok(()) => {
+                // If the entry still exists after the await, update it to the new contents
+                // We don't check ctx.command.reuse_response because it's true anyways in this branch
+                if let Some(mut edit_tracker) = lock_edit_tracker() {
+                    edit_tracker.set_bot_response(
+                        ctx.msg,
+                        response.clone(),
+                        ctx.command.track_deletion,
+                    );
+                }
+                return Ok(Box::new(response));
+            }
+            // The response was deleted without us noticing (e.g. the deletion event was missed),
+            // so fall through to sending a fresh one
+            Err(serenity::Error::Http(e))
+                if e.status_code() == Some(serenity::StatusCode::NOT_FOUND) =>
+            {
+                if let Some(mut edit_tracker) = lock_edit_tracker() {
+                    edit_tracker.forget_bot_response(ctx.msg.id);
+                }
+            }
+            Err(e) => return Err(e),
         }
+    }
 
-        response
-    } else {
-        let new_response = ctx
-            .msg
-            .channel_id
-            .send_message(ctx.serenity_context, builder.to_prefix(ctx.msg.into()))
-            .await?;
+    Ok(Box::new({
+        let new_response = send_with_retry(
+            ctx.framework.options.reply_retry.as_ref(),
+            RetryKind::Message,
+            || {
+                ctx.msg.channel_id.send_message(
+                    ctx.serenity_context,
+                    builder.clone().to_prefix(ctx.msg.into()),
+                )
+            },
+        )
+        .await?;
         // We don't check ctx.command.reuse_response because we need to store bot responses for
         // track_deletion too
         if let Some(track_edits) = &mut lock_edit_tracker() {
@@ -161,3 +251,46 @@ pub async fn send_prefix_reply<U, E>(
         new_response
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn reply(
+        manual_response: bool,
+        has_sent_initial_response: &std::sync::atomic::AtomicBool,
+        initial_response: Result<(), serenity::Error>,
+    ) -> Result<Option<&'static str>, serenity::Error> {
+        send_to_reply_endpoint(
+            manual_response,
+            has_sent_initial_response,
+            async { initial_response },
+            async { Ok("followup") },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_application_reply_endpoint() {
+        // The two messages of send_reply_then_followup
+        let has_sent_initial_response = std::sync::atomic::AtomicBool::new(false);
+        let initial = reply(false, &has_sent_initial_response, Ok(())).await;
+        assert_eq!(initial.unwrap(), None);
+        let followup = reply(false, &has_sent_initial_response, Ok(())).await;
+        assert_eq!(followup.unwrap(), Some("followup"));
+
+        // A failed initial response isn't recorded as sent, so the next reply tries it again
+        let has_sent_initial_response = std::sync::atomic::AtomicBool::new(false);
+        let error = serenity::Error::Other("interaction expired");
+        assert!(reply(false, &has_sent_initial_response, Err(error))
+            .await
+            .is_err());
+        let retried = reply(false, &has_sent_initial_response, Ok(())).await;
+        assert_eq!(retried.unwrap(), None);
+
+        // With manual_response, the command sends the initial response itself
+        let has_sent_initial_response = std::sync::atomic::AtomicBool::new(false);
+        let manual = reply(true, &has_sent_initial_response, Ok(())).await;
+        assert_eq!(manual.unwrap(), Some("followup"));
+    }
+}
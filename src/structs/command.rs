@@ -44,11 +44,11 @@ pub struct Command<U, E> {
     ///
     /// Can be configured via the [`crate::command`] macro (though it's probably not needed for most
     /// bots). If not explicitly configured, it falls back to the command function name.
-    pub identifying_name: String,
+    pub identifying_name: std::borrow::Cow<'static, str>,
     /// The name of the `#[poise::command]`-annotated function
-    pub source_code_name: String,
+    pub source_code_name: std::borrow::Cow<'static, str>,
     /// Identifier for the category that this command will be displayed in for help commands.
-    pub category: Option<String>,
+    pub category: Option<std::borrow::Cow<'static, str>>,
     /// Whether to hide this command in help menus.
     pub hide_in_help: bool,
     /// Short description of the command. Displayed inline in help menus and similar.
@@ -57,19 +57,58 @@ pub struct Command<U, E> {
     pub description_localizations: std::collections::HashMap<String, String>,
     /// Multiline description with detailed usage instructions. Displayed in the command specific
     /// help: `~help command_name`
-    pub help_text: Option<String>,
+    pub help_text: Option<std::borrow::Cow<'static, str>>,
+    /// Example invocations, without the prefix, e.g. `remind 10m take a break`. Displayed in the
+    /// command specific help, prefixed with the prefix that was used to invoke the help command.
+    pub examples: Vec<std::borrow::Cow<'static, str>>,
     /// Handles command cooldowns. Mainly for framework internal use
     pub cooldowns: std::sync::Mutex<crate::CooldownTracker>,
     /// Configuration for the [`crate::CooldownTracker`]
     pub cooldown_config: std::sync::RwLock<crate::CooldownConfig>,
+    /// Token bucket state of this command's rate limits. Mainly for framework internal use
+    pub rate_limits: std::sync::Mutex<crate::RateLimitTracker>,
+    /// Token bucket rate limits of this command, which allow short bursts of invocations unlike
+    /// [`Self::cooldown_config`]. If a bucket is empty,
+    /// [`crate::FrameworkError::RateLimited`] is emitted. Like cooldowns, they are disabled by
+    /// [`crate::FrameworkOptions::manual_cooldowns`].
+    pub rate_limit_config: std::sync::RwLock<crate::RateLimitConfig>,
+    /// Command-specific override for [`crate::FrameworkOptions::command_timeout`]
+    pub timeout: Option<std::time::Duration>,
+    /// If this returns false, the command is excluded entirely: [`crate::Framework`] removes it
+    /// from [`crate::FrameworkOptions::commands`] on startup, before dispatch and registration
+    /// ever see it. See [`crate::remove_unavailable_commands`]
+    ///
+    /// Useful for staged rollouts and commands behind runtime flags. For Cargo features, a
+    /// `#[cfg]` on the `commands` entry works too. Always true by default.
+    #[derivative(Debug = "ignore", Default(value = "|| true"))]
+    pub available: fn() -> bool,
+    /// If not empty, this command is registered in these guilds only instead of globally, e.g. for
+    /// a few premium or partner guilds. Only has an effect on top-level commands.
+    ///
+    /// Used by [`crate::builtins::register_scoped`]; the other registration functions leave such
+    /// commands out of the global command list too. This only affects registration, so prefix
+    /// invocations are still possible anywhere; combine with a check if that's undesired.
+    pub registration_guilds: Vec<serenity::GuildId>,
+    /// Guilds in which this command has been disabled at runtime. Mainly for framework internal
+    /// use; see [`Self::set_enabled_in_guild`]
+    pub disabled_guilds: std::sync::RwLock<std::collections::HashSet<serenity::GuildId>>,
+    /// Guild-specific names and descriptions of this command. Mainly for framework internal use;
+    /// see [`Self::set_guild_override`]
+    pub guild_overrides:
+        std::sync::RwLock<std::collections::HashMap<serenity::GuildId, CommandOverride>>,
     /// After the first response, whether to post subsequent responses as edits to the initial
     /// message
     ///
     /// Note: in prefix commands, this only has an effect if
-    /// `crate::PrefixFrameworkOptions::edit_tracker` is set.
+    /// `crate::PrefixFrameworkOptions::edit_tracker` is set. Then, when an edited invocation
+    /// message re-runs the command, [`crate::Context::send`] edits the tracked response instead of
+    /// sending a new message. If that response has been deleted in the meantime, a fresh message
+    /// is sent and tracked instead.
     pub reuse_response: bool,
     /// Permissions which users must have to invoke this command. Used by Discord to set who can
-    /// invoke this as a slash command. Not used on prefix commands or checked internally.
+    /// see and invoke this as a slash or context menu command. Not used on prefix commands or
+    /// checked internally, and not to be confused with [`Self::required_permissions`]. Server
+    /// admins can override this per guild.
     ///
     /// Set to [`serenity::Permissions::empty()`] by default
     pub default_member_permissions: serenity::Permissions,
@@ -82,12 +121,26 @@ pub struct Command<U, E> {
     /// give a descriptive error message in case the
     /// bot hasn't been assigned the minimum permissions by the guild admin.
     ///
+    /// For application commands, this is checked against the bot permissions included in the
+    /// interaction ([`crate::Context::app_permissions`]).
+    ///
     /// Set to [`serenity::Permissions::empty()`] by default
     pub required_bot_permissions: serenity::Permissions,
+    /// Gateway intents this command relies on, for example [`serenity::GatewayIntents::GUILD_MEMBERS`]
+    /// for a command reading the member list.
+    ///
+    /// Not enforced. On startup, the framework prints a warning for each command whose intents are
+    /// not enabled on the client. See also [`crate::FrameworkOptions::required_intents`].
+    ///
+    /// Set to [`serenity::GatewayIntents::empty()`] by default
+    pub required_intents: serenity::GatewayIntents,
     /// If true, only users from the [owners list](crate::FrameworkOptions::owners) may use this
     /// command.
     pub owners_only: bool,
     /// If true, only people in guilds may use this command
+    ///
+    /// For application commands, this is also sent to Discord on registration (as
+    /// `dm_permission: false`), so the command isn't shown in DMs in the first place.
     pub guild_only: bool,
     /// If true, the command may only run in DMs
     pub dm_only: bool,
@@ -106,10 +159,25 @@ pub struct Command<U, E> {
     /// Arbitrary data, useful for storing custom metadata about your commands
     #[derivative(Default(value = "Box::new(())"))]
     pub custom_data: Box<dyn std::any::Any + Send + Sync>,
+    /// Typed values attached to this command, for example injected dependencies of a reusable
+    /// command. Unlike [`Self::custom_data`], this can hold one value per type.
+    ///
+    /// Populate with [`Self::insert_extension`] when building the command list; retrieve from
+    /// the command body with [`crate::Context::command_extension`].
+    pub extensions:
+        std::collections::HashMap<std::any::TypeId, Box<dyn std::any::Any + Send + Sync>>,
 
     // ============= Prefix-specific data
     /// Alternative triggers for the command (prefix-only)
     pub aliases: Vec<String>,
+    /// Character which separates the arguments of this command, instead of whitespace
+    /// (prefix-only)
+    ///
+    /// For example with `,`, `~poll Best color?, dark red, blue` parses into the arguments
+    /// `Best color?`, `dark red` and `blue`. Arguments are trimmed and quotes have no special
+    /// meaning. A trailing `#[rest]` parameter still receives the remaining input verbatim,
+    /// unless it's preceded by a `Vec` parameter. See [`crate::requote_delimited_args`].
+    pub delimiter: Option<char>,
     /// Whether to rerun the command if an existing invocation message is edited (prefix-only)
     pub invoke_on_edit: bool,
     /// Whether to delete the bot response if an existing invocation message is deleted (prefix-only)
@@ -121,7 +189,27 @@ pub struct Command<U, E> {
     /// Context menu specific name for this command, displayed in Discord's context menu
     pub context_menu_name: Option<String>,
     /// Whether responses to this command should be ephemeral by default (application-only)
+    ///
+    /// Applies to all replies and deferrals which don't explicitly set
+    /// [`crate::CreateReply::ephemeral`]. Prefix commands ignore this.
     pub ephemeral: bool,
+    /// If true, the command takes full responsibility for the initial interaction response, for
+    /// example to open a modal via raw serenity calls (application-only).
+    ///
+    /// Poise doesn't acknowledge such interactions by itself, even with
+    /// [`crate::FrameworkOptions::defer_all_commands`]. With this flag, it additionally assumes
+    /// the initial response has already been sent, so [`crate::send_reply`] and everything built
+    /// on it (including [`crate::builtins::on_error`]) only ever send followup messages.
+    ///
+    /// Pitfall: Discord expires interactions that haven't been responded to within three seconds.
+    /// If the command doesn't respond in time, or fails before responding, followups fail too and
+    /// the user sees "The application did not respond".
+    pub manual_response: bool,
+    /// If true, this command isn't deferred automatically, even if
+    /// [`crate::FrameworkOptions::defer_all_commands`] is set (application-only)
+    ///
+    /// Needed for commands which respond with a modal, since a modal must be the initial response.
+    pub no_defer: bool,
 
     // Like #[non_exhaustive], but #[poise::command] still needs to be able to create an instance
     #[doc(hidden)]
@@ -135,7 +223,100 @@ impl<U, E> PartialEq for Command<U, E> {
 }
 impl<U, E> Eq for Command<U, E> {}
 
+/// Guild-specific replacement for a command's name and description, see
+/// [`Command::set_guild_override`]
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct CommandOverride {
+    /// Name to register the command under instead of [`Command::name`]
+    pub name: Option<String>,
+    /// Description to register the command with instead of [`Command::description`]
+    pub description: Option<String>,
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+/// Purges expired cooldown and rate limit data of this command and all its subcommands
+impl<U, E> crate::Purgeable for Command<U, E> {
+    fn purge(&self) {
+        let cooldown_config = self.cooldown_config.read().unwrap();
+        self.cooldowns.lock().unwrap().purge(&cooldown_config);
+        self.rate_limits.lock().unwrap().purge();
+        for subcommand in &self.subcommands {
+            subcommand.purge();
+        }
+    }
+}
+
 impl<U, E> Command<U, E> {
+    /// Enables or disables this command (and its subcommands) in the given guild. DMs are
+    /// unaffected.
+    ///
+    /// Invoking a disabled command yields [`crate::FrameworkError::CommandDisabled`]. Disabled
+    /// commands are also left out by [`crate::builtins::create_application_commands_for_guild`].
+    pub fn set_enabled_in_guild(&self, guild_id: serenity::GuildId, enabled: bool) {
+        let mut disabled_guilds = self.disabled_guilds.write().unwrap();
+        if enabled {
+            disabled_guilds.remove(&guild_id);
+        } else {
+            disabled_guilds.insert(guild_id);
+        }
+    }
+
+    /// Returns whether this command is enabled in the given guild. See
+    /// [`Self::set_enabled_in_guild`]
+    pub fn is_enabled_in_guild(&self, guild_id: serenity::GuildId) -> bool {
+        !self.disabled_guilds.read().unwrap().contains(&guild_id)
+    }
+
+    /// Presents this slash command under a different name and/or description in the given guild,
+    /// e.g. for white-label deployments. Pass None to remove the override.
+    ///
+    /// Only applies to top-level slash commands, and only once the commands are registered in the
+    /// guild, e.g. with [`crate::builtins::register_in_guild`]. Interactions using the overridden
+    /// name are dispatched to this command as usual. Note that the overridden name must follow
+    /// the usual naming rules and not collide with other commands.
+    pub fn set_guild_override(&self, guild_id: serenity::GuildId, value: Option<CommandOverride>) {
+        let mut guild_overrides = self.guild_overrides.write().unwrap();
+        match value {
+            Some(value) => guild_overrides.insert(guild_id, value),
+            None => guild_overrides.remove(&guild_id),
+        };
+    }
+
+    /// Returns the override of this command's name and description in the given guild, if any.
+    /// See [`Self::set_guild_override`]
+    pub fn guild_override(&self, guild_id: serenity::GuildId) -> Option<CommandOverride> {
+        self.guild_overrides.read().unwrap().get(&guild_id).cloned()
+    }
+
+    /// Returns the name under which this command is registered in the given guild, taking
+    /// [`Self::set_guild_override`] into account
+    pub(crate) fn name_in_guild(
+        &self,
+        guild_id: Option<serenity::GuildId>,
+    ) -> std::borrow::Cow<'_, str> {
+        let name = guild_id.and_then(|guild_id| self.guild_override(guild_id)?.name);
+        match name {
+            Some(name) => std::borrow::Cow::Owned(name),
+            None => std::borrow::Cow::Borrowed(&self.name),
+        }
+    }
+
+    /// Attaches a value to this command, replacing any previous value of the same type. See
+    /// [`Self::extensions`]
+    pub fn insert_extension<T: std::any::Any + Send + Sync>(&mut self, value: T) {
+        self.extensions
+            .insert(std::any::TypeId::of::<T>(), Box::new(value));
+    }
+
+    /// Returns the value of the given type attached to this command, if any. See
+    /// [`Self::extensions`]
+    pub fn extension<T: std::any::Any>(&self) -> Option<&T> {
+        self.extensions
+            .get(&std::any::TypeId::of::<T>())?
+            .downcast_ref()
+    }
+
     /// Serializes this Command into an application command option, which is the form which Discord
     /// requires subcommands to be in
     fn create_as_subcommand(&self) -> Option<serenity::CreateCommandOption> {
@@ -229,6 +410,11 @@ impl<U, E> Command<U, E> {
             crate::ContextMenuCommandAction::__NonExhaustive => unreachable!(),
         });
 
+        // See create_as_slash_command for why the is_empty check is needed
+        if !self.default_member_permissions.is_empty() {
+            builder = builder.default_member_permissions(self.default_member_permissions);
+        }
+
         if self.guild_only {
             builder = builder.dm_permission(false);
         }
@@ -236,3 +422,46 @@ impl<U, E> Command<U, E> {
         Some(builder)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registration_payload(builder: Option<serenity::CreateCommand>) -> serenity::json::Value {
+        serenity::json::to_value(builder.unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_registration_permissions() {
+        let mut command = Command::<(), ()> {
+            name: "ban".into(),
+            slash_action: Some(|_| Box::pin(async { Ok(()) })),
+            context_menu_action: Some(crate::ContextMenuCommandAction::User(|_, _| {
+                Box::pin(async { Ok(()) })
+            })),
+            ..Default::default()
+        };
+
+        // Without restrictions, the fields are omitted so Discord applies its defaults
+        for payload in [
+            registration_payload(command.create_as_slash_command()),
+            registration_payload(command.create_as_context_menu_command()),
+        ] {
+            assert!(payload.get("default_member_permissions").is_none());
+            assert!(payload.get("dm_permission").is_none());
+        }
+
+        command.default_member_permissions = serenity::Permissions::BAN_MEMBERS;
+        command.guild_only = true;
+        for payload in [
+            registration_payload(command.create_as_slash_command()),
+            registration_payload(command.create_as_context_menu_command()),
+        ] {
+            assert_eq!(
+                payload["default_member_permissions"],
+                serenity::Permissions::BAN_MEMBERS.bits().to_string(),
+            );
+            assert_eq!(payload["dm_permission"], false);
+        }
+    }
+}
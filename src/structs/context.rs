@@ -16,7 +16,8 @@ impl<U, E> _GetGenerics for Context<'_, U, E> {
 }
 
 /// Wrapper around either [`crate::ApplicationContext`] or [`crate::PrefixContext`]
-#[derive(Debug)]
+#[derive(derivative::Derivative)]
+#[derivative(Debug(bound = ""))]
 pub enum Context<'a, U, E> {
     /// Application command context
     Application(crate::ApplicationContext<'a, U, E>),
@@ -24,6 +25,44 @@ pub enum Context<'a, U, E> {
     Prefix(crate::PrefixContext<'a, U, E>),
     // Not non_exhaustive.. adding a whole new category of commands would justify breakage lol
 }
+
+/// Which kind of invocation a [`Context`] stems from. See [`Context::kind`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ContextKind {
+    /// Prefix command invoked via a message
+    Prefix,
+    /// Slash command
+    Slash,
+    /// Context menu command on a user or message
+    ContextMenu,
+    /// Autocomplete request for a slash command parameter
+    Autocomplete,
+}
+
+/// Returned by [`Context::show_working`]. Keeps the typing indicator of prefix commands going until
+/// it's dropped or [stopped](Self::stop)
+///
+/// Dropping happens automatically at the end of the command body, including when it panics.
+#[must_use = "The typing indicator stops immediately if the guard isn't stored"]
+#[derive(Debug)]
+pub struct WorkingGuard {
+    /// The typing broadcast, if this is a prefix command
+    typing: Option<serenity::Typing>,
+}
+
+impl WorkingGuard {
+    /// Stops the typing indicator, if any, e.g. right before sending a response that is followed
+    /// by more work
+    pub fn stop(self) {
+        if let Some(typing) = self.typing {
+            typing.stop();
+        }
+    }
+}
+
+/// Stored in the invocation data by [`Context::channel_name`] to remember the looked up name
+struct ChannelName(String);
+
 impl<U, E> Clone for Context<'_, U, E> {
     fn clone(&self) -> Self {
         *self
@@ -81,11 +120,12 @@ context_methods! {
     ///
     /// No-op if this is an autocomplete context
     ///
-    /// This will make the response public; to make it ephemeral, use [`Self::defer_ephemeral()`].
+    /// This will make the response public, unless the command is [ephemeral by
+    /// default](crate::Command::ephemeral); to make it ephemeral, use [`Self::defer_ephemeral()`].
     await (defer self)
     (pub async fn defer(self) -> Result<(), serenity::Error>) {
         if let Self::Application(ctx) = self {
-            ctx.defer_response(false).await?;
+            ctx.defer_response(ctx.command.ephemeral).await?;
         }
         Ok(())
     }
@@ -110,7 +150,7 @@ context_methods! {
     (pub async fn defer_or_broadcast(self) -> Result<Option<serenity::Typing>, serenity::Error>) {
         Ok(match self {
             Self::Application(ctx) => {
-                ctx.defer_response(false).await?;
+                ctx.defer_response(ctx.command.ephemeral).await?;
                 None
             }
             Self::Prefix(ctx) => Some(
@@ -121,8 +161,55 @@ context_methods! {
         })
     }
 
+    /// Shows that the bot is working on the response, regardless of the command type: in
+    /// application commands, the response is [deferred](Self::defer); in prefix commands, a
+    /// typing indicator is broadcast until the returned guard is dropped.
+    ///
+    /// Like [`Self::defer_or_broadcast`], but the returned guard can't accidentally be discarded,
+    /// so command bodies shared between slash and prefix commands don't need to branch.
+    ///
+    /// ```rust,no_run
+    /// # async fn _test(ctx: poise::Context<'_, (), poise::serenity_prelude::Error>) -> Result<(), poise::serenity_prelude::Error> {
+    /// let _working = ctx.show_working().await?;
+    /// let result = expensive_computation().await;
+    /// ctx.say(result).await?;
+    /// # Ok(()) }
+    /// # async fn expensive_computation() -> String { String::new() }
+    /// ```
+    await (show_working self)
+    (pub async fn show_working(self) -> Result<WorkingGuard, serenity::Error>) {
+        let typing = self.defer_or_broadcast().await?;
+        Ok(WorkingGuard { typing })
+    }
+
+    /// Begins a response which takes a while, for command bodies shared between slash and prefix
+    /// commands: in application commands, the response is [deferred](Self::defer); in prefix
+    /// commands, a typing indicator is broadcast. Send the response with
+    /// [`crate::ResponseHandle::finish`], which edits the deferred response or stops typing.
+    ///
+    /// Unlike [`Self::show_working`], this works out where the response goes, so the "thinking"
+    /// state is replaced by the response instead of a followup. If a response was already sent,
+    /// the interaction isn't deferred and the response becomes a followup.
+    ///
+    /// ```rust,no_run
+    /// # async fn _test(ctx: poise::Context<'_, (), poise::serenity_prelude::Error>) -> Result<(), poise::serenity_prelude::Error> {
+    /// let response = ctx.begin_response().await?;
+    /// let result = expensive_computation().await;
+    /// response.finish(poise::CreateReply::default().content(result)).await?;
+    /// # Ok(()) }
+    /// # async fn expensive_computation() -> String { String::new() }
+    /// ```
+    await (begin_response self)
+    (pub async fn begin_response(
+        self,
+    ) -> Result<crate::ResponseHandle<'a, U, E>, serenity::Error>) {
+        crate::ResponseHandle::begin(self).await
+    }
+
     /// Shorthand of [`crate::say_reply`]
     ///
+    /// Returns an error if the message couldn't be sent; see [`crate::send_reply`] for details.
+    ///
     /// Note: panics when called in an autocomplete context!
     await (say self text)
     (pub async fn say(
@@ -138,7 +225,8 @@ context_methods! {
     /// Equivalent to `.send(|b| b.content("...").reply(true))`.
     ///
     /// Only has an effect in prefix context, because slash command responses are always
-    /// formatted as a reply.
+    /// formatted as a reply; there, this is equivalent to [`Self::say`]. Whether the reply pings
+    /// the author is controlled by [`crate::FrameworkOptions::default_reply_ping`].
     ///
     /// Note: panics when called in an autocomplete context!
     await (reply self text)
@@ -196,6 +284,24 @@ context_methods! {
         }
     }
 
+    /// Returns the raw serenity event which triggered this invocation, for data that poise
+    /// doesn't expose otherwise. For most purposes, the message or interaction stored in the
+    /// underlying context type is more convenient.
+    ///
+    /// For invocations by a message edit, this is the [`serenity::FullEvent::MessageUpdate`]
+    /// event, not the edited message. The event is borrowed, not cloned, which is why it's bound
+    /// to the lifetime of the context and can't be kept around after the command has finished.
+    ///
+    /// Returns `None` if the invocation wasn't dispatched through [`crate::dispatch_event`], e.g.
+    /// when calling [`crate::dispatch_message`] directly.
+    (raw_event self)
+    (pub fn raw_event(self) -> Option<&'a serenity::FullEvent>) {
+        match self {
+            Self::Application(ctx) => ctx.raw_event,
+            Self::Prefix(ctx) => ctx.raw_event,
+        }
+    }
+
     /// Return a reference to your custom user data
     (data self)
     (pub fn data(self) -> &'a U) {
@@ -241,6 +347,19 @@ context_methods! {
         self.guild_id()?.to_guild_cached(self.serenity_context())
     }
 
+    /// Returns the voice channel the author is currently connected to, e.g. for music bots to
+    /// join it
+    ///
+    /// Read from the voice states of the cached guild, which are only kept up to date with the
+    /// `GUILD_VOICE_STATES` intent; declare it in [`crate::Command::required_intents`] of commands
+    /// using this. Returns None if not in a guild, if the author isn't in a voice channel, or if
+    /// the guild isn't cached.
+    #[cfg(feature = "cache")]
+    (author_voice_channel self)
+    (pub fn author_voice_channel(self) -> Option<serenity::ChannelId>) {
+        self.guild()?.voice_states.get(&self.author().id)?.channel_id
+    }
+
     // Doesn't fit in with the rest of the functions here but it's convenient
     /// Return the partial guild of this context, if we are inside a guild.
     ///
@@ -267,7 +386,8 @@ context_methods! {
     /// Returns None if this command was invoked in DMs, or if the member cache lookup or HTTP
     /// request failed
     ///
-    /// Warning: can clone the entire Member instance out of the cache
+    /// Warning: can clone the entire Member instance out of the cache. If you only need roles or
+    /// the nickname, [`Self::partial_member`] avoids the lookup
     await (author_member self)
     (pub async fn author_member(self) -> Option<Cow<'a, serenity::Member>>) {
         if let Self::Application(ctx) = self {
@@ -281,7 +401,219 @@ context_methods! {
         }
     }
 
+    /// Returns the member data of the author that Discord sent along with the invoking message or
+    /// interaction, like nickname, roles and join date
+    ///
+    /// Unlike [`Self::author_member`], this never does a cache lookup or HTTP request. In prefix
+    /// contexts, the member data is borrowed from the message. In application contexts, the
+    /// interaction carries a full member object instead, which is cloned and converted on every
+    /// call; use [`Self::author_member`] there to borrow it.
+    ///
+    /// Returns None if this command was invoked in DMs, or if the message came without member
+    /// data, which is the case for some message edits
+    (partial_member self)
+    (pub fn partial_member(self) -> Option<Cow<'a, serenity::PartialMember>>) {
+        match self {
+            Self::Application(ctx) => ctx
+                .interaction
+                .member
+                .as_deref()
+                .map(|member| Cow::Owned(member.clone().into())),
+            Self::Prefix(ctx) => ctx.msg.member.as_deref().map(Cow::Borrowed),
+        }
+    }
+
+    // Doesn't fit in with the rest of the functions here but it's convenient
+    /// Return the channel of this context
+    ///
+    /// Attempts to find the channel in cache, if cache feature is enabled. Otherwise, falls back
+    /// to an HTTP request
+    ///
+    /// Returns None if the channel HTTP request fails
+    await (channel self)
+    (pub async fn channel(self) -> Option<serenity::Channel>) {
+        self.channel_id().to_channel(self.serenity_context()).await.ok()
+    }
+
+    /// Returns the human-readable name of the channel this command was invoked in, e.g. for logging
+    ///
+    /// Application commands usually carry the channel name in the interaction, so no request is
+    /// needed. Otherwise, the channel is looked up like in [`Self::channel`] and the name is
+    /// stored in the [invocation data](Self::invocation_data), so repeated calls in the same
+    /// invocation don't look it up again. That's skipped if the command has stored its own
+    /// invocation data, which is never overwritten. DM channels are named `"Direct Message"`.
+    ///
+    /// Returns None if the channel HTTP request fails
+    await (channel_name self)
+    (pub async fn channel_name(self) -> Option<String>) {
+        if let Self::Application(ctx) = self {
+            if let Some(name) = ctx.interaction.channel.as_ref().and_then(|c| c.name.clone()) {
+                return Some(name);
+            }
+        }
+        if self.guild_id().is_none() {
+            return Some("Direct Message".into());
+        }
+
+        if let Some(name) = self.invocation_data::<ChannelName>().await {
+            return Some(name.0.clone());
+        }
+
+        let name = match self.channel().await? {
+            serenity::Channel::Guild(channel) => channel.name,
+            serenity::Channel::Private(_) => "Direct Message".into(),
+            other => other.id().to_string(),
+        };
+        let mut invocation_data = self.invocation_data_raw().lock().await;
+        if invocation_data.is::<()>() {
+            *invocation_data = Box::new(ChannelName(name.clone()));
+        }
+        Some(name)
+    }
+
+    /// Returns the channel which the thread this command was invoked in belongs to, e.g. to post
+    /// to the parent channel
+    ///
+    /// Application commands carry the channel type in the interaction, so no request is needed.
+    /// Otherwise, the channel is looked up like in [`Self::channel`].
+    ///
+    /// Returns None if not invoked in a thread, or if the channel HTTP request fails
+    await (thread_parent self)
+    (pub async fn thread_parent(self) -> Option<serenity::ChannelId>) {
+        let is_thread = |kind| {
+            matches!(
+                kind,
+                serenity::ChannelType::PublicThread
+                    | serenity::ChannelType::PrivateThread
+                    | serenity::ChannelType::NewsThread
+            )
+        };
+
+        if let Self::Application(ctx) = self {
+            if let Some(channel) = &ctx.interaction.channel {
+                return channel.parent_id.filter(|_| is_thread(channel.kind));
+            }
+        }
+        // Threads only exist in guilds
+        self.guild_id()?;
+
+        match self.channel().await? {
+            serenity::Channel::Guild(channel) => channel.parent_id.filter(|_| is_thread(channel.kind)),
+            _ => None,
+        }
+    }
+
+    /// Returns whether this command was invoked in a thread, e.g. to rename the thread
+    ///
+    /// See [`Self::thread_parent`] for how the channel is looked up. Returns false if the channel
+    /// HTTP request fails
+    await (is_thread self)
+    (pub async fn is_thread(self) -> bool) {
+        self.thread_parent().await.is_some()
+    }
+
+    /// Returns the effective permissions of the invoking member in this channel, e.g. to branch on
+    /// whether the author is a moderator
+    ///
+    /// Application commands use the permissions resolved by Discord, which are contained in the
+    /// interaction. Otherwise, they are computed from the guild, channel and member, using the
+    /// cache if available.
+    ///
+    /// Returns None if in DMs, or if the guild, channel or member couldn't be retrieved
+    await (author_permissions self)
+    (pub async fn author_permissions(self) -> Option<serenity::Permissions>) {
+        let guild_id = self.guild_id()?;
+        if let Self::Application(ctx) = self {
+            if let Some(permissions) = ctx.interaction.member.as_ref().and_then(|m| m.permissions) {
+                return Some(permissions);
+            }
+        }
+
+        crate::dispatch::user_permissions(
+            self.serenity_context(),
+            Some(guild_id),
+            self.channel_id(),
+            self.author().id,
+            self.framework().options.member_roles_cache.as_deref(),
+        )
+        .await
+    }
+
+    /// Returns the bot's permissions in the channel of this application command, as computed by
+    /// Discord and sent along with the interaction
+    ///
+    /// This is more accurate than computing them from the cache, and requires no requests. The
+    /// framework uses it for [`crate::Command::required_bot_permissions`] when available.
+    ///
+    /// Returns None for prefix commands
+    (app_permissions self)
+    (pub fn app_permissions(self) -> Option<serenity::Permissions>) {
+        match self {
+            Self::Application(ctx) => ctx.interaction.app_permissions,
+            Self::Prefix(_) => None,
+        }
+    }
+
+    /// Returns the ID and position of the invoking member's highest role, e.g. to check the role
+    /// hierarchy in moderation commands. See [`crate::Context::compare_highest_roles`].
+    ///
+    /// Members without any roles get the `@everyone` role, at position 0. If two roles have the
+    /// same position, the one with the lower ID is considered higher, like Discord does. The guild
+    /// roles are read from cache if available, otherwise fetched via HTTP.
+    ///
+    /// Returns None if in DMs, or if the member or the guild roles couldn't be retrieved
+    await (author_highest_role self)
+    (pub async fn author_highest_role(self) -> Option<(serenity::RoleId, u16)>) {
+        let member = self.author_member().await?;
+        self.highest_role(&member.roles).await
+    }
+
+    // Doesn't fit in with the rest of the functions here but it's convenient
+    /// Returns the given user as a member of the guild of this context
+    ///
+    /// Attempts to find the member in cache, if cache feature is enabled. Otherwise, falls back to
+    /// an HTTP request
+    ///
+    /// Returns None if in DMs, or if the member HTTP request fails
+    await (member self user_id)
+    (pub async fn member(self, user_id: serenity::UserId) -> Option<serenity::Member>) {
+        self.guild_id()?.member(self.serenity_context(), user_id).await.ok()
+    }
+
+    // Doesn't fit in with the rest of the functions here but it's convenient
+    /// Returns the user with the given ID
+    ///
+    /// Attempts to find the user in cache, if cache feature is enabled. Otherwise, falls back to an
+    /// HTTP request
+    ///
+    /// Returns None if the user HTTP request fails
+    await (user self user_id)
+    (pub async fn user(self, user_id: serenity::UserId) -> Option<serenity::User>) {
+        user_id.to_user(self.serenity_context()).await.ok()
+    }
+
+    /// Returns which kind of invocation this is, for when matching on the [`Context`] enum itself
+    /// would be overkill
+    (kind self)
+    (pub fn kind(self) -> ContextKind) {
+        match self {
+            Self::Prefix(_) => ContextKind::Prefix,
+            Self::Application(ctx) => match ctx.interaction_type {
+                CommandInteractionType::Autocomplete => ContextKind::Autocomplete,
+                CommandInteractionType::Command => {
+                    match ctx.interaction.data.kind {
+                        serenity::CommandType::ChatInput => ContextKind::Slash,
+                        _ => ContextKind::ContextMenu,
+                    }
+                }
+            },
+        }
+    }
+
     /// Return the datetime of the invoking message or interaction
+    ///
+    /// For slash commands, this is the creation time encoded in the interaction ID. See
+    /// [`Self::age`] to measure how long ago the command was invoked.
     (created_at self)
     (pub fn created_at(self) -> serenity::Timestamp) {
         match self {
@@ -290,6 +622,25 @@ context_methods! {
         }
     }
 
+    /// Return how much time has passed since the invoking message or interaction was created,
+    /// according to the local system clock
+    ///
+    /// Useful to report processing latency in a ping command or to ignore stale invocations.
+    /// Returns [`std::time::Duration::ZERO`] if the system clock is behind Discord's.
+    (age self)
+    (pub fn age(self) -> std::time::Duration) {
+        let created_at_millis = match self {
+            Self::Application(ctx) => ctx.interaction.id.get(),
+            Self::Prefix(ctx) => ctx.msg.id.get(),
+        } >> 22;
+        // Snowflakes store milliseconds since the Discord epoch, the first second of 2015
+        let created_at = std::time::UNIX_EPOCH
+            + std::time::Duration::from_millis(created_at_millis + 1_420_070_400_000);
+        std::time::SystemTime::now()
+            .duration_since(created_at)
+            .unwrap_or_default()
+    }
+
     /// Get the author of the command message or application command.
     (author self)
     (pub fn author(self) -> &'a serenity::User) {
@@ -299,6 +650,29 @@ context_methods! {
         }
     }
 
+    /// Return the user that a user context menu command was invoked on.
+    ///
+    /// None for any other kind of command. The target is also passed to the command function
+    /// directly, but this is convenient in checks, hooks and error handlers.
+    (target_user self)
+    (pub fn target_user(self) -> Option<&'a serenity::User>) {
+        match self.target()? {
+            serenity::ResolvedTarget::User(user, _) => Some(user),
+            _ => None,
+        }
+    }
+
+    /// Return the message that a message context menu command was invoked on.
+    ///
+    /// None for any other kind of command. See [`Self::target_user`].
+    (target_message self)
+    (pub fn target_message(self) -> Option<&'a serenity::Message>) {
+        match self.target()? {
+            serenity::ResolvedTarget::Message(message) => Some(message),
+            _ => None,
+        }
+    }
+
     /// Return a ID that uniquely identifies this command invocation.
     #[cfg(any(feature = "chrono", feature = "time"))]
     (id self)
@@ -359,17 +733,70 @@ context_methods! {
         }
     }
 
+    /// Returns the common start of the custom IDs of components created for this invocation:
+    /// [`crate::FrameworkOptions::component_id_prefix`] followed by [`Self::id`].
+    ///
+    /// Append a suffix to distinguish multiple components, and filter collectors by this string
+    /// to only receive interactions with this invocation's components.
+    #[cfg(any(feature = "chrono", feature = "time"))]
+    (component_id_base self)
+    (pub fn component_id_base(self) -> String) {
+        let prefix = self.framework().options.component_id_prefix.as_deref();
+        format!("{}{}", prefix.unwrap_or(""), self.id())
+    }
+
     /// Returns the command name that this command was invoked with
     ///
     /// Mainly useful in prefix context, for example to check whether a command alias was used.
     ///
     /// In slash contexts, the given command name will always be returned verbatim, since there are
-    /// no slash command aliases and the user has no control over spelling
+    /// no slash command aliases and the user has no control over spelling. For subcommands, that's
+    /// the subcommand's name, not the name of the top-level command.
     (invoked_command_name self)
     (pub fn invoked_command_name(self) -> &'a str) {
         match self {
             Self::Prefix(ctx) => ctx.invoked_command_name,
-            Self::Application(ctx) => &ctx.interaction.data.name,
+            Self::Application(ctx) if ctx.parent_commands.is_empty() => &ctx.interaction.data.name,
+            Self::Application(ctx) => &ctx.command.name,
+        }
+    }
+
+    /// Returns the full chain of command names that this command was invoked with, from the
+    /// top-level command down to the invoked subcommand, e.g. `["config", "set"]`.
+    ///
+    /// In slash contexts, this includes subcommand group names. In prefix contexts, these are the
+    /// names as typed by the user, so they may be aliases (see [`Self::invoked_command_name`])
+    (invoked_command_path self)
+    (pub fn invoked_command_path(self) -> Vec<&'a str>) {
+        match self {
+            Self::Prefix(ctx) => {
+                let content = &ctx.msg.content;
+                let invocation = content.get(ctx.prefix.len()..content.len() - ctx.args.len());
+                match invocation {
+                    Some(invocation) => invocation.split_whitespace().collect(),
+                    None => ctx
+                        .parent_commands
+                        .iter()
+                        .chain(std::iter::once(&ctx.command))
+                        .map(|command| &*command.name)
+                        .collect(),
+                }
+            }
+            Self::Application(ctx) => {
+                let mut path = vec![&*ctx.interaction.data.name];
+                let mut options = &ctx.interaction.data.options;
+                while let Some(option) = options.first() {
+                    match &option.value {
+                        serenity::CommandDataOptionValue::SubCommand(sub_options)
+                        | serenity::CommandDataOptionValue::SubCommandGroup(sub_options) => {
+                            path.push(&*option.name);
+                            options = sub_options;
+                        }
+                        _ => break,
+                    }
+                }
+                path
+            }
         }
     }
 
@@ -396,62 +823,37 @@ context_methods! {
     /// For example `"/slash_command subcommand arg1:value1 arg2:value2"`.
     (invocation_string self)
     (pub fn invocation_string(self) -> String) {
-        match self {
-            Context::Application(ctx) => {
-                let mut string = String::from("/");
-                for parent_command in ctx.parent_commands {
-                    string += &parent_command.name;
-                    string += " ";
-                }
-                string += &ctx.command.name;
-                for arg in ctx.args {
-                    #[allow(unused_imports)] // required for simd-json
-                    use ::serenity::json::*;
-                    use std::fmt::Write as _;
-
-                    string += " ";
-                    string += &arg.name;
-                    string += ":";
+        self.format_invocation(false)
+    }
 
-                    let _ = match arg.value {
-                        // This was verified to match Discord behavior when copy-pasting a not-yet
-                        // sent slash command invocation
-                        serenity::ResolvedValue::Attachment(_) => write!(string, ""),
-                        serenity::ResolvedValue::Boolean(x) => write!(string, "{}", x),
-                        serenity::ResolvedValue::Integer(x) => write!(string, "{}", x),
-                        serenity::ResolvedValue::Number(x) => write!(string, "{}", x),
-                        serenity::ResolvedValue::String(x) => write!(string, "{}", x),
-                        serenity::ResolvedValue::Channel(x) => {
-                            write!(string, "#{}", x.name.as_deref().unwrap_or(""))
-                        }
-                        serenity::ResolvedValue::Role(x) => write!(string, "@{}", x.name),
-                        serenity::ResolvedValue::User(x, _) => {
-                            string.push('@');
-                            string.push_str(&x.name);
-                            if let Some(discrim) = x.discriminator {
-                                let _ = write!(string, "#{discrim:04}");
-                            }
-                            Ok(())
-                        }
+    /// Like [`Self::invocation_string`], but with the values of
+    /// [`#[redact]`](crate::CommandParameter::redact) parameters replaced by `***`.
+    ///
+    /// Useful for logging invocations in [`crate::FrameworkOptions::pre_command`] without leaking
+    /// sensitive input.
+    ///
+    /// Prefix command arguments can't be reliably attributed to parameters before parsing, so if
+    /// any parameter is redacted, the entire argument string is replaced.
+    (redacted_invocation_string self)
+    (pub fn redacted_invocation_string(self) -> String) {
+        self.format_invocation(true)
+    }
 
-                        serenity::ResolvedValue::Unresolved(_)
-                        | serenity::ResolvedValue::SubCommand(_)
-                        | serenity::ResolvedValue::SubCommandGroup(_)
-                        | serenity::ResolvedValue::Autocomplete { .. } => {
-                            tracing::warn!("unexpected interaction option type");
-                            Ok(())
-                        }
-                        // We need this because ResolvedValue is #[non_exhaustive]
-                        _ => {
-                            tracing::warn!("newly-added unknown interaction option type");
-                            Ok(())
-                        }
-                    };
-                }
-                string
-            }
-            Context::Prefix(ctx) => ctx.msg.content.clone(),
-        }
+    /// Returns the arguments of this invocation as name-value pairs, for generic logging or
+    /// debugging without per-command code.
+    ///
+    /// For slash commands, these are the resolved options. For prefix commands, the argument
+    /// string is split into whitespace-separated (optionally quoted) tokens which are named after
+    /// the command parameters in order; this is best-effort, since tokens can't be reliably
+    /// attributed to parameters before parsing. Tokens beyond the last parameter are named by
+    /// their index.
+    ///
+    /// Values of [`#[redact]`](crate::CommandParameter::redact) parameters are replaced by `***`.
+    /// Like in [`Self::redacted_invocation_string`], prefix commands with any redacted parameter
+    /// have all of their values replaced.
+    (invocation_args self)
+    (pub fn invocation_args(self) -> Vec<(String, String)>) {
+        self.collect_invocation_args()
     }
 
     /// Stores the given value as the data for this command invocation
@@ -477,6 +879,32 @@ context_methods! {
         .ok()
     }
 
+    /// Returns the value of the given type attached to the invoked command via
+    /// [`crate::Command::insert_extension`], if any
+    ///
+    /// ```rust
+    /// # type Context<'a> = poise::Context<'a, (), ()>;
+    /// struct FeatureFlags {
+    ///     beta: bool,
+    /// }
+    ///
+    /// #[poise::command(slash_command)]
+    /// async fn beta(ctx: Context<'_>) -> Result<(), ()> {
+    ///     if ctx.command_extension::<FeatureFlags>().is_some_and(|flags| flags.beta) {
+    ///         // ...
+    ///     }
+    ///     Ok(())
+    /// }
+    ///
+    /// let mut command = beta();
+    /// command.insert_extension(FeatureFlags { beta: true });
+    /// assert!(command.extension::<FeatureFlags>().unwrap().beta);
+    /// ```
+    (command_extension self)
+    (pub fn command_extension<T: std::any::Any>(self) -> Option<&'a T>) {
+        self.command().extension()
+    }
+
     /// If available, returns the locale (selected language) of the invoking user
     (locale self)
     (pub fn locale(self) -> Option<&'a str>) {
@@ -486,20 +914,55 @@ context_methods! {
         }
     }
 
+    /// If available, returns the preferred locale of the guild this command was invoked in, e.g.
+    /// to localize server-wide announcements independently of the invoking user's
+    /// [`Self::locale`]
+    ///
+    /// Application contexts take it from the interaction. In prefix contexts, it's read from the
+    /// cached guild and therefore returned owned, because the cache entry can't stay borrowed;
+    /// use `.as_deref()` to get an `Option<&str>`. Returns None outside of guilds, and in prefix
+    /// contexts if the guild isn't cached.
+    (guild_locale self)
+    (pub fn guild_locale(self) -> Option<std::borrow::Cow<'a, str>>) {
+        match self {
+            Context::Application(ctx) => ctx
+                .interaction
+                .guild_locale
+                .as_deref()
+                .map(std::borrow::Cow::Borrowed),
+            #[cfg(feature = "cache")]
+            Context::Prefix(_) => self
+                .guild()
+                .map(|guild| std::borrow::Cow::Owned(guild.preferred_locale.clone())),
+            #[cfg(not(feature = "cache"))]
+            Context::Prefix(_) => None,
+        }
+    }
+
     /// Builds a [`crate::CreateReply`] by combining the builder closure with the defaults that were
-    /// pre-configured in poise.
+    /// pre-configured in poise, like [`crate::Command::ephemeral`].
     ///
     /// This is primarily an internal function and only exposed for people who want to manually
     /// convert [`crate::CreateReply`] instances into Discord requests.
     #[allow(unused_mut)] // side effect of how macro works
     (reply_builder self builder)
     (pub fn reply_builder(self, mut builder: crate::CreateReply) -> crate::CreateReply) {
-        builder.allowed_mentions = builder.allowed_mentions.or_else(|| self.framework().options().allowed_mentions.clone());
+        let options = self.framework().options();
+        builder = builder
+            .with_command_defaults(self.command())
+            .with_mention_defaults(options.allowed_mentions.as_ref(), options.default_reply_ping);
 
         if let Some(callback) = self.framework().options().reply_callback {
             builder = callback(self, builder);
         }
 
+        // Buttons from command_custom_id have no invocation message that could be replied to
+        if let Self::Prefix(ctx) = self {
+            if ctx.trigger == crate::MessageDispatchTrigger::ComponentInteraction {
+                builder.reply = false;
+            }
+        }
+
         builder
     }
 
@@ -520,12 +983,20 @@ context_methods! {
         &self.serenity_context().http
     }
 
+    /// Returns the ID of the shard which received the event that triggered this command.
+    ///
+    /// Shorthand for [`.serenity_context().shard_id`](serenity::Context::shard_id)
+    (shard_id self)
+    (pub fn shard_id(self) -> serenity::ShardId) {
+        self.serenity_context().shard_id
+    }
+
     /// Returns the current gateway heartbeat latency ([`::serenity::gateway::Shard::latency()`]).
     ///
     /// If the shard has just connected, this value is zero.
     await (ping self)
     (pub async fn ping(self) -> std::time::Duration) {
-        match self.framework().shard_manager.runners.lock().await.get(&self.serenity_context().shard_id) {
+        match self.framework().shard_manager.runners.lock().await.get(&self.shard_id()) {
             Some(runner) => runner.latency.unwrap_or(std::time::Duration::ZERO),
             None => {
                 tracing::error!("current shard is not in shard_manager.runners, this shouldn't happen");
@@ -535,7 +1006,198 @@ context_methods! {
     }
 }
 
+/// Looks up the positions of the given roles. Unknown roles are skipped
+fn role_positions(
+    roles: &std::collections::HashMap<serenity::RoleId, serenity::Role>,
+    member_roles: &[serenity::RoleId],
+) -> Vec<(serenity::RoleId, u16)> {
+    member_roles
+        .iter()
+        .filter_map(|id| Some((*id, roles.get(id)?.position)))
+        .collect()
+}
+
+/// Orders roles by their position in the hierarchy. Discord breaks ties by ID, with the older
+/// role ranking higher
+fn compare_roles(a: (serenity::RoleId, u16), b: (serenity::RoleId, u16)) -> std::cmp::Ordering {
+    a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0))
+}
+
+/// Writes a human-readable representation of a slash command argument value
+fn write_resolved_value(string: &mut String, value: &serenity::ResolvedValue<'_>) {
+    #[allow(unused_imports)] // required for simd-json
+    use ::serenity::json::*;
+    use std::fmt::Write as _;
+
+    let _ = match value {
+        // This was verified to match Discord behavior when copy-pasting a not-yet
+        // sent slash command invocation
+        serenity::ResolvedValue::Attachment(_) => write!(string, ""),
+        serenity::ResolvedValue::Boolean(x) => write!(string, "{}", x),
+        serenity::ResolvedValue::Integer(x) => write!(string, "{}", x),
+        serenity::ResolvedValue::Number(x) => write!(string, "{}", x),
+        serenity::ResolvedValue::String(x) => write!(string, "{}", x),
+        serenity::ResolvedValue::Channel(x) => {
+            write!(string, "#{}", x.name.as_deref().unwrap_or(""))
+        }
+        serenity::ResolvedValue::Role(x) => write!(string, "@{}", x.name),
+        serenity::ResolvedValue::User(x, _) => {
+            string.push('@');
+            string.push_str(&x.name);
+            if let Some(discrim) = x.discriminator {
+                let _ = write!(string, "#{discrim:04}");
+            }
+            Ok(())
+        }
+
+        serenity::ResolvedValue::Unresolved(_)
+        | serenity::ResolvedValue::SubCommand(_)
+        | serenity::ResolvedValue::SubCommandGroup(_)
+        | serenity::ResolvedValue::Autocomplete { .. } => {
+            tracing::warn!("unexpected interaction option type");
+            Ok(())
+        }
+        // We need this because ResolvedValue is #[non_exhaustive]
+        _ => {
+            tracing::warn!("newly-added unknown interaction option type");
+            Ok(())
+        }
+    };
+}
+
 impl<'a, U, E> Context<'a, U, E> {
+    /// Opens the given modal as the initial response to this application command and waits for
+    /// the user to submit it. See [`crate::execute_modal`] for details.
+    ///
+    /// Returns an error if this is a prefix context, or if the interaction has already been
+    /// responded to (including via [`Self::defer`]), since a modal must be the first response.
+    pub async fn send_modal<M: crate::Modal>(
+        self,
+        defaults: Option<M>,
+        timeout: Option<std::time::Duration>,
+    ) -> Result<Option<M>, serenity::Error>
+    where
+        U: Send + Sync,
+    {
+        let ctx = match self {
+            Self::Application(ctx) => ctx,
+            Self::Prefix(_) => {
+                return Err(serenity::Error::Other(
+                    "modals can only be sent in application command contexts",
+                ))
+            }
+        };
+        if ctx
+            .has_sent_initial_response
+            .load(std::sync::atomic::Ordering::SeqCst)
+        {
+            return Err(serenity::Error::Other(
+                "a modal must be the initial response to an interaction",
+            ));
+        }
+        crate::execute_modal(ctx, defaults, timeout).await
+    }
+
+    /// Shorthand of [`crate::send_reply_then_followup`]
+    ///
+    /// ```rust,no_run
+    /// # async fn _test(ctx: poise::Context<'_, (), ()>) -> Result<(), poise::serenity_prelude::Error> {
+    /// ctx.send_then_followup(
+    ///     poise::CreateReply::default().content("Poll created!"),
+    ///     poise::CreateReply::default().content("Only you can see this").ephemeral(true),
+    /// )
+    /// .await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn send_then_followup(
+        self,
+        initial: crate::CreateReply,
+        followup: crate::CreateReply,
+    ) -> Result<(crate::ReplyHandle<'a>, crate::ReplyHandle<'a>), serenity::Error> {
+        crate::send_reply_then_followup(self, initial, followup).await
+    }
+
+    /// Implementation of [`Self::invocation_string`] and [`Self::redacted_invocation_string`]
+    fn format_invocation(self, redact: bool) -> String {
+        match self {
+            Context::Application(ctx) => {
+                let mut string = String::from("/");
+                for parent_command in ctx.parent_commands {
+                    string += &parent_command.name;
+                    string += " ";
+                }
+                string += &ctx.command.name;
+                for arg in ctx.args {
+                    string += " ";
+                    string += arg.name;
+                    string += ":";
+
+                    let is_redacted = ctx
+                        .command
+                        .parameters
+                        .iter()
+                        .any(|p| p.redact && p.name == arg.name);
+                    if redact && is_redacted {
+                        string += "***";
+                        continue;
+                    }
+
+                    write_resolved_value(&mut string, &arg.value);
+                }
+                string
+            }
+            Context::Prefix(ctx) => {
+                let content = &ctx.msg.content;
+                let is_redacted = ctx.command.parameters.iter().any(|p| p.redact);
+                if redact && is_redacted && !ctx.args.is_empty() {
+                    // The args are always a suffix of the message content
+                    format!("{}***", &content[..content.len() - ctx.args.len()])
+                } else {
+                    content.clone()
+                }
+            }
+        }
+    }
+
+    /// Implementation of [`Self::invocation_args`]
+    fn collect_invocation_args(self) -> Vec<(String, String)> {
+        match self {
+            Context::Application(ctx) => ctx
+                .args
+                .iter()
+                .map(|arg| {
+                    let is_redacted = ctx
+                        .command
+                        .parameters
+                        .iter()
+                        .any(|p| p.redact && p.name == arg.name);
+                    let mut value = String::new();
+                    if is_redacted {
+                        value += "***";
+                    } else {
+                        write_resolved_value(&mut value, &arg.value);
+                    }
+                    (arg.name.to_owned(), value)
+                })
+                .collect(),
+            Context::Prefix(ctx) => {
+                let is_redacted = ctx.command.parameters.iter().any(|p| p.redact);
+                let mut args = Vec::new();
+                let mut remaining = ctx.args;
+                while let Ok((rest, token)) = crate::pop_string(remaining) {
+                    let name = match ctx.command.parameters.get(args.len()) {
+                        Some(param) => param.name.clone(),
+                        None => args.len().to_string(),
+                    };
+                    let value = if is_redacted { "***".into() } else { token };
+                    args.push((name, value));
+                    remaining = rest;
+                }
+                args
+            }
+        }
+    }
+
     /// Actual implementation of rerun() that returns `FrameworkError` for implementation convenience
     async fn rerun_inner(self) -> Result<(), crate::FrameworkError<'a, U, E>> {
         match self {
@@ -598,6 +1260,122 @@ impl<'a, U, E> Context<'a, U, E> {
             Context::Prefix(ctx) => ctx.invocation_data,
         }
     }
+
+    /// Compares the positions of the highest roles of two members of this guild, e.g. to prevent
+    /// moderators from banning someone with a higher role than themselves
+    ///
+    /// Returns [`std::cmp::Ordering::Greater`] if `a` has a higher role than `b`. Roles are
+    /// looked up like in [`Self::author_highest_role`]. Note that the guild owner can moderate
+    /// everyone regardless of roles; this is not taken into account here.
+    ///
+    /// Returns None if in DMs, or if the members or the guild roles couldn't be retrieved
+    pub async fn compare_highest_roles(
+        self,
+        a: serenity::UserId,
+        b: serenity::UserId,
+    ) -> Option<std::cmp::Ordering> {
+        let a = self.highest_role(&self.member(a).await?.roles).await?;
+        let b = self.highest_role(&self.member(b).await?.roles).await?;
+        Some(compare_roles(a, b))
+    }
+
+    /// Returns the highest of the given roles of a member of this guild, see
+    /// [`Self::author_highest_role`]
+    async fn highest_role(
+        self,
+        member_roles: &[serenity::RoleId],
+    ) -> Option<(serenity::RoleId, u16)> {
+        let guild_id = self.guild_id()?;
+
+        #[cfg(feature = "cache")]
+        let cached_positions = self
+            .guild()
+            .map(|guild| role_positions(&guild.roles, member_roles));
+        #[cfg(not(feature = "cache"))]
+        let cached_positions = None;
+        let positions = match cached_positions {
+            Some(positions) => positions,
+            None => {
+                let roles = guild_id.roles(self.http()).await.ok()?;
+                role_positions(&roles, member_roles)
+            }
+        };
+
+        Some(
+            positions
+                .into_iter()
+                .max_by(|&a, &b| compare_roles(a, b))
+                .unwrap_or((guild_id.everyone_role(), 0)),
+        )
+    }
+
+    /// Responds to a component interaction, like a button press received via a
+    /// [`serenity::ComponentInteractionCollector`], by editing the message the component is
+    /// attached to in place
+    ///
+    /// Uses the `UpdateMessage` interaction response type instead of sending a new message, which
+    /// is the building block for stateful component UIs. Like [`Self::send`], the builder is
+    /// combined with the framework defaults via [`Self::reply_builder`]. Ephemerality can't be
+    /// changed when editing, so [`crate::CreateReply::ephemeral`] is ignored.
+    ///
+    /// ```rust,no_run
+    /// # use poise::serenity_prelude as serenity;
+    /// # async fn _test(ctx: poise::Context<'_, (), ()>) -> Result<(), serenity::Error> {
+    /// while let Some(press) = serenity::ComponentInteractionCollector::new(ctx)
+    ///     .author_id(ctx.author().id)
+    ///     .await
+    /// {
+    ///     let reply = poise::CreateReply::default().content("You pressed the button!");
+    ///     ctx.update_response(&press, reply).await?;
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub async fn update_response(
+        self,
+        interaction: &serenity::ComponentInteraction,
+        builder: crate::CreateReply,
+    ) -> Result<(), serenity::Error> {
+        let mut builder = self.reply_builder(builder);
+        builder.ephemeral = None;
+        let response = builder.to_slash_initial_response(Default::default());
+        interaction
+            .create_response(
+                self.serenity_context(),
+                serenity::CreateInteractionResponse::UpdateMessage(response),
+            )
+            .await
+    }
+
+    /// Translates `key` into the locale of the invoking user ([`Self::locale`]) using
+    /// [`crate::FrameworkOptions::translator`], filling in the given named arguments.
+    ///
+    /// Falls back to the key itself if no translator is configured or the translation is
+    /// missing, so untranslated strings are easy to spot.
+    ///
+    /// ```rust,no_run
+    /// # async fn _test(ctx: poise::Context<'_, (), ()>) -> Result<(), poise::serenity_prelude::Error> {
+    /// let name = &ctx.author().name;
+    /// ctx.say(ctx.tr("greeting", &[("name", name)])).await?;
+    /// # Ok(()) }
+    /// ```
+    pub fn tr(self, key: &str, args: &[(&str, &dyn std::fmt::Display)]) -> String {
+        self.framework()
+            .options()
+            .translator
+            .as_ref()
+            .and_then(|translator| translator.translate(self.locale(), key, args))
+            .unwrap_or_else(|| key.to_owned())
+    }
+
+    /// Return the resolved target of a context menu command
+    fn target(self) -> Option<serenity::ResolvedTarget<'a>> {
+        match self {
+            Self::Application(ctx) if ctx.interaction_type == CommandInteractionType::Command => {
+                ctx.interaction.data.target()
+            }
+            _ => None,
+        }
+    }
 }
 
 /// Forwards for serenity::Context's impls. With these, poise's Context types can be passed in as-is
@@ -682,3 +1460,39 @@ impl<'a, U, E> From<Context<'a, U, E>> for PartialContext<'a, U, E> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_role_hierarchy() {
+        let role = |id, position| {
+            let mut role = serenity::Role::default();
+            role.id = serenity::RoleId::new(id);
+            role.position = position;
+            (role.id, role)
+        };
+        let roles = [role(1, 5), role(2, 3), role(3, 3)]
+            .iter()
+            .cloned()
+            .collect();
+        let id = serenity::RoleId::new;
+
+        // Unknown roles (e.g. from a stale member) are skipped
+        let positions = role_positions(&roles, &[id(2), id(4), id(1)]);
+        assert_eq!(positions, [(id(2), 3), (id(1), 5)]);
+        let highest = positions.into_iter().max_by(|&a, &b| compare_roles(a, b));
+        assert_eq!(highest, Some((id(1), 5)));
+
+        // Same position: the lower ID ranks higher
+        assert_eq!(
+            compare_roles((id(2), 3), (id(3), 3)),
+            std::cmp::Ordering::Greater
+        );
+        assert_eq!(
+            compare_roles((id(3), 3), (id(1), 5)),
+            std::cmp::Ordering::Less
+        );
+    }
+}
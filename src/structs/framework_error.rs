@@ -6,8 +6,11 @@ use crate::serenity_prelude as serenity;
 /// have an `error` field with your error type `E` in it), or originating from within the framework.
 ///
 /// These errors are handled with the [`crate::FrameworkOptions::on_error`] callback
+///
+/// Implements [`std::fmt::Display`] and, if `E` does, [`std::error::Error`]. The error thrown by
+/// user code, if any, is available via [`std::error::Error::source`].
 #[derive(derivative::Derivative)]
-#[derivative(Debug)]
+#[derivative(Debug(bound = "E: std::fmt::Debug"))]
 pub enum FrameworkError<'a, U, E> {
     /// User code threw an error in user data setup
     #[non_exhaustive]
@@ -93,6 +96,8 @@ pub enum FrameworkError<'a, U, E> {
     #[non_exhaustive]
     CooldownHit {
         /// Time until the command may be invoked for the next time in the given context
+        ///
+        /// Use [`crate::builtins::format_duration`] to display it to users
         remaining_cooldown: std::time::Duration,
         /// General context
         ctx: crate::Context<'a, U, E>,
@@ -185,6 +190,12 @@ pub enum FrameworkError<'a, U, E> {
         trigger: crate::MessageDispatchTrigger,
     },
     /// The command name from the interaction is unrecognized
+    ///
+    /// This usually happens when a command was removed from the bot but is still registered on
+    /// Discord, e.g. from an old deployment. The interaction hasn't been responded to yet, so
+    /// Discord will show "The application did not respond" unless the error handler responds.
+    /// [`crate::builtins::on_error`] responds with an ephemeral message saying that the command
+    /// no longer exists.
     #[non_exhaustive]
     UnknownInteraction {
         #[derivative(Debug = "ignore")]
@@ -196,6 +207,48 @@ pub enum FrameworkError<'a, U, E> {
         /// The interaction in question
         interaction: &'a serenity::CommandInteraction,
     },
+    /// Command execution took longer than [`crate::Command::timeout`] or
+    /// [`crate::FrameworkOptions::command_timeout`] and was cancelled
+    #[non_exhaustive]
+    CommandTimeout {
+        /// General context
+        ctx: crate::Context<'a, U, E>,
+    },
+    /// Command was invoked in a guild where it has been disabled via
+    /// [`crate::Command::set_enabled_in_guild`]
+    #[non_exhaustive]
+    CommandDisabled {
+        /// General context
+        ctx: crate::Context<'a, U, E>,
+    },
+    /// [`crate::FrameworkOptions::guild_setup`] returned an error. The command was not executed
+    #[non_exhaustive]
+    GuildSetup {
+        /// Error which was thrown in the guild setup code
+        error: E,
+        /// Guild which was being set up
+        guild_id: serenity::GuildId,
+        /// General context
+        ctx: crate::Context<'a, U, E>,
+    },
+    /// Command was invoked while one of its [rate limit](crate::Command::rate_limit_config)
+    /// buckets was empty
+    #[non_exhaustive]
+    RateLimited {
+        /// Time until the command may be invoked again in the given context
+        ///
+        /// Use [`crate::builtins::format_duration`] to display it to users
+        retry_after: std::time::Duration,
+        /// General context
+        ctx: crate::Context<'a, U, E>,
+    },
+    /// Command was invoked while [`crate::FrameworkOptions::max_concurrent_invocations`] other
+    /// invocations were running, and [`crate::ConcurrencyLimitBehavior::Reject`] is set
+    #[non_exhaustive]
+    ConcurrencyLimitHit {
+        /// General context
+        ctx: crate::Context<'a, U, E>,
+    },
     // #[non_exhaustive] forbids struct update syntax for ?? reason
     #[doc(hidden)]
     __NonExhaustive(std::convert::Infallible),
@@ -223,6 +276,11 @@ impl<'a, U, E> FrameworkError<'a, U, E> {
             Self::DynamicPrefix { ctx, .. } => ctx.serenity_context,
             Self::UnknownCommand { ctx, .. } => ctx,
             Self::UnknownInteraction { ctx, .. } => ctx,
+            Self::CommandTimeout { ctx } => ctx.serenity_context(),
+            Self::CommandDisabled { ctx } => ctx.serenity_context(),
+            Self::GuildSetup { ctx, .. } => ctx.serenity_context(),
+            Self::RateLimited { ctx, .. } => ctx.serenity_context(),
+            Self::ConcurrencyLimitHit { ctx } => ctx.serenity_context(),
             Self::__NonExhaustive(unreachable) => match unreachable {},
         }
     }
@@ -243,6 +301,11 @@ impl<'a, U, E> FrameworkError<'a, U, E> {
             Self::DmOnly { ctx, .. } => ctx,
             Self::NsfwOnly { ctx, .. } => ctx,
             Self::CommandCheckFailed { ctx, .. } => ctx,
+            Self::CommandTimeout { ctx } => ctx,
+            Self::CommandDisabled { ctx } => ctx,
+            Self::GuildSetup { ctx, .. } => ctx,
+            Self::RateLimited { ctx, .. } => ctx,
+            Self::ConcurrencyLimitHit { ctx } => ctx,
             Self::Setup { .. }
             | Self::EventHandler { .. }
             | Self::UnknownCommand { .. }
@@ -252,8 +315,41 @@ impl<'a, U, E> FrameworkError<'a, U, E> {
         })
     }
 
+    /// Returns the name of this error variant, e.g. `"CooldownHit"`
+    pub(crate) fn variant_name(&self) -> &'static str {
+        match self {
+            Self::Setup { .. } => "Setup",
+            Self::EventHandler { .. } => "EventHandler",
+            Self::Command { .. } => "Command",
+            Self::SubcommandRequired { .. } => "SubcommandRequired",
+            Self::CommandPanic { .. } => "CommandPanic",
+            Self::ArgumentParse { .. } => "ArgumentParse",
+            Self::CommandStructureMismatch { .. } => "CommandStructureMismatch",
+            Self::CooldownHit { .. } => "CooldownHit",
+            Self::MissingBotPermissions { .. } => "MissingBotPermissions",
+            Self::MissingUserPermissions { .. } => "MissingUserPermissions",
+            Self::NotAnOwner { .. } => "NotAnOwner",
+            Self::GuildOnly { .. } => "GuildOnly",
+            Self::DmOnly { .. } => "DmOnly",
+            Self::NsfwOnly { .. } => "NsfwOnly",
+            Self::CommandCheckFailed { .. } => "CommandCheckFailed",
+            Self::DynamicPrefix { .. } => "DynamicPrefix",
+            Self::UnknownCommand { .. } => "UnknownCommand",
+            Self::UnknownInteraction { .. } => "UnknownInteraction",
+            Self::CommandTimeout { .. } => "CommandTimeout",
+            Self::CommandDisabled { .. } => "CommandDisabled",
+            Self::GuildSetup { .. } => "GuildSetup",
+            Self::RateLimited { .. } => "RateLimited",
+            Self::ConcurrencyLimitHit { .. } => "ConcurrencyLimitHit",
+            Self::__NonExhaustive(unreachable) => match *unreachable {},
+        }
+    }
+
     /// Calls the appropriate `on_error` function (command-specific or global) with this error
     pub async fn handle(self, framework_options: &crate::FrameworkOptions<U, E>) {
+        // Tag the command invocation span, if any (see the `tracing_spans` feature)
+        tracing::Span::current().record("error", self.variant_name());
+
         let on_error = self
             .ctx()
             .and_then(|c| c.command().on_error)
@@ -404,14 +500,37 @@ impl<U, E: std::fmt::Display> std::fmt::Display for FrameworkError<'_, U, E> {
             Self::UnknownInteraction { interaction, .. } => {
                 write!(f, "unknown interaction `{}`", interaction.data.name)
             }
+            Self::CommandTimeout { ctx } => {
+                write!(f, "command `{}` timed out", full_command_name!(ctx))
+            }
+            Self::CommandDisabled { ctx } => write!(
+                f,
+                "command `{}` is disabled in this guild",
+                full_command_name!(ctx)
+            ),
+            Self::GuildSetup { guild_id, ctx, .. } => write!(
+                f,
+                "error in guild setup for guild {} before command `{}`",
+                guild_id,
+                full_command_name!(ctx)
+            ),
+            Self::RateLimited { retry_after, ctx } => write!(
+                f,
+                "rate limit hit in command `{}` ({:?} until next token)",
+                full_command_name!(ctx),
+                retry_after
+            ),
+            Self::ConcurrencyLimitHit { ctx } => write!(
+                f,
+                "too many concurrent invocations to run command `{}`",
+                full_command_name!(ctx)
+            ),
             Self::__NonExhaustive(unreachable) => match *unreachable {},
         }
     }
 }
 
-impl<'a, U: std::fmt::Debug, E: std::error::Error + 'static> std::error::Error
-    for FrameworkError<'a, U, E>
-{
+impl<'a, U, E: std::error::Error + 'static> std::error::Error for FrameworkError<'a, U, E> {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             Self::Setup { error, .. } => Some(error),
@@ -432,6 +551,11 @@ impl<'a, U: std::fmt::Debug, E: std::error::Error + 'static> std::error::Error
             Self::DynamicPrefix { error, .. } => Some(error),
             Self::UnknownCommand { .. } => None,
             Self::UnknownInteraction { .. } => None,
+            Self::CommandTimeout { .. } => None,
+            Self::CommandDisabled { .. } => None,
+            Self::GuildSetup { error, .. } => Some(error),
+            Self::RateLimited { .. } => None,
+            Self::ConcurrencyLimitHit { .. } => None,
             Self::__NonExhaustive(unreachable) => match *unreachable {},
         }
     }
@@ -23,28 +23,116 @@ pub struct FrameworkOptions<U, E> {
     /// If individual commands add their own check, both callbacks are run and must return true.
     #[derivative(Debug = "ignore")]
     pub command_check: Option<fn(crate::Context<'_, U, E>) -> BoxFuture<'_, Result<bool, E>>>,
+    /// Called once per guild before the first command that runs in it, for lazily provisioning
+    /// per-guild state (e.g. creating a config row). Runs after command checks and before the
+    /// command's cooldowns are started, so a failed setup doesn't put the user on cooldown.
+    ///
+    /// If it returns an error, the command is skipped and the error is passed to
+    /// [`Self::on_error`] as [`crate::FrameworkError::GuildSetup`]; the setup is retried on the
+    /// next command in that guild. Setups of different guilds run concurrently; further commands
+    /// in a guild wait until its setup has finished.
+    #[derivative(Debug = "ignore")]
+    pub guild_setup: Option<
+        for<'a> fn(crate::Context<'a, U, E>, serenity::GuildId) -> BoxFuture<'a, Result<(), E>>,
+    >,
+    /// State of [`Self::guild_setup`] per guild. A guild's cell is initialized once its setup
+    /// has completed successfully.
+    ///
+    /// Remove a guild from this map to have the setup run again before its next command. Guilds
+    /// which the bot is removed from are removed automatically, which requires the `GUILDS`
+    /// intent.
+    pub initialized_guilds: std::sync::Mutex<
+        std::collections::HashMap<serenity::GuildId, std::sync::Arc<tokio::sync::OnceCell<()>>>,
+    >,
     /// If set to true, skips command checks if command was issued by [`FrameworkOptions::owners`]
     pub skip_checks_for_owners: bool,
     /// Default set of allowed mentions to use for all responses
     ///
     /// By default, user pings are allowed and role pings and everyone pings are filtered
     pub allowed_mentions: Option<serenity::CreateAllowedMentions>,
+    /// Whether inline replies, like [`crate::Context::reply`], ping the author of the command
+    /// message
+    ///
+    /// Only applies to replies which don't set [`crate::CreateReply::allowed_mentions`]
+    /// themselves, and only if [`Self::allowed_mentions`] doesn't set
+    /// [`serenity::CreateAllowedMentions::replied_user`]. Defaults to false, i.e. replies
+    /// don't ping.
+    pub default_reply_ping: bool,
     /// Invoked before every message sent using [`crate::Context::say`] or [`crate::Context::send`]
     ///
-    /// Allows you to modify every outgoing message in a central place
+    /// Allows you to modify every outgoing message in a central place, for example for branding
+    /// or disclaimers. Runs right before the builder is converted into a serenity request, for
+    /// initial responses, followups and [`crate::ReplyHandle::edit`] alike. Use the context and
+    /// the builder to conditionally skip replies:
+    ///
+    /// ```rust
+    /// # use poise::serenity_prelude as serenity;
+    /// # poise::FrameworkOptions::<(), serenity::Error> {
+    /// reply_callback: Some(|ctx, mut reply| {
+    ///     // Leave ephemeral replies, such as error messages, and DMs alone
+    ///     if reply.ephemeral == Some(true) || ctx.guild_id().is_none() {
+    ///         return reply;
+    ///     }
+    ///     let footer = serenity::CreateEmbedFooter::new("Need help? discord.gg/example");
+    ///     reply.embeds = reply.embeds.into_iter().map(|e| e.footer(footer.clone())).collect();
+    ///     reply
+    /// }),
+    /// # ..Default::default() };
+    /// ```
     #[derivative(Debug = "ignore")]
     pub reply_callback:
         Option<fn(crate::Context<'_, U, E>, crate::CreateReply) -> crate::CreateReply>,
-    /// If `true`, disables automatic cooldown handling before every command invocation.
+    /// If Some, replies which failed to send due to a transient error, like a Discord server
+    /// error or a timeout, are retried with backoff. See [`crate::ReplyRetry`] for details.
+    ///
+    /// None by default
+    pub reply_retry: Option<crate::ReplyRetry>,
+    /// If `true`, disables automatic cooldown and rate limit handling before every command
+    /// invocation.
     ///
     /// Useful for implementing custom cooldown behavior. See [`crate::Command::cooldowns`] and
     /// the methods on [`crate::Cooldowns`] for how to do that.
     pub manual_cooldowns: bool,
+    /// If Some, cooldowns are checked and started through this storage instead of the in-memory
+    /// [`crate::Command::cooldowns`], e.g. to persist them across restarts. See
+    /// [`crate::CooldownStorage`]
+    ///
+    /// Rate limits are unaffected and always kept in memory. None by default.
+    #[derivative(Debug = "ignore")]
+    pub cooldown_storage: Option<std::sync::Arc<dyn crate::CooldownStorage>>,
     /// If `true`, changes behavior of guild_only command check to abort execution if the guild is
     /// not in cache.
     ///
     /// **If `cache` feature is disabled, this has no effect!**
     pub require_cache_for_guild_check: bool,
+    /// If `true`, every application command is [deferred](crate::Context::defer) after its
+    /// checks passed and before its body runs, so API-heavy commands don't have to do it
+    /// themselves. Replies then automatically become followups. Check errors are sent before
+    /// deferring, so they keep their own visibility.
+    ///
+    /// Commands which open a modal must opt out with [`crate::Command::no_defer`], since a modal
+    /// can only be the initial response. Commands with [`crate::Command::manual_response`] are
+    /// never deferred. False by default.
+    pub defer_all_commands: bool,
+    /// If set, commands which run longer than this are cancelled and
+    /// [`crate::FrameworkError::CommandTimeout`] is emitted. Can be overridden per command with
+    /// [`crate::Command::timeout`].
+    ///
+    /// The command future is simply dropped on timeout. For application commands, Discord
+    /// requires a response within three seconds, so if a command may run into the timeout, make
+    /// sure to [defer](crate::Context::defer) early so the interaction isn't left unacknowledged.
+    pub command_timeout: Option<std::time::Duration>,
+    /// If set, at most this many command invocations run at the same time, to provide backpressure
+    /// when the bot is flooded with commands. Further invocations are queued or rejected, see
+    /// [`Self::on_concurrency_limit`].
+    ///
+    /// An invocation counts from after its checks and guild setup passed until the command
+    /// finished, so invocations which fail their checks are never queued. Autocomplete requests
+    /// aren't limited. None by default.
+    pub max_concurrent_invocations: Option<ConcurrencyLimit>,
+    /// What to do with invocations beyond [`Self::max_concurrent_invocations`]. Default
+    /// [`ConcurrencyLimitBehavior::Queue`]
+    pub on_concurrency_limit: ConcurrencyLimitBehavior,
     /// Called on every Discord event. Can be used to react to non-command events, like messages
     /// deletions or guild updates.
     #[derivative(Debug = "ignore")]
@@ -63,15 +151,181 @@ pub struct FrameworkOptions<U, E> {
     /// User IDs which are allowed to use owners_only commands
     pub owners: std::collections::HashSet<serenity::UserId>,
     /// If true, [`Self::owners`] is automatically initialized with the results of
-    /// [`serenity::Http::get_current_application_info()`].
+    /// [`serenity::Http::get_current_application_info()`]: the application owner or, for
+    /// team-owned applications, all team members. Skipped if [`Self::owners`] is not empty.
     ///
     /// True by default.
     pub initialize_owners: bool,
+    /// If `true`, [`crate::builtins::register_application_commands`] and
+    /// [`crate::builtins::register_application_commands_buttons`] delete the application commands
+    /// which are registered on Discord but missing from [`Self::commands`], within the scope that
+    /// is registered to (globally or the current guild). Each deleted command is logged.
+    ///
+    /// Destructive, so false by default: stale commands are kept. See
+    /// [`crate::builtins::register_in_scope`]
+    pub delete_stale_commands: bool,
+    /// How often [`crate::Framework`] purges outdated data like expired cooldowns or old edit
+    /// tracker entries, in a background task. See [`crate::Purgeable`]
+    ///
+    /// Must not be zero. One minute by default.
+    pub purge_interval: std::time::Duration,
+    /// Prepended to the custom IDs of components created by the framework, i.e. in
+    /// [`crate::builtins::paginate`], [`crate::builtins::confirm`] and [`crate::execute_modal`].
+    ///
+    /// Those custom IDs consist of this prefix, the invocation ID ([`crate::Context::id`]) and a
+    /// suffix, and their collectors only react to IDs of that form. Set this to a string which
+    /// none of your own custom IDs start with to keep them apart, e.g. `"poise:"`.
+    ///
+    /// None by default
+    pub component_id_prefix: Option<String>,
+    /// Called for component and modal interactions which no framework collector is waiting for,
+    /// for example button presses on a [`crate::builtins::paginate`] message after it timed out,
+    /// or on components sent by a previous version of the bot. Use this to acknowledge such
+    /// interactions, e.g. with an ephemeral "this button has expired" message, so the user isn't
+    /// left with a failed interaction.
+    ///
+    /// Poise only knows about the collectors of [`crate::builtins::paginate`],
+    /// [`crate::builtins::confirm`] and [`crate::execute_modal`]. Interactions meant for
+    /// collectors you create yourself are passed to this callback too, so check the custom ID and
+    /// return early for components you handle elsewhere.
+    ///
+    /// None by default, in which case such interactions are ignored.
+    #[derivative(Debug = "ignore")]
+    pub on_unknown_interaction:
+        Option<for<'a> fn(&'a serenity::Context, &'a serenity::Interaction) -> BoxFuture<'a, ()>>,
+    /// Custom ID prefixes of components that framework collectors are currently waiting for.
+    /// Interactions with any other custom ID are passed to [`Self::on_unknown_interaction`].
+    pub active_component_ids: std::sync::Mutex<std::collections::HashSet<String>>,
+    /// Called on every Ready event, i.e. once per shard and again after a shard reconnects and
+    /// was unable to resume its session. Useful for re-applying state like presences.
+    ///
+    /// Unlike the setup callback of [`crate::Framework`], which runs exactly once, this runs
+    /// every time. It's called after the setup callback on the first Ready event.
+    #[derivative(Debug = "ignore")]
+    pub on_ready:
+        Option<for<'a> fn(&'a serenity::Context, &'a serenity::Ready) -> BoxFuture<'a, ()>>,
+    /// Called when the bot joins a guild, and when a guild becomes available, e.g. for the initial
+    /// burst of guilds after the bot connected. Useful for onboarding, like registering
+    /// guild-specific commands.
+    ///
+    /// The `bool` argument tells whether the guild was newly joined, as opposed to having been
+    /// announced in the Ready event. It's only known with the `cache` feature; without it, it's
+    /// always None. Errors are passed to [`Self::on_error`] like those of
+    /// [`Self::event_handler`].
+    ///
+    /// Requires the `GUILDS` intent, which is added automatically (see
+    /// [`Self::automatic_intents`]).
+    #[derivative(Debug = "ignore")]
+    pub on_guild_create: Option<
+        for<'a> fn(
+            &'a serenity::Context,
+            &'a serenity::Guild,
+            Option<bool>,
+            crate::FrameworkContext<'a, U, E>,
+        ) -> BoxFuture<'a, Result<(), E>>,
+    >,
+    /// Called when the bot left a guild or was removed from it, e.g. to clean up configuration.
+    ///
+    /// Not called when a guild merely becomes unavailable due to an outage. The guild is passed as
+    /// well if it was cached. Errors are passed to [`Self::on_error`] like those of
+    /// [`Self::event_handler`].
+    #[derivative(Debug = "ignore")]
+    pub on_guild_delete: Option<
+        for<'a> fn(
+            &'a serenity::Context,
+            serenity::GuildId,
+            Option<&'a serenity::Guild>,
+            crate::FrameworkContext<'a, U, E>,
+        ) -> BoxFuture<'a, Result<(), E>>,
+    >,
+    /// If set, this activity is applied to every shard as soon as it receives its Ready event
+    pub initial_activity: Option<serenity::ActivityData>,
+    /// If Some, member roles are cached for permission checks instead of fetching the member on
+    /// every invocation. Requires the `GUILD_MEMBERS` intent to stay up to date.
+    ///
+    /// See [`crate::MemberRolesCache`] for staleness guarantees. None by default.
+    pub member_roles_cache: Option<std::sync::Arc<std::sync::RwLock<crate::MemberRolesCache>>>,
+    /// Translation backend used by [`crate::Context::tr`] to localize responses
+    ///
+    /// Complements the localizations of command names and descriptions, which are registered with
+    /// Discord. None by default.
+    #[derivative(Debug = "ignore")]
+    pub translator: Option<std::sync::Arc<dyn crate::Translator>>,
+    /// Supplies command and parameter name and description localizations at runtime.
+    ///
+    /// Invoked once via [`crate::localize_commands`] when the framework starts, before commands
+    /// are registered. None by default.
+    #[derivative(Debug = "ignore")]
+    pub command_localizer: Option<std::sync::Arc<dyn crate::CommandLocalizer>>,
+    /// If true, [`crate::FrameworkBuilder::client_builder`] adds the intents returned by
+    /// [`Self::automatic_intents`] to the intents you pass, so that prefix commands and commands
+    /// with [`crate::Command::required_intents`] work out of the box
+    ///
+    /// True by default. Has no effect if you create the [`serenity::ClientBuilder`] yourself.
+    pub add_automatic_intents: bool,
     // #[non_exhaustive] forbids struct update syntax for ?? reason
     #[doc(hidden)]
     pub __non_exhaustive: (),
 }
 
+/// Limits how many command invocations run at the same time. See
+/// [`FrameworkOptions::max_concurrent_invocations`]
+#[derive(Debug)]
+pub struct ConcurrencyLimit {
+    /// One permit per invocation that may run
+    permits: tokio::sync::Semaphore,
+}
+
+impl ConcurrencyLimit {
+    /// Allows at most `max_concurrent_invocations` command invocations to run at the same time
+    ///
+    /// Panics if `max_concurrent_invocations` is zero, since no command could ever run
+    pub fn new(max_concurrent_invocations: usize) -> Self {
+        assert!(
+            max_concurrent_invocations > 0,
+            "a concurrency limit of zero would never let any command run",
+        );
+        Self {
+            permits: tokio::sync::Semaphore::new(max_concurrent_invocations),
+        }
+    }
+
+    /// Takes a permit to run an invocation if one is free. The permit must be held while the
+    /// command runs
+    pub(crate) fn try_acquire(&self) -> Option<tokio::sync::SemaphorePermit<'_>> {
+        self.permits.try_acquire().ok()
+    }
+
+    /// Waits until a permit to run an invocation is free and takes it. The permit must be held
+    /// while the command runs
+    pub(crate) async fn acquire(&self) -> tokio::sync::SemaphorePermit<'_> {
+        self.permits
+            .acquire()
+            .await
+            .expect("the semaphore is never closed")
+    }
+}
+
+/// What to do with command invocations beyond [`FrameworkOptions::max_concurrent_invocations`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ConcurrencyLimitBehavior {
+    /// Wait until one of the running invocations has finished. Waiting invocations still take up
+    /// memory, so under sustained load, [`Self::Reject`] may be preferable
+    ///
+    /// Since Discord requires a response to application commands within three seconds, an
+    /// application command which has to wait is deferred first, with its
+    /// [`crate::Command::ephemeral`] setting, regardless of
+    /// [`FrameworkOptions::defer_all_commands`]. Commands with [`crate::Command::no_defer`] or
+    /// [`crate::Command::manual_response`] aren't deferred and may time out while waiting.
+    Queue,
+    /// Drop the invocation and emit [`crate::FrameworkError::ConcurrencyLimitHit`]. By default,
+    /// application commands are answered with an ephemeral message saying that the bot is busy,
+    /// so they don't show as failed, and prefix commands are ignored silently
+    Reject,
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
 impl<U, E> FrameworkOptions<U, E> {
     /// Add a new command to the framework
     #[deprecated = "supply commands in FrameworkOptions directly with `commands: vec![...]`"]
@@ -83,6 +337,135 @@ impl<U, E> FrameworkOptions<U, E> {
         meta_builder(&mut command);
         self.commands.push(command);
     }
+
+    /// Marks components whose custom ID starts with `id` as handled by a collector until the
+    /// returned guard is dropped. See [`Self::active_component_ids`]
+    pub(crate) fn claim_component_ids(&self, id: String) -> ComponentIdClaim<'_> {
+        self.active_component_ids.lock().unwrap().insert(id.clone());
+        ComponentIdClaim {
+            active_component_ids: &self.active_component_ids,
+            id,
+        }
+    }
+
+    /// Returns whether a framework collector is waiting for a component with this custom ID
+    pub(crate) fn is_component_id_active(&self, custom_id: &str) -> bool {
+        self.active_component_ids
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|id| custom_id.starts_with(id.as_str()))
+    }
+
+    /// Returns an iterator over all commands, including nested subcommands, e.g. to generate a
+    /// full command reference
+    ///
+    /// Commands are visited depth-first, so every command is directly followed by its
+    /// subcommands. Use [`crate::Command::qualified_name`] to get the full invocation path, which
+    /// is the same for prefix (`~parent child`) and slash commands (`/parent child`). Note that
+    /// qualified names are only filled in by [`crate::Framework`] or
+    /// [`crate::set_qualified_names`].
+    ///
+    /// ```rust
+    /// # type Error = Box<dyn std::error::Error + Send + Sync>;
+    /// #[poise::command(prefix_command, slash_command, subcommands("child"))]
+    /// async fn parent(ctx: poise::Context<'_, (), Error>) -> Result<(), Error> { Ok(()) }
+    /// #[poise::command(prefix_command, slash_command)]
+    /// async fn child(ctx: poise::Context<'_, (), Error>) -> Result<(), Error> { Ok(()) }
+    /// #[poise::command(prefix_command, slash_command)]
+    /// async fn other(ctx: poise::Context<'_, (), Error>) -> Result<(), Error> { Ok(()) }
+    ///
+    /// let mut options = poise::FrameworkOptions {
+    ///     commands: vec![parent(), other()],
+    ///     ..Default::default()
+    /// };
+    /// poise::set_qualified_names(&mut options.commands);
+    ///
+    /// let names = options
+    ///     .all_commands()
+    ///     .map(|command| command.qualified_name.as_str())
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(names, ["parent", "parent child", "other"]);
+    /// ```
+    pub fn all_commands(&self) -> impl Iterator<Item = &crate::Command<U, E>> {
+        let mut stack = self.commands.iter().rev().collect::<Vec<_>>();
+        std::iter::from_fn(move || {
+            let command = stack.pop()?;
+            stack.extend(command.subcommands.iter().rev());
+            Some(command)
+        })
+    }
+
+    /// Returns the union of [`crate::Command::required_intents`] of all commands and subcommands
+    pub fn required_intents(&self) -> serenity::GatewayIntents {
+        self.all_commands()
+            .fold(serenity::GatewayIntents::empty(), |intents, command| {
+                intents | command.required_intents
+            })
+    }
+
+    /// Returns the intents which the configured commands need to be received at all:
+    /// [`Self::required_intents`], plus `GUILD_MESSAGES`, `DIRECT_MESSAGES` and
+    /// `MESSAGE_CONTENT` if there are prefix commands, and `GUILDS` if [`Self::on_guild_create`]
+    /// or [`Self::on_guild_delete`] is set
+    ///
+    /// See [`Self::add_automatic_intents`]
+    ///
+    /// ```rust
+    /// # use poise::serenity_prelude as serenity;
+    /// # type Error = Box<dyn std::error::Error + Send + Sync>;
+    /// #[poise::command(prefix_command)]
+    /// async fn ping(ctx: poise::Context<'_, (), Error>) -> Result<(), Error> { Ok(()) }
+    ///
+    /// let options = poise::FrameworkOptions { commands: vec![ping()], ..Default::default() };
+    /// assert!(options
+    ///     .automatic_intents()
+    ///     .contains(serenity::GatewayIntents::MESSAGE_CONTENT));
+    /// ```
+    pub fn automatic_intents(&self) -> serenity::GatewayIntents {
+        let mut intents = self.required_intents();
+        if self
+            .all_commands()
+            .any(|command| command.prefix_action.is_some())
+        {
+            intents |= serenity::GatewayIntents::GUILD_MESSAGES
+                | serenity::GatewayIntents::DIRECT_MESSAGES
+                | serenity::GatewayIntents::MESSAGE_CONTENT;
+        }
+        if self.on_guild_create.is_some() || self.on_guild_delete.is_some() {
+            intents |= serenity::GatewayIntents::GUILDS;
+        }
+        intents
+    }
+}
+
+/// Purges the edit tracker, the member roles cache and the cooldowns of all commands
+impl<U, E> crate::Purgeable for FrameworkOptions<U, E> {
+    fn purge(&self) {
+        if let Some(edit_tracker) = &self.prefix_options.edit_tracker {
+            edit_tracker.purge();
+        }
+        if let Some(member_roles_cache) = &self.member_roles_cache {
+            member_roles_cache.purge();
+        }
+        for command in &self.commands {
+            command.purge();
+        }
+    }
+}
+
+/// Removes a custom ID prefix from [`FrameworkOptions::active_component_ids`] when dropped
+pub(crate) struct ComponentIdClaim<'a> {
+    /// The set to remove the ID from
+    active_component_ids: &'a std::sync::Mutex<std::collections::HashSet<String>>,
+    /// The claimed custom ID prefix
+    id: String,
+}
+
+impl Drop for ComponentIdClaim<'_> {
+    fn drop(&mut self) {
+        self.active_component_ids.lock().unwrap().remove(&self.id);
+    }
 }
 
 impl<U, E> Default for FrameworkOptions<U, E>
@@ -106,21 +489,65 @@ where
             pre_command: |_| Box::pin(async {}),
             post_command: |_| Box::pin(async {}),
             command_check: None,
+            guild_setup: None,
+            initialized_guilds: Default::default(),
             skip_checks_for_owners: false,
             allowed_mentions: Some(
                 // Only support direct user pings by default
-                serenity::CreateAllowedMentions::default()
-                    .all_users(true)
-                    // https://github.com/serenity-rs/poise/issues/176
-                    .replied_user(true),
+                serenity::CreateAllowedMentions::default().all_users(true),
             ),
+            default_reply_ping: false,
             reply_callback: None,
+            reply_retry: None,
             manual_cooldowns: false,
+            cooldown_storage: None,
             require_cache_for_guild_check: false,
+            defer_all_commands: false,
+            command_timeout: None,
+            max_concurrent_invocations: None,
+            on_concurrency_limit: ConcurrencyLimitBehavior::Queue,
             prefix_options: Default::default(),
             owners: Default::default(),
             initialize_owners: true,
+            delete_stale_commands: false,
+            purge_interval: std::time::Duration::from_secs(60),
+            component_id_prefix: None,
+            on_unknown_interaction: None,
+            active_component_ids: Default::default(),
+            on_ready: None,
+            on_guild_create: None,
+            on_guild_delete: None,
+            initial_activity: None,
+            member_roles_cache: None,
+            translator: None,
+            command_localizer: None,
+            add_automatic_intents: true,
             __non_exhaustive: (),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::FutureExt as _;
+
+    #[tokio::test]
+    async fn test_concurrency_limit() {
+        let limit = ConcurrencyLimit::new(1);
+
+        let permit = limit.try_acquire();
+        assert!(permit.is_some());
+        assert!(limit.try_acquire().is_none());
+        // Queued invocations wait until the running one has finished
+        assert!(limit.acquire().now_or_never().is_none());
+        drop(permit);
+        assert!(limit.acquire().now_or_never().is_some());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_concurrency_limit() {
+        ConcurrencyLimit::new(0);
+    }
+}
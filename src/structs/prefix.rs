@@ -12,6 +12,9 @@ pub enum MessageDispatchTrigger {
     /// The message was edited, and was not a valid invocation pre-edit (i.e. user typoed the
     /// command, then fixed it)
     MessageEditFromInvalid,
+    /// A button with a [custom ID encoding the invocation](crate::command_custom_id) was clicked.
+    /// The message is made up from the interaction, with the clicking user as author
+    ComponentInteraction,
     #[doc(hidden)]
     __NonExhaustive,
 }
@@ -55,6 +58,10 @@ pub struct PrefixContext<'a, U, E> {
     pub action: fn(
         PrefixContext<'_, U, E>,
     ) -> crate::BoxFuture<'_, Result<(), crate::FrameworkError<'_, U, E>>>,
+    /// The raw event which triggered this invocation, if it was dispatched through
+    /// [`crate::dispatch_event`]. See [`crate::Context::raw_event`]
+    #[derivative(Debug = "ignore")]
+    pub raw_event: Option<&'a serenity::FullEvent>,
 
     // #[non_exhaustive] forbids struct update syntax for ?? reason
     #[doc(hidden)]
@@ -72,6 +79,31 @@ impl<U, E> crate::_GetGenerics for PrefixContext<'_, U, E> {
     type E = E;
 }
 
+/// Describes a prefix command invocation which was executed successfully by
+/// [`crate::dispatch_message`]
+#[derive(derivative::Derivative)]
+#[derivative(Debug(bound = ""))]
+pub struct CommandResult<'a, U, E> {
+    /// The command which was executed; a subcommand if one was invoked
+    pub command: &'a crate::Command<U, E>,
+    /// Prefix used by the user to invoke this command
+    pub prefix: &'a str,
+    /// Command name or alias used by the user to invoke this command
+    pub invoked_command_name: &'a str,
+    /// How this command invocation was triggered
+    pub trigger: MessageDispatchTrigger,
+    // #[non_exhaustive] forbids struct update syntax for ?? reason
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+// manual Copy+Clone implementations because Rust is getting confused about the type parameter
+impl<U, E> Clone for CommandResult<'_, U, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<U, E> Copy for CommandResult<'_, U, E> {}
+
 /// Possible ways to define a command prefix
 #[derive(Clone, Debug)]
 pub enum Prefix {
@@ -79,6 +111,52 @@ pub enum Prefix {
     Literal(&'static str),
     /// Regular expression which matches the prefix
     Regex(regex::Regex),
+    /// Custom matcher function which returns the length in bytes of the prefix at the start of the
+    /// given message content, or `None` if the message doesn't start with a prefix
+    Matcher(fn(&str) -> Option<usize>),
+    #[doc(hidden)]
+    __NonExhaustive,
+}
+
+impl Prefix {
+    /// Returns the length in bytes of this prefix at the start of `content`, if it matches
+    pub fn match_len(&self, content: &str) -> Option<usize> {
+        let len = match self {
+            Self::Literal(prefix) => content.starts_with(prefix).then_some(prefix.len()),
+            Self::Regex(prefix) => prefix
+                .find(content)
+                .filter(|regex_match| regex_match.start() == 0)
+                .map(|regex_match| regex_match.end()),
+            Self::Matcher(matcher) => matcher(content),
+            Self::__NonExhaustive => unreachable!(),
+        }?;
+        // Don't trust custom matchers to return a valid split point
+        content.is_char_boundary(len).then_some(len)
+    }
+
+    /// Splits `content` into the matched prefix and the rest, if this prefix matches
+    pub fn strip<'a>(&self, content: &'a str) -> Option<(&'a str, &'a str)> {
+        Some(content.split_at(self.match_len(content)?))
+    }
+}
+
+/// What to do when a prefix command is invoked with more arguments than it accepts. See
+/// [`PrefixFrameworkOptions::on_extra_arguments`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ExtraArgumentsBehavior {
+    /// Fail with a [`crate::FrameworkError::ArgumentParse`] error, since extra arguments may
+    /// indicate a typo. This is the default
+    ///
+    /// To handle this case in [`crate::FrameworkOptions::on_error`], check whether the `error`
+    /// field of the [`crate::FrameworkError::ArgumentParse`] is a [`crate::TooManyArguments`],
+    /// for example with `error.downcast_ref::<poise::TooManyArguments>()`.
+    Error,
+    /// Discard the extra arguments, as if every command had the `discard_spare_arguments`
+    /// attribute
+    Ignore,
+    /// Append the extra arguments to the last parameter, separated by a space, if that's a plain
+    /// [`String`] parameter. For other commands, behaves like [`Self::Error`]
+    AppendToLastArgument,
     #[doc(hidden)]
     __NonExhaustive,
 }
@@ -94,10 +172,47 @@ pub struct PrefixFrameworkOptions<U, E> {
     // TODO: maybe it would be nicer to have separate fields for literal and regex prefixes
     // That way, you don't need to wrap every single literal prefix in a long path which looks ugly
     pub additional_prefixes: Vec<Prefix>,
+    /// Called when one of [`Self::additional_prefixes`] matches, with the matched prefix string.
+    /// If it returns false, the prefix is treated as not matching and the remaining prefixes are
+    /// tried.
+    ///
+    /// Useful for prefixes that only a subset of users may use, like a staff-only debug prefix:
+    /// ```rust
+    /// # poise::PrefixFrameworkOptions::<(), ()> {
+    /// additional_prefixes: vec![poise::Prefix::Literal("sudo!")],
+    /// additional_prefix_check: Some(|ctx, prefix| Box::pin(async move {
+    ///     prefix != "sudo!" || ctx.framework.options.owners.contains(&ctx.author.id)
+    /// })),
+    /// # ..Default::default() };
+    /// ```
+    #[derivative(Debug = "ignore")]
+    pub additional_prefix_check:
+        Option<for<'a> fn(crate::PartialContext<'a, U, E>, &'a str) -> BoxFuture<'a, bool>>,
     /// Callback invoked on every message to return a prefix.
     ///
     /// Override this field for a simple dynamic prefix which changes depending on the guild or user.
     ///
+    /// Return an empty string to not require a prefix at all, e.g. in a dedicated bot channel
+    /// where every message is a command. Messages which don't contain a valid command are then
+    /// reported as [`crate::FrameworkError::UnknownCommand`] with an empty prefix, which
+    /// [`crate::builtins::on_error`] only logs at debug level. The bot's own messages are still
+    /// ignored as per [`Self::execute_self_messages`] and [`Self::ignore_bots`].
+    ///
+    /// ```rust
+    /// # use poise::serenity_prelude as serenity;
+    /// const COMMAND_CHANNEL: serenity::ChannelId = serenity::ChannelId::new(123456789);
+    ///
+    /// # poise::PrefixFrameworkOptions::<(), ()> {
+    /// dynamic_prefix: Some(|ctx| Box::pin(async move {
+    ///     if ctx.channel_id == COMMAND_CHANNEL {
+    ///         Ok(Some(String::new()))
+    ///     } else {
+    ///         Ok(Some("~".into()))
+    ///     }
+    /// })),
+    /// # ..Default::default() };
+    /// ```
+    ///
     /// For more advanced dynamic prefixes, see [`Self::stripped_dynamic_prefix`]
     #[derivative(Debug = "ignore")]
     pub dynamic_prefix:
@@ -126,6 +241,16 @@ pub struct PrefixFrameworkOptions<U, E> {
     >,
     /// Treat a bot mention (a ping) like a prefix
     pub mention_as_prefix: bool,
+    /// Callback to normalize message content before prefix and command matching, for example to
+    /// strip zero-width spaces or replace smart quotes.
+    ///
+    /// Commands see the transformed content in [`crate::PrefixContext::msg`]. Edit tracking keeps
+    /// working as usual since messages are tracked by ID; the callback is reapplied on every edit.
+    ///
+    /// Only applies to messages dispatched via [`crate::dispatch_event`]; if you call
+    /// [`crate::dispatch_message`] directly, preprocess the message yourself.
+    #[derivative(Debug = "ignore")]
+    pub content_preprocessor: Option<fn(&str) -> std::borrow::Cow<'_, str>>,
     /// If Some, the framework will react to message edits by editing the corresponding bot response
     /// with the new result.
     pub edit_tracker: Option<std::sync::Arc<std::sync::RwLock<crate::EditTracker>>>,
@@ -152,6 +277,21 @@ pub struct PrefixFrameworkOptions<U, E> {
     pub ignore_thread_creation: bool,
     /// Whether command names should be compared case-insensitively.
     pub case_insensitive_commands: bool,
+    /// What to do when a command is invoked with more arguments than it accepts. Commands with a
+    /// trailing `#[rest]` parameter or the `discard_spare_arguments` attribute never receive extra
+    /// arguments.
+    ///
+    /// Extra arguments are only considered if parsing fails with [`crate::TooManyArguments`], so
+    /// this doesn't change how valid invocations are parsed, and malformed arguments are still
+    /// reported as such. Default [`ExtraArgumentsBehavior::Error`]
+    pub on_extra_arguments: ExtraArgumentsBehavior,
+    /// Called when a message consists of nothing but a prefix (for example just a bot mention).
+    ///
+    /// Receives the matched prefix. Useful to reply with a short help text. If `None`, such
+    /// messages are treated like any other unknown command.
+    #[derivative(Debug = "ignore")]
+    pub on_empty_prefix:
+        Option<for<'a> fn(crate::PartialContext<'a, U, E>, &'a str) -> BoxFuture<'a, ()>>,
     /* // TODO: implement
     /// Whether to invoke help command when someone sends a message with just a bot mention
     pub help_when_mentioned: bool,
@@ -171,9 +311,11 @@ impl<U, E> Default for PrefixFrameworkOptions<U, E> {
         Self {
             prefix: None,
             additional_prefixes: Vec::new(),
+            additional_prefix_check: None,
             dynamic_prefix: None,
             stripped_dynamic_prefix: None,
             mention_as_prefix: true,
+            content_preprocessor: None,
             edit_tracker: None,
             execute_untracked_edits: true,
             ignore_edits_if_not_yet_responded: false,
@@ -181,6 +323,8 @@ impl<U, E> Default for PrefixFrameworkOptions<U, E> {
             ignore_bots: true,
             ignore_thread_creation: true,
             case_insensitive_commands: true,
+            on_extra_arguments: ExtraArgumentsBehavior::Error,
+            on_empty_prefix: None,
             // help_when_mentioned: true,
             // help_commmand: None,
             // command_specific_help_commmand: None,
@@ -188,3 +332,31 @@ impl<U, E> Default for PrefixFrameworkOptions<U, E> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_matcher() {
+        fn emoji_prefix(content: &str) -> Option<usize> {
+            ["🤖", "🐍", "⭐"]
+                .iter()
+                .find(|emoji| content.starts_with(**emoji))
+                .map(|emoji| emoji.len())
+        }
+        let prefix = Prefix::Matcher(emoji_prefix);
+
+        assert_eq!(prefix.strip("🤖ping"), Some(("🤖", "ping")));
+        assert_eq!(prefix.strip("⭐ help me"), Some(("⭐", " help me")));
+        assert_eq!(prefix.strip("ping 🤖"), None);
+
+        // Invalid lengths from a matcher are rejected instead of panicking
+        assert_eq!(Prefix::Matcher(|_| Some(1)).strip("🤖ping"), None);
+
+        assert_eq!(Prefix::Literal("~").strip("~ping"), Some(("~", "ping")));
+        let regex = Prefix::Regex(regex::Regex::new("hey bot,?").unwrap());
+        assert_eq!(regex.strip("hey bot, ping"), Some(("hey bot,", " ping")));
+        assert_eq!(regex.strip("ping hey bot"), None);
+    }
+}
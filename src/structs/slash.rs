@@ -47,6 +47,10 @@ pub struct ApplicationContext<'a, U, E> {
     pub data: &'a U,
     /// Custom user data carried across a single command invocation
     pub invocation_data: &'a tokio::sync::Mutex<Box<dyn std::any::Any + Send + Sync>>,
+    /// The raw event which triggered this invocation, if it was dispatched through
+    /// [`crate::dispatch_event`]. See [`crate::Context::raw_event`]
+    #[derivative(Debug = "ignore")]
+    pub raw_event: Option<&'a serenity::FullEvent>,
     // #[non_exhaustive] forbids struct update syntax for ?? reason
     #[doc(hidden)]
     pub __non_exhaustive: (),
@@ -145,6 +149,9 @@ pub struct CommandParameter<U, E> {
     pub channel_types: Option<Vec<serenity::ChannelType>>,
     /// If this parameter is a choice parameter, this is the fixed list of options
     pub choices: Vec<CommandParameterChoice>,
+    /// Whether this parameter's value should be hidden in
+    /// [`crate::Context::redacted_invocation_string`], e.g. for passwords
+    pub redact: bool,
     /// Closure that sets this parameter's type and min/max value in the given builder
     ///
     /// For example a u32 [`CommandParameter`] would store this as the [`Self::type_setter`]:
@@ -209,3 +216,43 @@ impl<U, E> CommandParameter<U, E> {
         Some((self.type_setter?)(builder))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_localized_choices() {
+        let choice = |name: &str, localized: &str| CommandParameterChoice {
+            name: name.into(),
+            localizations: [("de".to_string(), localized.to_string())]
+                .iter()
+                .cloned()
+                .collect(),
+            __non_exhaustive: (),
+        };
+        let parameter = CommandParameter::<(), ()> {
+            name: "mode".into(),
+            name_localizations: Default::default(),
+            description: None,
+            description_localizations: Default::default(),
+            required: true,
+            channel_types: None,
+            choices: vec![choice("Fast", "Schnell"), choice("Slow", "Langsam")],
+            redact: false,
+            type_setter: Some(|b| b.kind(serenity::CommandOptionType::Integer)),
+            autocomplete_callback: None,
+            __non_exhaustive: (),
+        };
+
+        let option = parameter.create_as_slash_command_option().unwrap();
+        let json = ::serenity::json::to_value(&option).unwrap();
+        let choices = json["choices"].as_array().unwrap();
+        assert_eq!(choices.len(), 2);
+        assert_eq!(choices[0]["name"], "Fast");
+        assert_eq!(choices[0]["name_localizations"]["de"], "Schnell");
+        // Discord sends back the value, not the label, so the locale doesn't matter for parsing
+        assert_eq!(choices[1]["value"], 1);
+        assert_eq!(choices[1]["name_localizations"]["de"], "Langsam");
+    }
+}
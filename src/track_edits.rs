@@ -85,12 +85,21 @@ impl EditTracker {
     /// Returns a copy of a newly up-to-date cached message, or a brand new generated message when
     /// not in cache. Also returns a bool with `true` if this message was previously tracked
     ///
-    /// Returns None if the command shouldn't be re-run, e.g. if the message content wasn't edited
+    /// Returns None if the command shouldn't be re-run: if the message content wasn't changed (e.g.
+    /// for updates caused by embeds loading or pins), or if the message was sent longer ago than
+    /// the tracking duration
     pub fn process_message_update(
         &mut self,
         user_msg_update: &serenity::MessageUpdateEvent,
         ignore_edits_if_not_yet_responded: bool,
     ) -> Option<(serenity::Message, bool)> {
+        // Creation timestamp is derived from the ID, so this works with partial updates too
+        let age = serenity::Timestamp::now().unix_timestamp()
+            - user_msg_update.id.created_at().unix_timestamp();
+        if age >= self.max_duration.as_secs() as i64 {
+            return None;
+        }
+
         match self
             .cache
             .iter_mut()
@@ -101,20 +110,18 @@ impl EditTracker {
                     return None;
                 }
 
-                // If message content wasn't touched, don't re-run command
-                // Note: this may be Some, but still identical to previous content. We want to
-                // re-run the command in that case too; because that means the user explicitly
-                // edited their message
-                #[allow(clippy::question_mark)]
-                if user_msg_update.content.is_none() {
-                    return None;
+                // If message content wasn't changed, don't re-run command. Discord also sends
+                // updates with unchanged content, e.g. when embeds are resolved
+                match &user_msg_update.content {
+                    Some(content) if *content != invocation.user_msg.content => {}
+                    _ => return None,
                 }
 
                 update_message(&mut invocation.user_msg, user_msg_update.clone());
                 Some((invocation.user_msg.clone(), true))
             }
             None => {
-                if ignore_edits_if_not_yet_responded {
+                if ignore_edits_if_not_yet_responded || user_msg_update.content.is_none() {
                     return None;
                 }
                 let mut user_msg = serenity::CustomMessage::new().build();
@@ -127,10 +134,23 @@ impl EditTracker {
     /// Removes this command invocation from the cache and returns the associated bot response,
     /// if the command invocation is cached, and it has an associated bot response, and the command
     /// is marked track_deletion
+    ///
+    /// If the deleted message is a tracked bot response instead, it's forgotten, so that the next
+    /// response of that invocation is sent as a new message.
     pub fn process_message_delete(
         &mut self,
         deleted_message_id: serenity::MessageId,
     ) -> Option<serenity::Message> {
+        if let Some(invocation) = self.cache.iter_mut().find(|invocation| {
+            invocation
+                .bot_response
+                .as_ref()
+                .is_some_and(|response| response.id == deleted_message_id)
+        }) {
+            invocation.bot_response = None;
+            return None;
+        }
+
         let invocation = self.cache.remove(
             self.cache
                 .iter()
@@ -156,6 +176,18 @@ impl EditTracker {
         });
     }
 
+    /// Forgets the bot response associated with the given user message, if any, while keeping the
+    /// invocation itself tracked
+    pub fn forget_bot_response(&mut self, user_msg_id: serenity::MessageId) {
+        if let Some(invocation) = self
+            .cache
+            .iter_mut()
+            .find(|invocation| invocation.user_msg.id == user_msg_id)
+        {
+            invocation.bot_response = None;
+        }
+    }
+
     /// Given a message by a user, find the corresponding bot response, if one exists and is cached.
     pub fn find_bot_response(
         &self,
@@ -208,3 +240,93 @@ impl EditTracker {
         }
     }
 }
+
+impl crate::Purgeable for std::sync::RwLock<EditTracker> {
+    fn purge(&self) {
+        self.write().unwrap().purge();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deleted_bot_response_is_forgotten() {
+        let message = |id| {
+            let mut message = serenity::CustomMessage::new().build();
+            message.id = serenity::MessageId::new(id);
+            message
+        };
+        let (user_msg, bot_response) = (message(1), message(2));
+
+        let mut tracker = EditTracker::for_timespan(std::time::Duration::from_secs(60))
+            .into_inner()
+            .unwrap();
+        tracker.set_bot_response(&user_msg, bot_response.clone(), true);
+        assert_eq!(
+            tracker.find_bot_response(user_msg.id).map(|m| m.id),
+            Some(bot_response.id)
+        );
+
+        // Deleting the bot response keeps the invocation but makes the next response a new message
+        assert!(tracker.process_message_delete(bot_response.id).is_none());
+        assert!(tracker.find_bot_response(user_msg.id).is_none());
+        assert_eq!(tracker.cache.len(), 1);
+
+        tracker.set_bot_response(&user_msg, message(3), true);
+        assert_eq!(
+            tracker.process_message_delete(user_msg.id).map(|m| m.id),
+            Some(serenity::MessageId::new(3))
+        );
+        assert!(tracker.cache.is_empty());
+    }
+
+    #[test]
+    fn test_message_update_filtering() {
+        let update = |id: serenity::MessageId, content: Option<&str>| {
+            ::serenity::json::from_value::<serenity::MessageUpdateEvent>(::serenity::json::json!({
+                "id": id.to_string(),
+                "channel_id": "1",
+                "content": content,
+            }))
+            .unwrap()
+        };
+        // Snowflakes encode their creation time: milliseconds since 2015 in the upper bits
+        let snowflake = |age: std::time::Duration| {
+            let unix_ms = serenity::Timestamp::now().unix_timestamp() as u64 * 1000;
+            let discord_ms = unix_ms - 1_420_070_400_000 - age.as_millis() as u64;
+            serenity::MessageId::new(discord_ms << 22)
+        };
+
+        let mut tracker = EditTracker::for_timespan(std::time::Duration::from_secs(3600))
+            .into_inner()
+            .unwrap();
+
+        // Edits of old messages are ignored
+        let old = snowflake(std::time::Duration::from_secs(2 * 3600));
+        assert!(tracker
+            .process_message_update(&update(old, Some("~ping")), false)
+            .is_none());
+
+        let recent = snowflake(std::time::Duration::from_secs(60));
+        let (msg, previously_tracked) = tracker
+            .process_message_update(&update(recent, Some("~ping")), false)
+            .unwrap();
+        assert!(!previously_tracked);
+
+        // Once tracked, only updates which change the content are re-dispatched
+        tracker.track_command(&msg, false);
+        assert!(tracker
+            .process_message_update(&update(recent, None), false)
+            .is_none());
+        assert!(tracker
+            .process_message_update(&update(recent, Some("~ping")), false)
+            .is_none());
+        let (msg, previously_tracked) = tracker
+            .process_message_update(&update(recent, Some("~pong")), false)
+            .unwrap();
+        assert!(previously_tracked);
+        assert_eq!(msg.content, "~pong");
+    }
+}
@@ -0,0 +1,289 @@
+//! Integration points for translation backends like Fluent or gettext, used to localize responses
+//! with [`crate::Context::tr`] and command definitions with [`localize_commands`]
+
+/// A translation backend which looks up localized strings by key
+///
+/// poise doesn't ship an implementation; wrap your Fluent bundles, gettext catalogs or plain
+/// hashmaps in a type implementing this trait and supply it in
+/// [`crate::FrameworkOptions::translator`].
+///
+/// ```rust
+/// struct Translations(std::collections::HashMap<(&'static str, &'static str), &'static str>);
+///
+/// impl poise::Translator for Translations {
+///     fn translate(
+///         &self,
+///         locale: Option<&str>,
+///         key: &str,
+///         args: &[(&str, &dyn std::fmt::Display)],
+///     ) -> Option<String> {
+///         let mut text = self.0.get(&(locale.unwrap_or("en-US"), key))?.to_string();
+///         for (name, value) in args {
+///             text = text.replace(&format!("{{{}}}", name), &value.to_string());
+///         }
+///         Some(text)
+///     }
+/// }
+///
+/// let translations = Translations(
+///     [(("en-US", "greeting"), "Hello, {name}!"), (("de", "greeting"), "Hallo, {name}!")]
+///         .iter()
+///         .copied()
+///         .collect(),
+/// );
+/// let text = poise::Translator::translate(&translations, Some("de"), "greeting", &[("name", &"Ferris")]);
+/// assert_eq!(text.as_deref(), Some("Hallo, Ferris!"));
+/// ```
+pub trait Translator: Send + Sync {
+    /// Returns the translation of `key` into `locale`, with the given named arguments filled in,
+    /// or None if there is no translation
+    ///
+    /// `locale` is a Discord locale like `en-US` or `de`, or None if the locale of the user is
+    /// unknown, e.g. in prefix commands. Implementations usually fall back to a default language
+    /// in that case.
+    fn translate(
+        &self,
+        locale: Option<&str>,
+        key: &str,
+        args: &[(&str, &dyn std::fmt::Display)],
+    ) -> Option<String>;
+}
+
+/// Which localizable string of a command or parameter a [`LocalizationKey`] refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LocalizedField {
+    /// The name of the command or parameter
+    Name,
+    /// The description of the command or parameter
+    Description,
+}
+
+/// Identifies a string to be localized by a [`CommandLocalizer`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LocalizationKey<'a> {
+    /// Full name of the command including parent command names, like `settings language`
+    pub command: &'a str,
+    /// Name of the parameter, or None if this is about the command itself
+    pub parameter: Option<&'a str>,
+    /// Whether the name or the description is to be localized
+    pub field: LocalizedField,
+    /// The unlocalized string, as written in the command definition
+    pub default: &'a str,
+}
+
+/// Supplies localized command and parameter names and descriptions at runtime, e.g. from a
+/// database, as an alternative to the static `name_localized` and `description_localized`
+/// attributes
+///
+/// Set in [`crate::FrameworkOptions::command_localizer`] to be invoked once when the framework
+/// starts, before the bot is Ready and commands are registered; or call [`localize_commands`]
+/// yourself.
+///
+/// ```rust
+/// struct DatabaseLocalizer;
+///
+/// #[poise::async_trait]
+/// impl poise::CommandLocalizer for DatabaseLocalizer {
+///     fn locales(&self) -> Vec<String> {
+///         vec!["de".into()]
+///     }
+///
+///     async fn localize(&self, locale: &str, key: poise::LocalizationKey<'_>) -> Option<String> {
+///         // Query your database here
+///         match (locale, key.default) {
+///             ("de", "ping") => Some("klingeln".into()),
+///             _ => None,
+///         }
+///     }
+/// }
+/// ```
+#[async_trait::async_trait]
+pub trait CommandLocalizer: Send + Sync {
+    /// The Discord locales to request localizations for, like `de` or `es-ES`
+    fn locales(&self) -> Vec<String>;
+
+    /// Returns the localization of the given string into `locale`, or None to keep the
+    /// localization from the command definition, if any
+    async fn localize(&self, locale: &str, key: LocalizationKey<'_>) -> Option<String>;
+}
+
+/// Owned form of [`LocalizationKey`], to hold on to keys across awaits
+struct OwnedLocalizationKey {
+    /// See [`LocalizationKey::command`]
+    command: String,
+    /// See [`LocalizationKey::parameter`]
+    parameter: Option<String>,
+    /// See [`LocalizationKey::field`]
+    field: LocalizedField,
+    /// See [`LocalizationKey::default`]
+    default: String,
+}
+
+/// Visits all localizable strings of the given commands and their subcommands in a stable order
+fn visit_localizable<U, E>(
+    commands: &mut [crate::Command<U, E>],
+    parent_name: &str,
+    visit: &mut dyn FnMut(OwnedLocalizationKey, &mut std::collections::HashMap<String, String>),
+) {
+    for command in commands {
+        let command_name = if parent_name.is_empty() {
+            command.name.clone()
+        } else {
+            format!("{} {}", parent_name, command.name)
+        };
+        let key = |parameter: Option<&String>, field, default: &String| OwnedLocalizationKey {
+            command: command_name.clone(),
+            parameter: parameter.cloned(),
+            field,
+            default: default.clone(),
+        };
+
+        visit(
+            key(None, LocalizedField::Name, &command.name),
+            &mut command.name_localizations,
+        );
+        if let Some(description) = &command.description {
+            visit(
+                key(None, LocalizedField::Description, description),
+                &mut command.description_localizations,
+            );
+        }
+        for parameter in &mut command.parameters {
+            visit(
+                key(Some(&parameter.name), LocalizedField::Name, &parameter.name),
+                &mut parameter.name_localizations,
+            );
+            if let Some(description) = &parameter.description {
+                visit(
+                    key(
+                        Some(&parameter.name),
+                        LocalizedField::Description,
+                        description,
+                    ),
+                    &mut parameter.description_localizations,
+                );
+            }
+        }
+
+        visit_localizable(&mut command.subcommands, &command_name, visit);
+    }
+}
+
+/// Fills in the name and description localizations of the given commands, their parameters and
+/// their subcommands with the strings supplied by `localizer`
+///
+/// Localizations returned by the localizer take precedence over the ones from the command
+/// definition, which are kept for locales where the localizer returns None. The results are
+/// stored in the commands, so the localizer is invoked only once per string and locale, no matter
+/// how often the commands are registered afterwards.
+///
+/// The framework calls this automatically if [`crate::FrameworkOptions::command_localizer`] is set.
+pub async fn localize_commands<U, E>(
+    commands: &mut [crate::Command<U, E>],
+    localizer: &dyn CommandLocalizer,
+) {
+    let locales = localizer.locales();
+
+    let mut keys = Vec::new();
+    visit_localizable(commands, "", &mut |key, _| keys.push(key));
+
+    let mut localizations = Vec::with_capacity(keys.len());
+    for key in &keys {
+        let key = LocalizationKey {
+            command: &key.command,
+            parameter: key.parameter.as_deref(),
+            field: key.field,
+            default: &key.default,
+        };
+        let mut localized = Vec::new();
+        for locale in &locales {
+            if let Some(text) = localizer.localize(locale, key).await {
+                localized.push((locale.clone(), text));
+            }
+        }
+        localizations.push(localized);
+    }
+
+    let mut localizations = localizations.into_iter();
+    visit_localizable(commands, "", &mut |_, map| {
+        map.extend(localizations.next().into_iter().flatten());
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestLocalizer;
+
+    #[async_trait::async_trait]
+    impl CommandLocalizer for TestLocalizer {
+        fn locales(&self) -> Vec<String> {
+            vec!["de".into(), "fr".into()]
+        }
+
+        async fn localize(&self, locale: &str, key: LocalizationKey<'_>) -> Option<String> {
+            match (locale, key.field) {
+                ("de", LocalizedField::Name) => Some(format!("{}_de", key.default)),
+                ("de", LocalizedField::Description) => {
+                    Some(format!("{} ({})", key.default, key.command))
+                }
+                _ => None,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_localize_commands() {
+        let mut commands = vec![crate::Command::<(), ()> {
+            name: "settings".into(),
+            description: Some("Change settings".into()),
+            name_localizations: [("fr".to_string(), "parametres".to_string())]
+                .iter()
+                .cloned()
+                .collect(),
+            subcommands: vec![crate::Command {
+                name: "language".into(),
+                parameters: vec![crate::CommandParameter {
+                    name: "locale".into(),
+                    name_localizations: Default::default(),
+                    description: Some("Language to use".into()),
+                    description_localizations: Default::default(),
+                    required: true,
+                    channel_types: None,
+                    choices: Vec::new(),
+                    redact: false,
+                    type_setter: None,
+                    autocomplete_callback: None,
+                    __non_exhaustive: (),
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        }];
+
+        localize_commands(&mut commands, &TestLocalizer).await;
+
+        let command = &commands[0];
+        assert_eq!(command.name_localizations["de"], "settings_de");
+        // The localizer returned nothing, so the static localization is kept
+        assert_eq!(command.name_localizations["fr"], "parametres");
+        assert_eq!(
+            command.description_localizations["de"],
+            "Change settings (settings)"
+        );
+
+        let subcommand = &command.subcommands[0];
+        assert_eq!(subcommand.name_localizations["de"], "language_de");
+        // Commands without description don't get a localized one
+        assert!(subcommand.description_localizations.is_empty());
+
+        let parameter = &subcommand.parameters[0];
+        assert_eq!(parameter.name_localizations["de"], "locale_de");
+        assert_eq!(
+            parameter.description_localizations["de"],
+            "Language to use (settings language)"
+        );
+        assert!(!parameter.name_localizations.contains_key("fr"));
+    }
+}